@@ -0,0 +1,40 @@
+/* C interface of the infinite-arrays crate, built with the `ffi` feature */
+
+#ifndef INFINITE_ARRAYS_H
+#define INFINITE_ARRAYS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An opaque handle to an infinite array of doubles; release with ia_free */
+typedef struct IaArray IaArray;
+
+typedef double (*IaElementFn)(size_t index, void *data);
+
+IaArray *ia_ones(void);
+IaArray *ia_zeros(void);
+IaArray *ia_fill(double value);
+IaArray *ia_range(double start, double step);
+IaArray *ia_from_fn(IaElementFn f, void *data);
+
+IaArray *ia_add(const IaArray *a, const IaArray *b);
+IaArray *ia_sub(const IaArray *a, const IaArray *b);
+IaArray *ia_mul(const IaArray *a, const IaArray *b);
+IaArray *ia_div(const IaArray *a, const IaArray *b);
+IaArray *ia_scale(const IaArray *a, double factor);
+IaArray *ia_cumsum(const IaArray *a);
+
+double ia_get(const IaArray *a, size_t index);
+int32_t ia_get_range(const IaArray *a, size_t start, size_t n, double *out);
+
+void ia_free(IaArray *a);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
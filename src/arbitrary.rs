@@ -0,0 +1,211 @@
+//! Random structured arrays for property-based tests
+//!
+//! The strategies here generate [`Fill`], [`PaddedArray`] and
+//! [`EventuallyPeriodic`] arrays, and [`array`] generates
+//! [`ArbitraryArray`] expressions that also include ranges and sums,
+//! products and multiples of other arrays. Elements come from a strategy
+//! chosen by the caller; keep it small (say `-100i64..100`) when composed
+//! integer arrays must not overflow.
+//!
+//! ```rust
+//! use infinite_arrays::arbitrary::array;
+//! use infinite_arrays::InfiniteArray;
+//! use proptest::test_runner::TestRunner;
+//!
+//! TestRunner::default()
+//!     .run(&array(-100i64..100), |arr| {
+//!         let mut out = [0; 8];
+//!         arr.get_range(5, &mut out);
+//!         assert!((0..8).all(|k| out[k] == arr.get(5 + k)));
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+
+use crate::arrays::{EventuallyPeriodic, Fill, Indexed, InfiniteArray, PaddedArray};
+use crate::describe::Description;
+use num_traits::FromPrimitive;
+use proptest::arbitrary::{any, Arbitrary};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::strategy::BoxedStrategy;
+use std::fmt::Debug;
+use std::ops::{Add, Mul};
+
+/// Longest stored prefix or period generated by the default strategies
+pub const MAX_STORED: usize = 8;
+
+/// A structured array or an arithmetic combination of them
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArbitraryArray<T> {
+    Fill(Fill<T>),
+    /// `start + step * i`
+    Range { start: T, step: T },
+    Padded(PaddedArray<T>),
+    Periodic(EventuallyPeriodic<T>),
+    Add(Box<ArbitraryArray<T>>, Box<ArbitraryArray<T>>),
+    Mul(Box<ArbitraryArray<T>>, Box<ArbitraryArray<T>>),
+    Scale(Box<ArbitraryArray<T>>, T),
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl<T> InfiniteArray<T> for ArbitraryArray<T>
+where
+    T: Copy + Add<Output = T> + Mul<Output = T> + FromPrimitive,
+{
+    fn get(&self, index: usize) -> T {
+        match self {
+            ArbitraryArray::Fill(fill) => fill.get(index),
+            ArbitraryArray::Range { start, step } => {
+                *start + *step * T::from_usize(index).expect("index representable in the element type")
+            }
+            ArbitraryArray::Padded(padded) => padded.get(index),
+            ArbitraryArray::Periodic(periodic) => periodic.get(index),
+            ArbitraryArray::Add(a, b) => a.get(index) + b.get(index),
+            ArbitraryArray::Mul(a, b) => a.get(index) * b.get(index),
+            ArbitraryArray::Scale(a, factor) => a.get(index) * *factor,
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        match self {
+            ArbitraryArray::Fill(fill) => fill.periodicity(),
+            ArbitraryArray::Range { .. } => None,
+            ArbitraryArray::Padded(padded) => padded.periodicity(),
+            ArbitraryArray::Periodic(periodic) => periodic.periodicity(),
+            ArbitraryArray::Add(a, b) | ArbitraryArray::Mul(a, b) => {
+                let ((sa, pa), (sb, pb)) = (a.periodicity()?, b.periodicity()?);
+                Some((sa.max(sb), pa / gcd(pa, pb) * pb))
+            }
+            ArbitraryArray::Scale(a, _) => a.periodicity(),
+        }
+    }
+
+    fn describe(&self) -> Description {
+        match self {
+            ArbitraryArray::Fill(fill) => InfiniteArray::<T>::describe(fill),
+            ArbitraryArray::Range { .. } => Description::leaf("range"),
+            ArbitraryArray::Padded(padded) => InfiniteArray::<T>::describe(padded),
+            ArbitraryArray::Periodic(periodic) => InfiniteArray::<T>::describe(periodic),
+            ArbitraryArray::Add(a, b) => Description::new("add", [a.describe(), b.describe()]),
+            ArbitraryArray::Mul(a, b) => Description::new("mul", [a.describe(), b.describe()]),
+            ArbitraryArray::Scale(a, _) => Description::new("mul_scalar", [a.describe()]),
+        }
+    }
+}
+
+/// Arrays repeating one value from `element`
+pub fn fill<T, S>(element: S) -> impl Strategy<Value = Fill<T>>
+where
+    T: Debug,
+    S: Strategy<Value = T>,
+{
+    element.prop_map(Fill::new)
+}
+
+/// Padded arrays storing up to `max_len` values from `element`
+pub fn padded<T, S>(element: S, max_len: usize) -> impl Strategy<Value = PaddedArray<T>>
+where
+    T: Debug,
+    S: Strategy<Value = T> + Clone,
+{
+    (vec(element.clone(), 0..=max_len), element).prop_map(|(data, tail)| PaddedArray::new(data, tail))
+}
+
+/// Eventually periodic arrays with a prefix of up to `max_prefix` values
+/// and a period of 1 to `max_period` values
+///
+/// # Panics
+///
+/// Panics if `max_period` is zero.
+pub fn periodic<T, S>(element: S, max_prefix: usize, max_period: usize) -> impl Strategy<Value = EventuallyPeriodic<T>>
+where
+    T: Debug,
+    S: Strategy<Value = T> + Clone,
+{
+    assert!(max_period > 0, "periods need at least one element");
+    (vec(element.clone(), 0..=max_prefix), vec(element, 1..=max_period))
+        .prop_map(|(prefix, period)| EventuallyPeriodic::new(prefix, period))
+}
+
+/// Structured arrays and sums, products and multiples of them, nested up to
+/// three levels deep
+pub fn array<T, S>(element: S) -> BoxedStrategy<ArbitraryArray<T>>
+where
+    T: Debug + Clone + 'static,
+    S: Strategy<Value = T> + Clone + 'static,
+{
+    let leaf = prop_oneof![
+        fill(element.clone()).prop_map(ArbitraryArray::Fill),
+        (element.clone(), element.clone()).prop_map(|(start, step)| ArbitraryArray::Range { start, step }),
+        padded(element.clone(), MAX_STORED).prop_map(ArbitraryArray::Padded),
+        periodic(element.clone(), MAX_STORED, MAX_STORED).prop_map(ArbitraryArray::Periodic),
+    ];
+    leaf.prop_recursive(3, 16, 2, move |inner| {
+        prop_oneof![
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| ArbitraryArray::Add(Box::new(a), Box::new(b))),
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| ArbitraryArray::Mul(Box::new(a), Box::new(b))),
+            (inner, element.clone()).prop_map(|(a, factor)| ArbitraryArray::Scale(Box::new(a), factor)),
+        ]
+    })
+    .boxed()
+}
+
+impl<T: Arbitrary + Clone + 'static> Arbitrary for Fill<T> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        fill(any::<T>()).boxed()
+    }
+}
+
+impl<T: Arbitrary + Clone + 'static> Arbitrary for PaddedArray<T> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        padded(any::<T>().boxed(), MAX_STORED).boxed()
+    }
+}
+
+impl<T: Arbitrary + Clone + 'static> Arbitrary for EventuallyPeriodic<T> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        periodic(any::<T>().boxed(), MAX_STORED, MAX_STORED).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_periodicity_holds(arr in array(-100i64..100)) {
+            if let Some((start, period)) = arr.periodicity() {
+                prop_assert!((start..start + 3 * period).all(|i| arr.get(i) == arr.get(i + period)));
+            }
+        }
+
+        #[test]
+        fn test_iter_matches_get(arr in any::<PaddedArray<i16>>()) {
+            let n = arr.data().len() + 3;
+            prop_assert!(arr.iter().take(n).enumerate().all(|(i, x)| x == arr.get(i)));
+            prop_assert_eq!(arr.get(n), *arr.tail());
+        }
+    }
+}
@@ -1,6 +1,9 @@
 //! Infinite array types
 
+use crate::describe::Description;
+use crate::error::Error;
 use num_traits::{One, Zero};
+use std::ops::Range;
 
 /// Trait for infinite arrays that can be indexed
 pub trait InfiniteArray<T> {
@@ -14,14 +17,154 @@ pub trait InfiniteArray<T> {
     fn len(&self) -> Option<usize> {
         None
     }
+
+    /// Whether the array is known to have no elements
+    fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// Length of a prefix outside of which every element is zero, if known
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        None
+    }
+
+    /// Length of a prefix in which every value of the array occurs, if known
+    ///
+    /// Questions about all elements, such as whether a predicate always
+    /// holds, can then be answered exactly by inspecting that prefix.
+    fn exhaustive_prefix(&self) -> Option<usize> {
+        self.periodicity().map(|(start, period)| start + period)
+    }
+
+    /// `(start, period)` such that `arr[i + period] == arr[i]` for all
+    /// `i >= start`, if the array is known to be eventually periodic
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// Write the elements `start..start + out.len()` into `out`
+    ///
+    /// Arrays that can fill a batch faster than element by element, such as
+    /// the vectorized combinators in [`simd`](crate::simd), override this.
+    fn get_range(&self, start: usize, out: &mut [T]) {
+        for (offset, slot) in out.iter_mut().enumerate() {
+            *slot = self.get(start + offset);
+        }
+    }
+
+    /// Element `index`, or why it cannot be read
+    ///
+    /// The default checks the index against [`len`](Self::len) and then
+    /// calls `get`; arrays whose elements can be missing or fail to load
+    /// override it.
+    fn try_get(&self, index: usize) -> Result<T, Error> {
+        match self.len() {
+            Some(len) if index >= len => Err(Error::OutOfBounds { index, len }),
+            _ => Ok(self.get(index)),
+        }
+    }
+
+    /// An iterator over the elements in `range`, stopping at the end of a
+    /// finite array, with an exact size
+    fn iter_range(&self, range: Range<usize>) -> Box<dyn ExactSizeIterator<Item = T> + '_> {
+        let end = self.len().map_or(range.end, |len| range.end.min(len));
+        Box::new((range.start.min(end)..end).map(move |i| self.get(i)))
+    }
+
+    /// What the array computes, as a tree of operations; arrays that are not
+    /// built from others are described by their type name
+    fn describe(&self) -> Description {
+        Description::of_type::<Self>()
+    }
 }
 
 /// Trait for infinite vectors (1D arrays)
 pub trait InfiniteVector<T>: InfiniteArray<T> {}
 
+/// Forward `InfiniteArray` through references and smart pointers, so that
+/// e.g. `Box<dyn InfiniteArray<T>>` can be used wherever an array is expected
+macro_rules! forward_infinite_array {
+    ($($pointer:ty),*) => {$(
+        impl<T, A> InfiniteArray<T> for $pointer
+        where
+            A: InfiniteArray<T> + ?Sized,
+        {
+            fn get(&self, index: usize) -> T {
+                (**self).get(index)
+            }
+
+            fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+                (**self).iter()
+            }
+
+            fn len(&self) -> Option<usize> {
+                (**self).len()
+            }
+
+            fn support(&self) -> Option<usize>
+            where
+                T: Zero + PartialEq,
+            {
+                (**self).support()
+            }
+
+            fn periodicity(&self) -> Option<(usize, usize)> {
+                (**self).periodicity()
+            }
+
+            fn get_range(&self, start: usize, out: &mut [T]) {
+                (**self).get_range(start, out)
+            }
+
+            fn try_get(&self, index: usize) -> Result<T, Error> {
+                (**self).try_get(index)
+            }
+
+            fn iter_range(&self, range: Range<usize>) -> Box<dyn ExactSizeIterator<Item = T> + '_> {
+                (**self).iter_range(range)
+            }
+
+            fn describe(&self) -> Description {
+                (**self).describe()
+            }
+        }
+
+        impl<T, A> InfiniteVector<T> for $pointer where A: InfiniteArray<T> + ?Sized {}
+    )*};
+}
+
+forward_infinite_array!(&A, Box<A>, std::rc::Rc<A>, std::sync::Arc<A>);
+
+/// `PartialEq`, `Eq` and `Hash` for a type that holds nothing but a
+/// `PhantomData<T>`, without the `T: Eq + Hash` bound the derives would add
+macro_rules! marker_eq_hash {
+    ($($ty:ident),*) => {$(
+        impl<T> PartialEq for $ty<T> {
+            fn eq(&self, _other: &Self) -> bool {
+                true
+            }
+        }
+
+        impl<T> Eq for $ty<T> {}
+
+        impl<T> std::hash::Hash for $ty<T> {
+            fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+        }
+    )*};
+}
+
+pub(crate) use marker_eq_hash;
+
+marker_eq_hash!(Ones, Zeros);
+
 /// An infinite array filled with ones
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ones<T> {
+    #[cfg_attr(feature = "serde", serde(skip))]
     _phantom: std::marker::PhantomData<T>,
 }
 
@@ -42,20 +185,71 @@ impl<T> Default for Ones<T> {
 
 impl<T> InfiniteArray<T> for Ones<T>
 where
-    T: One + Copy,
+    T: One + Clone,
 {
     fn get(&self, _index: usize) -> T {
         T::one()
     }
 
+    fn get_range(&self, _start: usize, out: &mut [T]) {
+        out.fill(T::one());
+    }
+
     fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
         Box::new(OnesIter {
             value: T::one(),
         })
     }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        Some((0, 1))
+    }
+}
+
+impl<T> InfiniteVector<T> for Ones<T> where T: One + Clone {}
+
+/// An endless iterator over `f(0), f(1), ...`, the usual
+/// [`InfiniteArray::iter`] of an array with random access
+///
+/// `nth`, and with it `skip` and `step_by`, jumps straight to the index
+/// instead of evaluating every element passed over.
+#[derive(Debug, Clone)]
+pub struct Indexed<F> {
+    f: F,
+    index: usize,
+}
+
+impl<F> Indexed<F> {
+    pub fn new(f: F) -> Self {
+        Self { f, index: 0 }
+    }
+
+    /// The index of the next element
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T, F: Fn(usize) -> T> Iterator for Indexed<F> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let value = (self.f)(self.index);
+        self.index = self.index.saturating_add(1);
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<T> {
+        self.index = self.index.saturating_add(n);
+        self.next()
+    }
 }
 
-impl<T> InfiniteVector<T> for Ones<T> where T: One + Copy {}
+impl<T, F: Fn(usize) -> T> std::iter::FusedIterator for Indexed<F> {}
 
 /// Iterator over Ones
 struct OnesIter<T> {
@@ -64,18 +258,30 @@ struct OnesIter<T> {
 
 impl<T> Iterator for OnesIter<T>
 where
-    T: Copy,
+    T: Clone,
 {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(self.value)
+        Some(self.value.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, _n: usize) -> Option<Self::Item> {
+        Some(self.value.clone())
     }
 }
 
+impl<T: Clone> std::iter::FusedIterator for OnesIter<T> {}
+
 /// An infinite array filled with zeros
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Zeros<T> {
+    #[cfg_attr(feature = "serde", serde(skip))]
     _phantom: std::marker::PhantomData<T>,
 }
 
@@ -96,20 +302,35 @@ impl<T> Default for Zeros<T> {
 
 impl<T> InfiniteArray<T> for Zeros<T>
 where
-    T: Zero + Copy,
+    T: Zero + Clone,
 {
     fn get(&self, _index: usize) -> T {
         T::zero()
     }
 
+    fn get_range(&self, _start: usize, out: &mut [T]) {
+        out.fill(T::zero());
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        Some(0)
+    }
+
     fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
         Box::new(ZerosIter {
             value: T::zero(),
         })
     }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        Some((0, 1))
+    }
 }
 
-impl<T> InfiniteVector<T> for Zeros<T> where T: Zero + Copy {}
+impl<T> InfiniteVector<T> for Zeros<T> where T: Zero + Clone {}
 
 /// Iterator over Zeros
 struct ZerosIter<T> {
@@ -118,19 +339,240 @@ struct ZerosIter<T> {
 
 impl<T> Iterator for ZerosIter<T>
 where
-    T: Copy,
+    T: Clone,
 {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(self.value)
+        Some(self.value.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, _n: usize) -> Option<Self::Item> {
+        Some(self.value.clone())
+    }
+}
+
+impl<T: Clone> std::iter::FusedIterator for ZerosIter<T> {}
+
+/// An infinite array repeating a single value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fill<T> {
+    value: T,
+}
+
+impl<T> Fill<T> {
+    pub fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    /// The repeated value
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> InfiniteArray<T> for Fill<T>
+where
+    T: Clone,
+{
+    fn get(&self, _index: usize) -> T {
+        self.value.clone()
+    }
+
+    fn get_range(&self, _start: usize, out: &mut [T]) {
+        out.fill(self.value.clone());
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(std::iter::repeat(self.value.clone()))
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        if self.value == T::zero() {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        Some((0, 1))
+    }
+}
+
+impl<T> InfiniteVector<T> for Fill<T> where T: Clone {}
+
+/// A finite vector followed by an infinitely repeated tail value
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PaddedArray<T> {
+    data: Vec<T>,
+    tail: T,
+}
+
+impl<T> PaddedArray<T> {
+    pub fn new(data: Vec<T>, tail: T) -> Self {
+        Self { data, tail }
+    }
+
+    /// The explicitly stored prefix
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    /// The value repeated after the stored prefix
+    pub fn tail(&self) -> &T {
+        &self.tail
+    }
+}
+
+impl<T> PaddedArray<T>
+where
+    T: Zero,
+{
+    /// Pad the given data with zeros
+    pub fn zero_padded(data: Vec<T>) -> Self {
+        Self::new(data, T::zero())
+    }
+}
+
+impl<T> InfiniteArray<T> for PaddedArray<T>
+where
+    T: Clone,
+{
+    fn get(&self, index: usize) -> T {
+        self.data.get(index).cloned().unwrap_or_else(|| self.tail.clone())
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(self.data.iter().cloned().chain(std::iter::repeat(self.tail.clone())))
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        Some((self.data.len(), 1))
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        if self.tail == T::zero() {
+            Some(self.data.len())
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> InfiniteVector<T> for PaddedArray<T> where T: Clone {}
+
+/// A finite prefix followed by a block repeated forever
+///
+/// Element `i` is `prefix[i]` for `i < prefix.len()` and
+/// `period[(i - prefix.len()) % period.len()]` afterwards.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "PeriodicParts<T>"))]
+pub struct EventuallyPeriodic<T> {
+    prefix: Vec<T>,
+    period: Vec<T>,
+}
+
+/// The serialized form of an [`EventuallyPeriodic`], checked on
+/// deserialization
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct PeriodicParts<T> {
+    prefix: Vec<T>,
+    period: Vec<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> TryFrom<PeriodicParts<T>> for EventuallyPeriodic<T> {
+    type Error = &'static str;
+
+    fn try_from(parts: PeriodicParts<T>) -> Result<Self, Self::Error> {
+        if parts.period.is_empty() {
+            return Err("EventuallyPeriodic requires a nonempty period");
+        }
+        Ok(Self {
+            prefix: parts.prefix,
+            period: parts.period,
+        })
+    }
+}
+
+impl<T> EventuallyPeriodic<T> {
+    /// # Panics
+    ///
+    /// Panics if `period` is empty.
+    pub fn new(prefix: Vec<T>, period: Vec<T>) -> Self {
+        assert!(!period.is_empty(), "EventuallyPeriodic requires a nonempty period");
+        Self { prefix, period }
+    }
+
+    /// A purely periodic array repeating `period`
+    pub fn cyclic(period: Vec<T>) -> Self {
+        Self::new(Vec::new(), period)
+    }
+
+    /// The elements before the periodic part starts
+    pub fn prefix(&self) -> &[T] {
+        &self.prefix
+    }
+
+    /// The repeated block
+    pub fn period(&self) -> &[T] {
+        &self.period
+    }
+}
+
+impl<T> InfiniteArray<T> for EventuallyPeriodic<T>
+where
+    T: Clone,
+{
+    fn get(&self, index: usize) -> T {
+        match self.prefix.get(index) {
+            Some(value) => value.clone(),
+            None => self.period[(index - self.prefix.len()) % self.period.len()].clone(),
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(self.prefix.iter().cloned().chain(self.period.iter().cloned().cycle()))
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        if self.period.iter().all(|x| *x == T::zero()) {
+            Some(self.prefix.len())
+        } else {
+            None
+        }
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        Some((self.prefix.len(), self.period.len()))
     }
 }
 
+impl<T> InfiniteVector<T> for EventuallyPeriodic<T> where T: Clone {}
+
 /// An infinite array from a function
 #[derive(Clone)]
 pub struct InfiniteArrayFromFn<F, T> {
     f: F,
+    description: Option<Description>,
     _phantom: std::marker::PhantomData<T>,
 }
 
@@ -141,9 +583,25 @@ where
     pub fn new(f: F) -> Self {
         Self {
             f,
+            description: None,
             _phantom: std::marker::PhantomData,
         }
     }
+
+    /// Record what the function computes, for [`InfiniteArray::describe`]
+    pub fn with_description(mut self, description: Description) -> Self {
+        self.description = Some(description);
+        self
+    }
+}
+
+impl<F, T> std::fmt::Debug for InfiniteArrayFromFn<F, T>
+where
+    F: Fn(usize) -> T,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InfiniteArrayFromFn({:?})", self.describe())
+    }
 }
 
 
@@ -156,20 +614,91 @@ where
     }
 
     fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
-        // We can't clone the function, so we create a new iterator that borrows self
-        Box::new((0..).map(move |i| (self.f)(i)))
+        Box::new(Indexed::new(&self.f))
+    }
+
+    fn describe(&self) -> Description {
+        self.description.clone().unwrap_or_else(|| Description::leaf("from_fn"))
     }
 }
 
 impl<F, T> InfiniteVector<T> for InfiniteArrayFromFn<F, T> where F: Fn(usize) -> T {}
 
+/// `for x in &arr` over the structured arrays, through [`InfiniteArray::iter`]
+macro_rules! into_iterator_by_ref {
+    ($($array:ident<$($param:ident),*> where [$($bound:tt)*]),* $(,)?) => {$(
+        impl<'a, $($param),*> IntoIterator for &'a $array<$($param),*>
+        where
+            $($bound)*
+        {
+            type Item = T;
+            type IntoIter = Box<dyn Iterator<Item = T> + 'a>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                InfiniteArray::iter(self)
+            }
+        }
+    )*};
+}
+
+into_iterator_by_ref! {
+    Ones<T> where [T: One + Clone],
+    Zeros<T> where [T: Zero + Clone],
+    Fill<T> where [T: Clone],
+    PaddedArray<T> where [T: Clone],
+    EventuallyPeriodic<T> where [T: Clone],
+    InfiniteArrayFromFn<F, T> where [F: Fn(usize) -> T],
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_indexed_skips_without_evaluating() {
+        let calls = std::cell::Cell::new(0);
+        let squares = InfiniteArrayFromFn::new(|i| {
+            calls.set(calls.get() + 1);
+            i * i
+        });
+        assert_eq!(squares.iter().skip(1000).step_by(10).take(2).collect::<Vec<_>>(), vec![1_000_000, 1_020_100]);
+        assert_eq!(calls.get(), 2);
+        assert_eq!(squares.iter().size_hint(), (usize::MAX, None));
+        assert_eq!(Ones::<u8>::new().iter().nth(usize::MAX), Some(1));
+    }
+
+    #[test]
+    fn test_iter_range_windows() {
+        let squares = InfiniteArrayFromFn::new(|i| i * i);
+        let window = squares.iter_range(3..6);
+        assert_eq!(window.len(), 3);
+        assert_eq!(window.collect::<Vec<_>>(), vec![9, 16, 25]);
+        let boxed: Box<dyn InfiniteArray<usize>> = Box::new(squares);
+        assert_eq!(boxed.iter_range(7..7).len(), 0);
+
+        let short = crate::streamed::IteratorArray::new(vec![1, 2, 3]);
+        assert_eq!(short.iter_range(1..10).collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_into_iterator_by_ref() {
+        let mut total = 0;
+        for x in &Fill::new(2) {
+            if total >= 6 {
+                break;
+            }
+            total += x;
+        }
+        assert_eq!(total, 6);
+        let squares = InfiniteArrayFromFn::new(|i| i * i);
+        assert_eq!((&squares).into_iter().take(4).collect::<Vec<_>>(), vec![0, 1, 4, 9]);
+        let padded = PaddedArray::new(vec![1, 2], 0);
+        assert!((&padded).into_iter().zip(&Ones::<i32>::new()).take(3).eq([(1, 1), (2, 1), (0, 1)]));
+    }
+
     #[test]
     fn test_ones() {
-        let ones = Ones::new();
+        let ones: Ones<f64> = Ones::new();
         assert_eq!(ones.get(0), 1.0);
         assert_eq!(ones.get(100), 1.0);
         
@@ -181,11 +710,57 @@ mod tests {
 
     #[test]
     fn test_zeros() {
-        let zeros = Zeros::new();
+        let zeros: Zeros<f64> = Zeros::new();
         assert_eq!(zeros.get(0), 0.0);
         assert_eq!(zeros.get(100), 0.0);
     }
 
+    #[test]
+    fn test_fill() {
+        let fill = Fill::new(2.5);
+        assert_eq!(fill.get(0), 2.5);
+        assert_eq!(fill.get(1000), 2.5);
+        assert_eq!(Fill::new(0.0).support(), Some(0));
+    }
+
+    #[test]
+    fn test_eventually_periodic() {
+        let arr = EventuallyPeriodic::new(vec![9, 8], vec![1, 2, 3]);
+        let prefix: Vec<i32> = arr.iter().take(8).collect();
+        assert_eq!(prefix, vec![9, 8, 1, 2, 3, 1, 2, 3]);
+        assert_eq!(arr.get(1003), 3);
+        assert_eq!(arr.periodicity(), Some((2, 3)));
+        assert_eq!(arr.exhaustive_prefix(), Some(5));
+    }
+
+    #[test]
+    fn test_padded_array() {
+        let padded = PaddedArray::new(vec![1, 2, 3], 7);
+        assert_eq!(padded.get(0), 1);
+        assert_eq!(padded.get(2), 3);
+        assert_eq!(padded.get(3), 7);
+        assert_eq!(padded.get(100), 7);
+        assert_eq!(padded.support(), None);
+        assert_eq!(PaddedArray::zero_padded(vec![1, 2]).support(), Some(2));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let padded = PaddedArray::new(vec![1, 2], 0);
+        let json = serde_json::to_string(&padded).unwrap();
+        assert_eq!(serde_json::from_str::<PaddedArray<i32>>(&json).unwrap(), padded);
+
+        let ones: Ones<f64> = serde_json::from_str("{}").unwrap();
+        assert_eq!(ones.get(3), 1.0);
+        assert_eq!(serde_json::to_string(&Fill::new(2.5)).unwrap(), r#"{"value":2.5}"#);
+
+        let periodic = EventuallyPeriodic::new(vec![1], vec![2, 3]);
+        let json = serde_json::to_string(&periodic).unwrap();
+        assert_eq!(serde_json::from_str::<EventuallyPeriodic<i32>>(&json).unwrap(), periodic);
+        assert!(serde_json::from_str::<EventuallyPeriodic<i32>>(r#"{"prefix":[],"period":[]}"#).is_err());
+    }
+
     #[test]
     fn test_infinite_array_from_fn() {
         let arr = InfiniteArrayFromFn::new(|i| i * 2);
@@ -193,5 +768,27 @@ mod tests {
         assert_eq!(arr.get(1), 2);
         assert_eq!(arr.get(5), 10);
     }
+
+    #[test]
+    fn test_structural_equality_and_hash() {
+        use crate::kron::kron;
+        use crate::matrix::Eye;
+        use crate::ranges::{InfStepRange, OneToInf};
+        use std::collections::{HashMap, HashSet};
+
+        let fills: HashSet<Fill<i32>> = [Fill::new(1), Fill::new(2), Fill::new(1)].into_iter().collect();
+        assert_eq!(fills.len(), 2);
+        let periodic = EventuallyPeriodic::new(vec![0], vec![1, 2]);
+        assert_eq!(periodic, EventuallyPeriodic::new(vec![0], vec![1, 2]));
+        let mut sums = HashMap::new();
+        sums.insert(kron(periodic.clone(), PaddedArray::zero_padded(vec![1, 2])), 6);
+        assert_eq!(sums.get(&kron(periodic, PaddedArray::zero_padded(vec![1, 2]))), Some(&6));
+        assert_ne!(InfStepRange::new(0, 2), InfStepRange::new(0, 3));
+
+        type Markers = (Ones<f64>, Zeros<f64>, OneToInf<f64>, Eye<f64>);
+        let markers: HashSet<Markers> = [Default::default(), Default::default()].into_iter().collect();
+        assert_eq!(markers.len(), 1);
+        assert_eq!(Ones::<f64>::new(), Ones::new());
+    }
 }
 
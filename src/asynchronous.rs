@@ -0,0 +1,171 @@
+//! Infinite arrays whose elements are computed asynchronously
+//!
+//! [`AsyncInfiniteArray`] is the async counterpart of [`InfiniteArray`], for
+//! elements sourced from network or database calls. The trait does not tie
+//! itself to an executor; the adapters here only build futures.
+
+use crate::arrays::InfiniteArray;
+use crate::instrument::trace_event;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::{self, Future};
+
+#[cfg(feature = "futures")]
+mod stream;
+
+#[cfg(feature = "futures")]
+pub use stream::{ArrayStream, ChunkedStream, IntoStream};
+
+/// An infinite array whose elements are produced by futures
+///
+/// Implementations may write `async fn get`.
+pub trait AsyncInfiniteArray<T> {
+    /// Get the value at the given index
+    fn get(&self, index: usize) -> impl Future<Output = T>;
+
+    /// Get the length (infinity for infinite arrays)
+    fn len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether the array is known to have no elements
+    fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
+    /// The first `n` elements, awaited one after another
+    fn prefix(&self, n: usize) -> impl Future<Output = Vec<T>> {
+        async move {
+            let mut values = Vec::with_capacity(n);
+            for i in 0..n {
+                values.push(self.get(i).await);
+            }
+            values
+        }
+    }
+}
+
+/// A synchronous array used where an [`AsyncInfiniteArray`] is expected,
+/// with every element ready at once
+#[derive(Debug, Clone)]
+pub struct AsyncArray<A> {
+    inner: A,
+}
+
+impl<A> AsyncArray<A> {
+    pub fn new(inner: A) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+}
+
+impl<T, A: InfiniteArray<T>> AsyncInfiniteArray<T> for AsyncArray<A> {
+    fn get(&self, index: usize) -> impl Future<Output = T> {
+        future::ready(self.inner.get(index))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.inner.len()
+    }
+}
+
+/// An async array whose element `i` is the output of the future `f(i)`
+pub struct AsyncFromFn<F> {
+    f: F,
+}
+
+impl<F> AsyncFromFn<F> {
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+impl<T, F, Fut> AsyncInfiniteArray<T> for AsyncFromFn<F>
+where
+    F: Fn(usize) -> Fut,
+    Fut: Future<Output = T>,
+{
+    fn get(&self, index: usize) -> impl Future<Output = T> {
+        (self.f)(index)
+    }
+}
+
+/// An async array that awaits each element of its base at most once
+///
+/// Concurrent reads of an element that is not cached yet may each await
+/// the base; the first to finish is kept.
+pub struct AsyncCached<T, A> {
+    base: A,
+    cache: RefCell<HashMap<usize, T>>,
+}
+
+impl<T, A> AsyncCached<T, A> {
+    pub fn new(base: A) -> Self {
+        Self {
+            base,
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Number of cached elements
+    pub fn cache_size(&self) -> usize {
+        self.cache.borrow().len()
+    }
+}
+
+impl<T, A> AsyncInfiniteArray<T> for AsyncCached<T, A>
+where
+    T: Clone,
+    A: AsyncInfiniteArray<T>,
+{
+    async fn get(&self, index: usize) -> T {
+        if let Some(value) = self.cache.borrow().get(&index) {
+            return value.clone();
+        }
+        trace_event!(index, "cache miss");
+        let value = self.base.get(index).await;
+        self.cache.borrow_mut().entry(index).or_insert(value).clone()
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.base.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+    use futures::executor::block_on;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_async_array_from_sync() {
+        let squares = AsyncArray::new(InfiniteArrayFromFn::new(|i| i * i));
+        assert_eq!(block_on(squares.get(12)), 144);
+        assert_eq!(block_on(squares.prefix(4)), vec![0, 1, 4, 9]);
+    }
+
+    #[test]
+    fn test_async_cached_awaits_once() {
+        let calls = Cell::new(0);
+        let lookups = AsyncFromFn::new(|i: usize| {
+            calls.set(calls.get() + 1);
+            async move {
+                futures::future::ready(()).await;
+                i as f64 / 2.0
+            }
+        });
+        let cached = AsyncCached::new(lookups);
+        block_on(async {
+            assert_eq!(cached.get(3).await, 1.5);
+            assert_eq!(cached.get(3).await, 1.5);
+            assert_eq!(cached.prefix(5).await.len(), 5);
+        });
+        assert_eq!(calls.get(), 5);
+        assert_eq!(cached.cache_size(), 5);
+    }
+}
@@ -0,0 +1,169 @@
+//! [`Stream`] adapters over infinite arrays
+
+use crate::arrays::InfiniteArray;
+use crate::error::Error;
+use futures::Stream;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Conversion of an array into a [`Stream`] of its elements
+///
+/// Elements are computed only when the consumer polls for them, so a slow
+/// consumer holds the producer back. The streams are always ready; consume
+/// them with a combinator that yields, such as `take` or `zip` with a
+/// pending stream, to avoid starving other tasks.
+pub trait IntoStream<T>: InfiniteArray<T> + Sized {
+    /// A stream of the elements in order, ending with a finite array
+    ///
+    /// An infinite array's stream ends after the element at index
+    /// `usize::MAX`.
+    fn into_stream(self) -> ArrayStream<T, Self> {
+        ArrayStream {
+            array: self,
+            index: 0,
+            done: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// A stream of consecutive chunks of `size` elements; the last chunk of
+    /// a finite array may be shorter
+    ///
+    /// A chunk that would reach past index `usize::MAX` is an
+    /// [`Error::OutOfBounds`], which ends the stream.
+    fn into_chunked_stream(self, size: usize) -> ChunkedStream<T, Self> {
+        assert!(size > 0, "chunk size must be positive");
+        ChunkedStream {
+            array: self,
+            index: 0,
+            size,
+            done: false,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, A: InfiniteArray<T>> IntoStream<T> for A {}
+
+/// Stream of the elements of an array, made by [`IntoStream::into_stream`]
+pub struct ArrayStream<T, A> {
+    array: A,
+    index: usize,
+    done: bool,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T, A> ArrayStream<T, A> {
+    /// Index of the next element
+    pub fn position(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T, A: InfiniteArray<T> + Unpin> Stream for ArrayStream<T, A> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        if this.done || this.array.len().is_some_and(|len| this.index >= len) {
+            return Poll::Ready(None);
+        }
+        let value = this.array.get(this.index);
+        match this.index.checked_add(1) {
+            Some(next) => this.index = next,
+            None => this.done = true,
+        }
+        Poll::Ready(Some(value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        match self.array.len() {
+            Some(len) => (len - self.index, Some(len - self.index)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+/// Stream of chunks of an array, made by [`IntoStream::into_chunked_stream`]
+pub struct ChunkedStream<T, A> {
+    array: A,
+    index: usize,
+    size: usize,
+    done: bool,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T, A: InfiniteArray<T> + Unpin> Stream for ChunkedStream<T, A> {
+    type Item = Result<Vec<T>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        let end = match (this.array.len(), this.index.checked_add(this.size)) {
+            (Some(len), _) if this.index >= len => return Poll::Ready(None),
+            (Some(len), end) => end.map_or(len, |end| len.min(end)),
+            (None, Some(end)) => end,
+            (None, None) => {
+                this.done = true;
+                return Poll::Ready(Some(Err(Error::OutOfBounds {
+                    index: usize::MAX,
+                    len: usize::MAX,
+                })));
+            }
+        };
+        let chunk = (this.index..end).map(|i| this.array.get(i)).collect();
+        this.index = end;
+        Poll::Ready(Some(Ok(chunk)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{InfiniteArrayFromFn, Ones};
+    use futures::executor::block_on;
+    use futures::StreamExt;
+
+    #[test]
+    fn test_into_stream() {
+        let stream = InfiniteArrayFromFn::new(|i| i * i).into_stream();
+        let evens: Vec<usize> = block_on(stream.filter(|x| futures::future::ready(x % 2 == 0)).take(3).collect());
+        assert_eq!(evens, vec![0, 4, 16]);
+    }
+
+    #[test]
+    fn test_stream_ends_at_the_last_index() {
+        let mut stream = InfiniteArrayFromFn::new(|i| i).into_stream();
+        stream.index = usize::MAX - 1;
+        assert_eq!(block_on(stream.next()), Some(usize::MAX - 1));
+        assert_eq!(block_on(stream.next()), Some(usize::MAX));
+        assert_eq!(block_on(stream.next()), None);
+        assert_eq!(stream.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn test_chunked_stream() {
+        let mut chunks = Ones::<f64>::new().into_chunked_stream(4);
+        assert_eq!(block_on(chunks.next()).unwrap().unwrap(), vec![1.0; 4]);
+
+        let sums: Vec<usize> = block_on(
+            InfiniteArrayFromFn::new(|i| i).into_chunked_stream(3).map(|chunk| chunk.unwrap().iter().sum::<usize>()).take(3).collect(),
+        );
+        assert_eq!(sums, vec![3, 12, 21]);
+    }
+
+    #[test]
+    fn test_chunked_stream_ends_at_the_last_index() {
+        let mut chunks = Ones::<u8>::new().into_chunked_stream(4);
+        chunks.index = usize::MAX - 5;
+        assert_eq!(block_on(chunks.next()).unwrap().unwrap(), vec![1; 4]);
+        assert!(matches!(block_on(chunks.next()), Some(Err(Error::OutOfBounds { .. }))));
+        assert!(block_on(chunks.next()).is_none());
+    }
+}
@@ -0,0 +1,216 @@
+//! One-dimensional cellular automata with lazily computed generations
+//!
+//! Cells live on the integer line. Generation zero is a finite seed on a
+//! constant background, so every later generation is again a finite window
+//! of cells on a constant background: the window grows by one cell on each
+//! side per generation, and the background evolves by applying the rule to
+//! three background cells.
+
+use crate::arrays::{Indexed, InfiniteArray, InfiniteVector};
+use std::cell::RefCell;
+
+/// Rule 30, Wolfram's chaotic rule
+pub const RULE_30: u8 = 30;
+
+/// Rule 90, which draws a Sierpiński triangle from a single cell
+pub const RULE_90: u8 = 90;
+
+/// Rule 110, which is Turing complete
+pub const RULE_110: u8 = 110;
+
+/// The cells of one generation that may differ from the background
+#[derive(Debug, Clone)]
+struct Row {
+    /// Position of `cells[0]` on the line
+    start: isize,
+    cells: Vec<bool>,
+    background: bool,
+}
+
+impl Row {
+    fn at(&self, x: isize) -> bool {
+        x.checked_sub(self.start)
+            .and_then(|offset| usize::try_from(offset).ok())
+            .and_then(|offset| self.cells.get(offset).copied())
+            .unwrap_or(self.background)
+    }
+}
+
+/// An elementary cellular automaton started from a finite seed
+///
+/// Generations are computed on demand from the previous one and kept, so
+/// reading generation `g` costs `O(g (seed + g))` the first time and a
+/// lookup afterwards.
+#[derive(Debug, Clone)]
+pub struct CellularAutomaton {
+    rule: u8,
+    generations: RefCell<Vec<Row>>,
+}
+
+impl CellularAutomaton {
+    /// An automaton with Wolfram rule number `rule`, whose generation zero
+    /// holds `seed` at positions `0..seed.len()` and `false` elsewhere
+    pub fn new(rule: u8, seed: Vec<bool>) -> Self {
+        Self::with_background(rule, seed, false)
+    }
+
+    /// Like [`new`](Self::new), with every cell outside the seed set to
+    /// `background`
+    pub fn with_background(rule: u8, seed: Vec<bool>, background: bool) -> Self {
+        Self {
+            rule,
+            generations: RefCell::new(vec![Row {
+                start: 0,
+                cells: seed,
+                background,
+            }]),
+        }
+    }
+
+    /// The Wolfram rule number
+    pub fn rule(&self) -> u8 {
+        self.rule
+    }
+
+    /// The rule applied to a neighbourhood
+    pub fn step(&self, left: bool, centre: bool, right: bool) -> bool {
+        let pattern = (left as u8) << 2 | (centre as u8) << 1 | right as u8;
+        self.rule >> pattern & 1 == 1
+    }
+
+    fn ensure(&self, generation: usize) {
+        let mut generations = self.generations.borrow_mut();
+        while generations.len() <= generation {
+            let previous = &generations[generations.len() - 1];
+            let start = previous.start - 1;
+            let cells = (0..previous.cells.len() + 2)
+                .map(|offset| {
+                    let x = start + offset as isize;
+                    self.step(previous.at(x - 1), previous.at(x), previous.at(x + 1))
+                })
+                .collect();
+            let b = previous.background;
+            let row = Row {
+                start,
+                cells,
+                background: self.step(b, b, b),
+            };
+            generations.push(row);
+        }
+    }
+
+    /// The state of the cell at position `x` in generation `generation`
+    pub fn cell(&self, generation: usize, x: isize) -> bool {
+        self.ensure(generation);
+        self.generations.borrow()[generation].at(x)
+    }
+
+    /// Generation `generation` as an infinite array over positions `0, 1, …`
+    pub fn generation(&self, generation: usize) -> Generation<'_> {
+        self.ensure(generation);
+        Generation {
+            automaton: self,
+            generation,
+        }
+    }
+
+    /// Number of generations computed so far
+    pub fn computed(&self) -> usize {
+        self.generations.borrow().len()
+    }
+}
+
+/// One generation of a [`CellularAutomaton`], see
+/// [`CellularAutomaton::generation`]
+///
+/// As an [`InfiniteArray`] it covers positions `0, 1, …`; use
+/// [`at`](Self::at) for negative positions. Beyond the cells reached from
+/// the seed it is constant, which is reported through
+/// [`periodicity`](InfiniteArray::periodicity).
+#[derive(Debug, Clone, Copy)]
+pub struct Generation<'a> {
+    automaton: &'a CellularAutomaton,
+    generation: usize,
+}
+
+impl Generation<'_> {
+    /// The cell at position `x`, which may be negative
+    pub fn at(&self, x: isize) -> bool {
+        self.automaton.cell(self.generation, x)
+    }
+
+    /// The constant value of every cell far enough from the seed
+    pub fn background(&self) -> bool {
+        self.automaton.generations.borrow()[self.generation].background
+    }
+
+    /// Positions `start..end` of the cells that may differ from the
+    /// background
+    pub fn window(&self) -> std::ops::Range<isize> {
+        let row = &self.automaton.generations.borrow()[self.generation];
+        row.start..row.start + row.cells.len() as isize
+    }
+}
+
+impl InfiniteArray<bool> for Generation<'_> {
+    fn get(&self, index: usize) -> bool {
+        self.at(index as isize)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = bool> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        Some((self.window().end.max(0) as usize, 1))
+    }
+}
+
+impl InfiniteVector<bool> for Generation<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(automaton: &CellularAutomaton, generation: usize, positions: std::ops::Range<isize>) -> String {
+        positions
+            .map(|x| if automaton.cell(generation, x) { '#' } else { '.' })
+            .collect()
+    }
+
+    #[test]
+    fn test_rule_90_sierpinski() {
+        let automaton = CellularAutomaton::new(RULE_90, vec![true]);
+        assert_eq!(render(&automaton, 0, -3..4), "...#...");
+        assert_eq!(render(&automaton, 1, -3..4), "..#.#..");
+        assert_eq!(render(&automaton, 2, -3..4), ".#...#.");
+        assert_eq!(render(&automaton, 3, -3..4), "#.#.#.#");
+        assert_eq!(automaton.computed(), 4);
+    }
+
+    #[test]
+    fn test_rule_110() {
+        // Rule 110 grows to the left from a single cell
+        let automaton = CellularAutomaton::new(RULE_110, vec![true]);
+        assert_eq!(render(&automaton, 1, -2..1), ".##");
+        assert_eq!(render(&automaton, 2, -2..1), "###");
+        assert_eq!(render(&automaton, 3, -3..1), "##.#");
+
+        let row = automaton.generation(3);
+        assert!(row.get(0));
+        assert!(!row.get(1000));
+        assert_eq!(row.window(), -3..4);
+        assert_eq!(row.periodicity(), Some((4, 1)));
+    }
+
+    #[test]
+    fn test_flipping_background() {
+        // Rule 1 maps 000 to 1 and everything else to 0
+        let automaton = CellularAutomaton::new(1, vec![true]);
+        let row = automaton.generation(1);
+        assert!(row.background());
+        assert!(!row.at(0) && !row.at(-1) && !row.at(1));
+        assert!(row.at(5));
+        assert!(!automaton.generation(2).background());
+    }
+}
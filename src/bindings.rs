@@ -0,0 +1,68 @@
+//! Bindings for other languages
+//!
+//! Each binding wraps the same dynamically typed arrays of `f64`, built by
+//! the helpers here, so the operations offered stay in step.
+
+use crate::arrays::{Fill, InfiniteArray, InfiniteArrayFromFn, Ones, Zeros};
+use crate::operations::{add_arrays, cumsum, div_arrays, mul_arrays, mul_scalar, sub_arrays};
+use std::sync::Arc;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
+
+/// An array shared between handles in another language
+pub(crate) type SharedArray = Arc<dyn InfiniteArray<f64> + Send + Sync>;
+
+/// The element-wise operations offered to other languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+pub(crate) fn ones() -> SharedArray {
+    Arc::new(Ones::new())
+}
+
+pub(crate) fn zeros() -> SharedArray {
+    Arc::new(Zeros::new())
+}
+
+pub(crate) fn fill(value: f64) -> SharedArray {
+    Arc::new(Fill::new(value))
+}
+
+/// `start, start + step, start + 2 * step, ...`
+pub(crate) fn range(start: f64, step: f64) -> SharedArray {
+    Arc::new(InfiniteArrayFromFn::new(move |i| start + step * i as f64))
+}
+
+pub(crate) fn binary(op: BinaryOp, a: &SharedArray, b: &SharedArray) -> SharedArray {
+    let (a, b) = (a.clone(), b.clone());
+    match op {
+        BinaryOp::Add => Arc::new(add_arrays(a, b)),
+        BinaryOp::Sub => Arc::new(sub_arrays(a, b)),
+        BinaryOp::Mul => Arc::new(mul_arrays(a, b)),
+        BinaryOp::Div => Arc::new(div_arrays(a, b)),
+    }
+}
+
+pub(crate) fn scale(a: &SharedArray, factor: f64) -> SharedArray {
+    Arc::new(mul_scalar(a.clone(), factor))
+}
+
+pub(crate) fn cumulative(a: &SharedArray) -> SharedArray {
+    Arc::new(cumsum(a.clone()))
+}
+
+/// The first `n` elements
+#[cfg(any(feature = "python", feature = "wasm-bindgen"))]
+pub(crate) fn prefix(a: &SharedArray, n: usize) -> Vec<f64> {
+    a.iter().take(n).collect()
+}
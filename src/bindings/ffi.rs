@@ -0,0 +1,228 @@
+//! C bindings through opaque handles
+//!
+//! Every constructor returns a new `IaArray *` that the caller releases with
+//! `ia_free`. Combining arrays does not consume the operands, so each handle
+//! is freed exactly once whatever was built from it. Functions taking a
+//! handle return null (or NaN for `ia_get`) when given null. The matching
+//! declarations are in `include/infinite_arrays.h`.
+
+use super::{BinaryOp, SharedArray};
+use crate::arrays::InfiniteArrayFromFn;
+use std::ffi::c_void;
+use std::sync::Arc;
+
+/// An opaque handle to an array of `double`
+pub struct IaArray {
+    inner: SharedArray,
+}
+
+/// A C callback computing element `index` from user data
+pub type IaElementFn = extern "C" fn(index: usize, data: *mut c_void) -> f64;
+
+/// The user data of a callback array, which C code promises is usable from
+/// any thread
+struct UserData(*mut c_void);
+
+unsafe impl Send for UserData {}
+unsafe impl Sync for UserData {}
+
+impl UserData {
+    /// The pointer, read through a method so closures capture the whole
+    /// wrapper rather than the bare pointer
+    fn ptr(&self) -> *mut c_void {
+        self.0
+    }
+}
+
+fn handle(inner: SharedArray) -> *mut IaArray {
+    Box::into_raw(Box::new(IaArray { inner }))
+}
+
+/// # Safety
+///
+/// `array` is null or a live handle.
+unsafe fn inner<'a>(array: *const IaArray) -> Option<&'a SharedArray> {
+    array.as_ref().map(|array| &array.inner)
+}
+
+/// # Safety
+///
+/// `a` and `b` are null or live handles.
+unsafe fn combine(op: BinaryOp, a: *const IaArray, b: *const IaArray) -> *mut IaArray {
+    match (inner(a), inner(b)) {
+        (Some(a), Some(b)) => handle(super::binary(op, a, b)),
+        _ => std::ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn ia_ones() -> *mut IaArray {
+    handle(super::ones())
+}
+
+#[no_mangle]
+pub extern "C" fn ia_zeros() -> *mut IaArray {
+    handle(super::zeros())
+}
+
+#[no_mangle]
+pub extern "C" fn ia_fill(value: f64) -> *mut IaArray {
+    handle(super::fill(value))
+}
+
+/// `start, start + step, start + 2 * step, ...`
+#[no_mangle]
+pub extern "C" fn ia_range(start: f64, step: f64) -> *mut IaArray {
+    handle(super::range(start, step))
+}
+
+/// Element `i` is `f(i, data)`
+///
+/// # Safety
+///
+/// `f` must be callable with `data` from any thread for as long as the
+/// handle, or any array built from it, is alive.
+#[no_mangle]
+pub unsafe extern "C" fn ia_from_fn(f: IaElementFn, data: *mut c_void) -> *mut IaArray {
+    let data = UserData(data);
+    handle(Arc::new(InfiniteArrayFromFn::new(move |i| f(i, data.ptr()))))
+}
+
+/// # Safety
+///
+/// `a` and `b` are null or live handles.
+#[no_mangle]
+pub unsafe extern "C" fn ia_add(a: *const IaArray, b: *const IaArray) -> *mut IaArray {
+    combine(BinaryOp::Add, a, b)
+}
+
+/// # Safety
+///
+/// `a` and `b` are null or live handles.
+#[no_mangle]
+pub unsafe extern "C" fn ia_sub(a: *const IaArray, b: *const IaArray) -> *mut IaArray {
+    combine(BinaryOp::Sub, a, b)
+}
+
+/// # Safety
+///
+/// `a` and `b` are null or live handles.
+#[no_mangle]
+pub unsafe extern "C" fn ia_mul(a: *const IaArray, b: *const IaArray) -> *mut IaArray {
+    combine(BinaryOp::Mul, a, b)
+}
+
+/// # Safety
+///
+/// `a` and `b` are null or live handles.
+#[no_mangle]
+pub unsafe extern "C" fn ia_div(a: *const IaArray, b: *const IaArray) -> *mut IaArray {
+    combine(BinaryOp::Div, a, b)
+}
+
+/// Every element multiplied by `factor`
+///
+/// # Safety
+///
+/// `a` is null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn ia_scale(a: *const IaArray, factor: f64) -> *mut IaArray {
+    inner(a).map_or(std::ptr::null_mut(), |a| handle(super::scale(a, factor)))
+}
+
+/// Cumulative sums
+///
+/// # Safety
+///
+/// `a` is null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn ia_cumsum(a: *const IaArray) -> *mut IaArray {
+    inner(a).map_or(std::ptr::null_mut(), |a| handle(super::cumulative(a)))
+}
+
+/// Element `index`, or NaN for a null handle
+///
+/// # Safety
+///
+/// `a` is null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn ia_get(a: *const IaArray, index: usize) -> f64 {
+    inner(a).map_or(f64::NAN, |a| a.get(index))
+}
+
+/// Write elements `start..start + n` to `out`; returns 0 on success and -1
+/// for a null argument
+///
+/// # Safety
+///
+/// `a` is null or a live handle, and `out` is null or valid for writing
+/// `n` doubles.
+#[no_mangle]
+pub unsafe extern "C" fn ia_get_range(a: *const IaArray, start: usize, n: usize, out: *mut f64) -> i32 {
+    match inner(a) {
+        Some(a) if !out.is_null() => {
+            a.get_range(start, std::slice::from_raw_parts_mut(out, n));
+            0
+        }
+        _ => -1,
+    }
+}
+
+/// Release a handle; null is ignored
+///
+/// # Safety
+///
+/// `a` is null or a live handle, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ia_free(a: *mut IaArray) {
+    if !a.is_null() {
+        drop(Box::from_raw(a));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" fn powers(index: usize, data: *mut c_void) -> f64 {
+        let base = unsafe { *(data as *const f64) };
+        base.powi(index as i32)
+    }
+
+    #[test]
+    fn test_ffi_handles() {
+        unsafe {
+            let x = ia_range(1.0, 1.0);
+            let ones = ia_ones();
+            let sum = ia_add(x, ones);
+            ia_free(x);
+            ia_free(ones);
+            let one = ia_fill(1.0);
+            let reciprocal = ia_div(one, sum);
+            let harmonic = ia_cumsum(reciprocal);
+            for handle in [one, sum, reciprocal] {
+                ia_free(handle);
+            }
+            let mut out = [0.0; 3];
+            assert_eq!(ia_get_range(harmonic, 0, 3, out.as_mut_ptr()), 0);
+            assert_eq!(out, [0.5, 0.5 + 1.0 / 3.0, 0.5 + 1.0 / 3.0 + 0.25]);
+            ia_free(harmonic);
+        }
+    }
+
+    #[test]
+    fn test_ffi_callbacks_and_null() {
+        let mut base = 2.0f64;
+        unsafe {
+            let pow = ia_from_fn(powers, &mut base as *mut f64 as *mut c_void);
+            let scaled = ia_scale(pow, 0.5);
+            assert_eq!(ia_get(scaled, 10), 512.0);
+            ia_free(pow);
+            ia_free(scaled);
+            assert!(ia_get(std::ptr::null(), 0).is_nan());
+            assert!(ia_add(std::ptr::null(), std::ptr::null()).is_null());
+            assert_eq!(ia_get_range(std::ptr::null(), 0, 1, std::ptr::null_mut()), -1);
+            ia_free(std::ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Python bindings through `pyo3`
+//!
+//! The `infinite_arrays` module exposes an `InfiniteArray` class that is
+//! indexed and sliced like a sequence, combined with the usual arithmetic
+//! operators, and converted to NumPy with `to_numpy(n)`. Build it as an
+//! extension module with maturin, using a `cdylib` crate type.
+
+use super::{BinaryOp, SharedArray};
+use numpy::PyArray1;
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PySlice;
+
+/// A lazily defined infinite sequence of floats
+#[pyclass(name = "InfiniteArray", module = "infinite_arrays", frozen)]
+pub struct PyInfiniteArray {
+    inner: SharedArray,
+}
+
+impl PyInfiniteArray {
+    fn wrap(inner: SharedArray) -> Self {
+        Self { inner }
+    }
+}
+
+/// An index or a slice with a stop
+#[derive(FromPyObject)]
+enum Key<'py> {
+    Index(isize),
+    Slice(Bound<'py, PySlice>),
+}
+
+/// Another array or a number broadcast to every index
+#[derive(FromPyObject)]
+enum Operand<'py> {
+    Array(PyRef<'py, PyInfiniteArray>),
+    Scalar(f64),
+}
+
+impl Operand<'_> {
+    fn array(&self) -> SharedArray {
+        match self {
+            Operand::Array(array) => array.inner.clone(),
+            Operand::Scalar(value) => super::fill(*value),
+        }
+    }
+}
+
+fn slice_bound(slice: &Bound<'_, PySlice>, name: &str) -> PyResult<Option<isize>> {
+    slice.getattr(name)?.extract()
+}
+
+#[pymethods]
+impl PyInfiniteArray {
+    #[staticmethod]
+    fn ones() -> Self {
+        Self::wrap(super::ones())
+    }
+
+    #[staticmethod]
+    fn zeros() -> Self {
+        Self::wrap(super::zeros())
+    }
+
+    #[staticmethod]
+    fn fill(value: f64) -> Self {
+        Self::wrap(super::fill(value))
+    }
+
+    /// `start, start + step, start + 2 * step, ...`
+    #[staticmethod]
+    #[pyo3(signature = (start = 0.0, step = 1.0))]
+    fn range(start: f64, step: f64) -> Self {
+        Self::wrap(super::range(start, step))
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, key: Key<'py>) -> PyResult<Bound<'py, PyAny>> {
+        match key {
+            Key::Index(index) => {
+                let index = usize::try_from(index).map_err(|_| PyIndexError::new_err("infinite arrays have no end to count back from"))?;
+                Ok(self.inner.get(index).into_pyobject(py)?.into_any())
+            }
+            Key::Slice(slice) => {
+                let start = slice_bound(&slice, "start")?.unwrap_or(0);
+                let step = slice_bound(&slice, "step")?.unwrap_or(1);
+                let stop = slice_bound(&slice, "stop")?.ok_or_else(|| PyValueError::new_err("slices of infinite arrays need a stop"))?;
+                if start < 0 || stop < 0 || step <= 0 {
+                    return Err(PyIndexError::new_err("slice bounds must be non-negative with a positive step"));
+                }
+                let values: Vec<f64> = (start as usize..stop.max(start) as usize).step_by(step as usize).map(|i| self.inner.get(i)).collect();
+                values.into_pyobject(py)
+            }
+        }
+    }
+
+    /// The first `n` elements as a list
+    fn take(&self, n: usize) -> Vec<f64> {
+        super::prefix(&self.inner, n)
+    }
+
+    /// The first `n` elements as a NumPy array
+    fn to_numpy<'py>(&self, py: Python<'py>, n: usize) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_vec(py, super::prefix(&self.inner, n))
+    }
+
+    /// Cumulative sums
+    fn cumsum(&self) -> Self {
+        Self::wrap(super::cumulative(&self.inner))
+    }
+
+    fn __add__(&self, other: Operand<'_>) -> Self {
+        Self::wrap(super::binary(BinaryOp::Add, &self.inner, &other.array()))
+    }
+
+    fn __radd__(&self, other: Operand<'_>) -> Self {
+        Self::wrap(super::binary(BinaryOp::Add, &other.array(), &self.inner))
+    }
+
+    fn __sub__(&self, other: Operand<'_>) -> Self {
+        Self::wrap(super::binary(BinaryOp::Sub, &self.inner, &other.array()))
+    }
+
+    fn __rsub__(&self, other: Operand<'_>) -> Self {
+        Self::wrap(super::binary(BinaryOp::Sub, &other.array(), &self.inner))
+    }
+
+    fn __mul__(&self, other: Operand<'_>) -> Self {
+        Self::wrap(super::binary(BinaryOp::Mul, &self.inner, &other.array()))
+    }
+
+    fn __rmul__(&self, other: Operand<'_>) -> Self {
+        Self::wrap(super::binary(BinaryOp::Mul, &other.array(), &self.inner))
+    }
+
+    fn __truediv__(&self, other: Operand<'_>) -> Self {
+        Self::wrap(super::binary(BinaryOp::Div, &self.inner, &other.array()))
+    }
+
+    fn __rtruediv__(&self, other: Operand<'_>) -> Self {
+        Self::wrap(super::binary(BinaryOp::Div, &other.array(), &self.inner))
+    }
+
+    fn __neg__(&self) -> Self {
+        Self::wrap(super::scale(&self.inner, -1.0))
+    }
+
+    fn __repr__(&self) -> String {
+        let shown: Vec<String> = super::prefix(&self.inner, 5).iter().map(|x| format!("{:?}", x)).collect();
+        format!("InfiniteArray([{}, ...])", shown.join(", "))
+    }
+}
+
+/// Add the classes to a Python module, e.g. from another extension
+pub fn register(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyInfiniteArray>()
+}
+
+#[pymodule]
+fn infinite_arrays(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    register(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    fn eval<T: for<'a, 'py> FromPyObject<'a, 'py>>(code: &std::ffi::CStr) -> PyResult<T> {
+        Python::initialize();
+        Python::attach(|py| {
+            let locals = PyDict::new(py);
+            locals.set_item("a", Bound::new(py, PyInfiniteArray::range(0.0, 1.0))?)?;
+            locals.set_item("ones", Bound::new(py, PyInfiniteArray::ones())?)?;
+            py.eval(code, None, Some(&locals))?.extract().map_err(Into::into)
+        })
+    }
+
+    #[test]
+    fn test_python_indexing() {
+        assert_eq!(eval::<f64>(c"a[7]").unwrap(), 7.0);
+        assert_eq!(eval::<Vec<f64>>(c"a[2:9:3]").unwrap(), vec![2.0, 5.0, 8.0]);
+        assert!(eval::<f64>(c"a[-1]").is_err());
+        assert!(eval::<Vec<f64>>(c"a[3:]").is_err());
+        assert_eq!(eval::<String>(c"repr(ones)").unwrap(), "InfiniteArray([1.0, 1.0, 1.0, 1.0, 1.0, ...])");
+    }
+
+    #[test]
+    fn test_python_arithmetic() {
+        assert_eq!(eval::<Vec<f64>>(c"(2 * a + ones).take(3)").unwrap(), vec![1.0, 3.0, 5.0]);
+        assert_eq!(eval::<Vec<f64>>(c"(1 / (a + 1) - 0.5)[0:2]").unwrap(), vec![0.5, 0.0]);
+        assert_eq!(eval::<f64>(c"(-a).cumsum()[3]").unwrap(), -6.0);
+    }
+}
@@ -0,0 +1,94 @@
+//! JavaScript bindings through `wasm-bindgen`
+//!
+//! `LazyArray` objects are built from constructors and combined with methods,
+//! and nothing is computed until JavaScript calls `get` or `slice`.
+
+use super::{BinaryOp, SharedArray};
+use wasm_bindgen::prelude::*;
+
+/// A lazily defined infinite sequence of numbers
+#[wasm_bindgen]
+pub struct LazyArray {
+    inner: SharedArray,
+}
+
+impl LazyArray {
+    fn wrap(inner: SharedArray) -> LazyArray {
+        LazyArray { inner }
+    }
+}
+
+#[wasm_bindgen]
+impl LazyArray {
+    pub fn ones() -> LazyArray {
+        Self::wrap(super::ones())
+    }
+
+    pub fn zeros() -> LazyArray {
+        Self::wrap(super::zeros())
+    }
+
+    pub fn fill(value: f64) -> LazyArray {
+        Self::wrap(super::fill(value))
+    }
+
+    /// `start, start + step, start + 2 * step, ...`
+    pub fn range(start: f64, step: f64) -> LazyArray {
+        Self::wrap(super::range(start, step))
+    }
+
+    /// The element at `index`
+    pub fn get(&self, index: usize) -> f64 {
+        self.inner.get(index)
+    }
+
+    /// The first `n` elements, as a `Float64Array`
+    pub fn slice(&self, n: usize) -> Vec<f64> {
+        super::prefix(&self.inner, n)
+    }
+
+    pub fn add(&self, other: &LazyArray) -> LazyArray {
+        Self::wrap(super::binary(BinaryOp::Add, &self.inner, &other.inner))
+    }
+
+    pub fn sub(&self, other: &LazyArray) -> LazyArray {
+        Self::wrap(super::binary(BinaryOp::Sub, &self.inner, &other.inner))
+    }
+
+    pub fn mul(&self, other: &LazyArray) -> LazyArray {
+        Self::wrap(super::binary(BinaryOp::Mul, &self.inner, &other.inner))
+    }
+
+    pub fn div(&self, other: &LazyArray) -> LazyArray {
+        Self::wrap(super::binary(BinaryOp::Div, &self.inner, &other.inner))
+    }
+
+    /// Every element multiplied by `factor`
+    pub fn scale(&self, factor: f64) -> LazyArray {
+        Self::wrap(super::scale(&self.inner, factor))
+    }
+
+    /// Every element plus `offset`
+    pub fn shift(&self, offset: f64) -> LazyArray {
+        Self::wrap(super::binary(BinaryOp::Add, &self.inner, &super::fill(offset)))
+    }
+
+    /// Cumulative sums
+    pub fn cumsum(&self) -> LazyArray {
+        Self::wrap(super::cumulative(&self.inner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lazy_array_composition() {
+        let x = LazyArray::range(0.0, 0.5);
+        let y = x.mul(&x).add(&LazyArray::ones()).scale(2.0);
+        assert_eq!(y.get(4), 10.0);
+        assert_eq!(y.slice(3), vec![2.0, 2.5, 4.0]);
+        assert_eq!(LazyArray::fill(3.0).cumsum().shift(-3.0).slice(3), vec![0.0, 3.0, 6.0]);
+    }
+}
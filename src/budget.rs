@@ -0,0 +1,239 @@
+//! Guarding against runaway evaluation
+//!
+//! Nothing stops code from iterating an infinite array forever, for example
+//! a `find` whose predicate never holds or a `collect` without a `take`.
+//! [`Budgeted`] allows a fixed number of element reads and then refuses
+//! further ones: [`Budgeted::try_get_budgeted`] returns
+//! [`BudgetExhausted`], while `get` and iteration through
+//! [`InfiniteArray`] panic. When the limit is time rather than work,
+//! [`materialize_with_deadline`] returns whatever prefix was computed in
+//! time.
+
+use crate::arrays::{Indexed, InfiniteArray};
+use crate::describe::Description;
+use crate::error::Error;
+use num_traits::Zero;
+use std::cell::Cell;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// The budget of a [`Budgeted`] array ran out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetExhausted {
+    /// The number of reads allowed
+    pub budget: usize,
+    /// The element whose read was refused
+    pub index: usize,
+}
+
+impl fmt::Display for BudgetExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "evaluation budget of {} elements exhausted reading index {}", self.budget, self.index)
+    }
+}
+
+impl std::error::Error for BudgetExhausted {}
+
+/// An array that allows at most `budget` element reads
+#[derive(Debug)]
+pub struct Budgeted<A> {
+    inner: A,
+    budget: usize,
+    used: Cell<usize>,
+}
+
+impl<A> Budgeted<A> {
+    pub fn new(inner: A, budget: usize) -> Self {
+        Self {
+            inner,
+            budget,
+            used: Cell::new(0),
+        }
+    }
+
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Number of reads so far
+    pub fn used(&self) -> usize {
+        self.used.get()
+    }
+
+    /// Number of reads still allowed
+    pub fn remaining(&self) -> usize {
+        self.budget - self.used.get()
+    }
+
+    /// Allow `budget` more reads from now on
+    pub fn refill(&self) {
+        self.used.set(0);
+    }
+
+    /// Take `n` reads from the budget, starting at `index`
+    fn spend(&self, index: usize, n: usize) -> Result<(), BudgetExhausted> {
+        if n > self.remaining() {
+            return Err(BudgetExhausted {
+                budget: self.budget,
+                index: index + self.remaining(),
+            });
+        }
+        self.used.set(self.used.get() + n);
+        Ok(())
+    }
+
+    /// Element `index`, or an error once the budget is spent
+    pub fn try_get_budgeted<T>(&self, index: usize) -> Result<T, BudgetExhausted>
+    where
+        A: InfiniteArray<T>,
+    {
+        self.spend(index, 1)?;
+        Ok(self.inner.get(index))
+    }
+
+    /// Elements `start..start + out.len()`, or an error without reading any
+    /// if the budget does not cover all of them
+    pub fn try_get_range_budgeted<T>(&self, start: usize, out: &mut [T]) -> Result<(), BudgetExhausted>
+    where
+        A: InfiniteArray<T>,
+    {
+        self.spend(start, out.len())?;
+        self.inner.get_range(start, out);
+        Ok(())
+    }
+}
+
+impl<T, A> InfiniteArray<T> for Budgeted<A>
+where
+    A: InfiniteArray<T>,
+{
+    /// # Panics
+    ///
+    /// Panics once the budget is spent.
+    fn get(&self, index: usize) -> T {
+        self.try_get_budgeted(index).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.inner.len()
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        self.inner.support()
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        self.inner.periodicity()
+    }
+
+    fn get_range(&self, start: usize, out: &mut [T]) {
+        self.try_get_range_budgeted(start, out).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn try_get(&self, index: usize) -> Result<T, Error> {
+        self.spend(index, 1)?;
+        self.inner.try_get(index)
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("budgeted", [self.inner.describe()])
+    }
+}
+
+/// A prefix computed before a deadline, see [`materialize_with_deadline`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialPrefix<T> {
+    /// The elements computed, in index order
+    pub values: Vec<T>,
+    /// The number of elements asked for
+    pub requested: usize,
+}
+
+impl<T> PartialPrefix<T> {
+    /// Number of elements computed, which is the index reached
+    pub fn computed(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether every requested element was computed
+    pub fn is_complete(&self) -> bool {
+        self.values.len() == self.requested
+    }
+}
+
+/// The first `n` elements of `arr`, stopping early once `timeout` has passed
+///
+/// The clock is checked between elements, so a single slow element can
+/// overrun the deadline; everything computed by then is kept.
+pub fn materialize_with_deadline<T, A>(arr: &A, n: usize, timeout: Duration) -> PartialPrefix<T>
+where
+    A: InfiniteArray<T> + ?Sized,
+{
+    let requested = arr.len().map_or(n, |len| len.min(n));
+    let deadline = Instant::now() + timeout;
+    let mut elements = arr.iter();
+    let mut values = Vec::new();
+    while values.len() < requested && Instant::now() < deadline {
+        match elements.next() {
+            Some(x) => values.push(x),
+            None => break,
+        }
+    }
+    PartialPrefix { values, requested }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArrayFromFn};
+
+    #[test]
+    fn test_budget_refuses_reads() {
+        let x = Budgeted::new(InfiniteArrayFromFn::new(|i| i * i), 3);
+        assert_eq!(x.try_get_budgeted(4), Ok(16));
+        let mut out = [0; 3];
+        assert_eq!(x.try_get_range_budgeted(0, &mut out), Err(BudgetExhausted { budget: 3, index: 2 }));
+        assert_eq!(x.used(), 1);
+        assert_eq!(x.try_get_range_budgeted(0, &mut out[..2]), Ok(()));
+        assert_eq!(x.try_get_budgeted(5), Err(BudgetExhausted { budget: 3, index: 5 }));
+        x.refill();
+        assert_eq!(x.remaining(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "evaluation budget of 100 elements exhausted reading index 100")]
+    fn test_budget_stops_unbounded_search() {
+        let x = Budgeted::new(Fill::new(0), 100);
+        let _ = x.iter().position(|value| value == 1);
+    }
+
+    #[test]
+    fn test_deadline_keeps_partial_prefix() {
+        let quick = materialize_with_deadline(&Fill::new(2), 1000, Duration::from_secs(60));
+        assert!(quick.is_complete());
+        assert_eq!(quick.values, vec![2; 1000]);
+
+        let slow = InfiniteArrayFromFn::new(|i| {
+            std::thread::sleep(Duration::from_millis(20));
+            i
+        });
+        let partial = materialize_with_deadline(&slow, 1000, Duration::from_millis(100));
+        assert!(!partial.is_complete() && partial.computed() < 20);
+        assert_eq!(partial.values, (0..partial.computed()).collect::<Vec<_>>());
+    }
+}
@@ -1,12 +1,77 @@
 //! Caching for infinite arrays to enable mutability
 
 use std::collections::HashMap;
-use crate::arrays::InfiniteArray;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::arrays::{InfiniteArray, InfiniteVector};
+use crate::describe::Description;
+use crate::instrument::trace_event;
+use crate::prefix::count_prefix;
+
+mod backend;
+mod memoized;
+mod persist;
+mod shared;
+
+pub use backend::{BlockBackend, CacheBackend, ClockBackend, ForkLayer, DEFAULT_BLOCK_SIZE};
+#[cfg(feature = "sled")]
+pub use backend::SledBackend;
+pub use memoized::{Memoized, SyncMemoized};
+pub use persist::{FileBackend, Persist};
+pub use shared::{SharedCachedArray, Warmer, DEFAULT_SHARDS};
 
 /// A cached infinite array that stores computed values and allows mutation
-pub struct CachedArray<T, A> {
+///
+/// Entries live in a [`CacheBackend`], an in-memory `HashMap` by default.
+pub struct CachedArray<T, A, C = HashMap<usize, T>> {
     base: A,
-    cache: HashMap<usize, T>,
+    cache: C,
+    counters: Counters,
+    _phantom: PhantomData<T>,
+}
+
+/// How often a [`CachedArray`] found, missed and stored entries, see
+/// [`CachedArray::stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Reads answered from the cache
+    pub hits: usize,
+    /// Reads computed from the base array
+    pub misses: usize,
+    /// Values stored in the cache
+    pub insertions: usize,
+}
+
+impl CacheStats {
+    /// Fraction of reads answered from the cache, or `None` before any read
+    pub fn hit_rate(&self) -> Option<f64> {
+        let reads = self.hits + self.misses;
+        (reads > 0).then(|| self.hits as f64 / reads as f64)
+    }
+}
+
+/// Atomic counters, so that reads through `&self` can count without making
+/// the array `!Sync`
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    insertions: AtomicUsize,
+}
+
+impl Counters {
+    fn bump(counter: &AtomicUsize) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            insertions: self.insertions.load(Ordering::Relaxed),
+        }
+    }
 }
 
 impl<T, A> CachedArray<T, A>
@@ -16,35 +81,153 @@ where
 {
     /// Create a new cached array from a base infinite array
     pub fn new(base: A) -> Self {
+        Self::with_backend(base, HashMap::new())
+    }
+
+    /// Get a mutable reference to the cached value, computing it if necessary
+    pub fn get_mut(&mut self, index: usize) -> &mut T
+    where
+        T: Default,
+    {
+        if self.cache.contains_key(&index) {
+            Counters::bump(&self.counters.hits);
+        } else {
+            Counters::bump(&self.counters.misses);
+            Counters::bump(&self.counters.insertions);
+        }
+        self.cache.entry(index)
+            .or_insert_with(|| self.base.get(index))
+    }
+}
+
+impl<T, A, C> CachedArray<T, A, C>
+where
+    A: InfiniteArray<T>,
+    C: CacheBackend<T>,
+{
+    /// A cached array over `base` keeping its entries in `backend`, which
+    /// may already hold entries from an earlier run
+    pub fn with_backend(base: A, backend: C) -> Self {
         Self {
             base,
-            cache: HashMap::new(),
+            cache: backend,
+            counters: Counters::default(),
+            _phantom: PhantomData,
         }
     }
 
+    /// The backend holding the cached entries
+    pub fn backend(&self) -> &C {
+        &self.cache
+    }
+
     /// Get the value at the given index, using cache if available
+    ///
+    /// A miss is computed from the base array but not stored, since `get`
+    /// only borrows the backend immutably: plain reads never fill or
+    /// reorder the cache. Store entries with [`get_mut`](Self::get_mut),
+    /// [`prefetch`](Self::prefetch) or
+    /// [`get_or_compute_range`](Self::get_or_compute_range), or wrap the
+    /// base in a [`Memoized`] to remember every element read.
     pub fn get(&self, index: usize) -> T {
-        self.cache.get(&index)
-            .cloned()
-            .unwrap_or_else(|| self.base.get(index))
+        match self.cache.get(index) {
+            Some(value) => {
+                Counters::bump(&self.counters.hits);
+                value
+            }
+            None => {
+                trace_event!(index, "cache miss");
+                Counters::bump(&self.counters.misses);
+                self.base.get(index)
+            }
+        }
+    }
+
+    /// Write the elements `start..start + out.len()` into `out`, reading
+    /// the base array only where nothing is cached
+    pub fn get_range(&self, start: usize, out: &mut [T]) {
+        let mut cached: Vec<Option<T>> = std::iter::repeat_with(|| None).take(out.len()).collect();
+        self.cache.get_range(start, &mut cached);
+        for (offset, (slot, value)) in out.iter_mut().zip(cached).enumerate() {
+            *slot = match value {
+                Some(value) => {
+                    Counters::bump(&self.counters.hits);
+                    value
+                }
+                None => {
+                    Counters::bump(&self.counters.misses);
+                    self.base.get(start + offset)
+                }
+            };
+        }
     }
 
     /// Set the value at the given index
     pub fn set(&mut self, index: usize, value: T) {
+        Counters::bump(&self.counters.insertions);
         self.cache.insert(index, value);
     }
 
-    /// Get a mutable reference to the cached value, computing it if necessary
-    pub fn get_mut(&mut self, index: usize) -> &mut T
+    /// Compute and store every element of `range` that is not cached yet,
+    /// returning how many were computed
+    pub fn prefetch(&mut self, range: Range<usize>) -> usize {
+        let mut computed = 0;
+        for index in range {
+            if self.cache.get(index).is_none() {
+                let value = self.base.get(index);
+                self.set(index, value);
+                computed += 1;
+            }
+        }
+        computed
+    }
+
+    /// Elements `range`, computing every missing one and storing it
+    ///
+    /// The backend is read once for the whole range, and the misses are
+    /// computed together before being inserted.
+    pub fn get_or_compute_range(&mut self, range: Range<usize>) -> Vec<T>
     where
-        T: Default,
+        T: Clone,
     {
-        self.cache.entry(index)
-            .or_insert_with(|| self.base.get(index))
+        self.fill_range(range, |base, missing| missing.iter().map(|&index| base.get(index)).collect())
+    }
+
+    /// Like [`get_or_compute_range`](Self::get_or_compute_range), computing
+    /// the missing elements in parallel
+    #[cfg(feature = "rayon")]
+    pub fn get_or_compute_range_par(&mut self, range: Range<usize>) -> Vec<T>
+    where
+        T: Clone + Send,
+        A: Sync,
+    {
+        use rayon::prelude::*;
+        self.fill_range(range, |base, missing| missing.par_iter().map(|&index| base.get(index)).collect())
+    }
+
+    fn fill_range<F>(&mut self, range: Range<usize>, compute: F) -> Vec<T>
+    where
+        T: Clone,
+        F: FnOnce(&A, &[usize]) -> Vec<T>,
+    {
+        let start = range.start;
+        let mut cached: Vec<Option<T>> = std::iter::repeat_with(|| None).take(range.len()).collect();
+        self.cache.get_range(start, &mut cached);
+        let missing: Vec<usize> = range.filter(|index| cached[index - start].is_none()).collect();
+        let computed = compute(&self.base, &missing);
+        let counters = &self.counters;
+        counters.hits.fetch_add(cached.len() - missing.len(), Ordering::Relaxed);
+        counters.misses.fetch_add(missing.len(), Ordering::Relaxed);
+        counters.insertions.fetch_add(missing.len(), Ordering::Relaxed);
+        for (&index, value) in missing.iter().zip(computed) {
+            self.cache.insert(index, value.clone());
+            cached[index - start] = Some(value);
+        }
+        cached.into_iter().map(|value| value.expect("every element cached or computed")).collect()
     }
 
     /// Create an iterator over the cached array
-    pub fn iter(&self) -> CachedArrayIter<'_, T, A> {
+    pub fn iter(&self) -> CachedArrayIter<'_, T, A, C> {
         CachedArrayIter {
             cached: self,
             index: 0,
@@ -60,36 +243,219 @@ where
     pub fn cache_size(&self) -> usize {
         self.cache.len()
     }
+
+    /// A child sharing this array's base and cached entries without
+    /// copying them
+    ///
+    /// Values set in the fork, or cleared from it, stay in its own layer;
+    /// this array is borrowed, and so unchanged, while the fork lives.
+    pub fn fork(&self) -> CachedArray<T, &A, ForkLayer<'_, T, C>>
+    where
+        T: Clone,
+    {
+        CachedArray::with_backend(&self.base, ForkLayer::new(&self.cache))
+    }
+
+    /// Hits, misses and insertions since the array was created or the
+    /// counters were reset
+    pub fn stats(&self) -> CacheStats {
+        self.counters.snapshot()
+    }
+
+    /// Start counting from zero again
+    pub fn reset_stats(&mut self) {
+        self.counters = Counters::default();
+    }
+
+    /// How many of the first `n` elements satisfy `pred`
+    ///
+    /// Counts over the base array with [`count_prefix`] and then corrects
+    /// for the cached entries below `n`, so a structured base is never
+    /// scanned element by element.
+    pub fn count_prefix<P>(&self, pred: P, n: usize) -> usize
+    where
+        P: Fn(&T) -> bool,
+    {
+        let base = count_prefix(&self.base, &pred, n);
+        self.cache
+            .entries()
+            .filter(|&(index, _)| index < n)
+            .fold(base, |count, (index, value)| {
+                count + pred(&value) as usize - pred(&self.base.get(index)) as usize
+            })
+    }
+}
+
+impl<T, A> CachedArray<T, A, ClockBackend<T>>
+where
+    T: Clone,
+    A: InfiniteArray<T>,
+{
+    /// A cached array keeping at most `capacity` entries, evicting rarely
+    /// read ones first, see [`ClockBackend`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn bounded(base: A, capacity: usize) -> Self {
+        Self::with_backend(base, ClockBackend::new(capacity))
+    }
+
+    /// Get a mutable reference to the cached value, computing and storing
+    /// it if necessary, which may evict another entry
+    pub fn get_mut(&mut self, index: usize) -> &mut T {
+        if self.cache.contains(index) {
+            Counters::bump(&self.counters.hits);
+        } else {
+            Counters::bump(&self.counters.misses);
+            Counters::bump(&self.counters.insertions);
+            self.cache.insert(index, self.base.get(index));
+        }
+        self.cache.get_mut(index).expect("entry was just stored")
+    }
+}
+
+/// Reads go through the cache, so a cached array composes with every
+/// operation; since entries can be [`set`](CachedArray::set) to anything,
+/// the support and periodicity of the base are not assumed to hold.
+impl<T, A, C> InfiniteArray<T> for CachedArray<T, A, C>
+where
+    A: InfiniteArray<T>,
+    C: CacheBackend<T>,
+{
+    fn get(&self, index: usize) -> T {
+        CachedArray::get(self, index)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(CachedArray::iter(self))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.base.len()
+    }
+
+    fn get_range(&self, start: usize, out: &mut [T]) {
+        CachedArray::get_range(self, start, out)
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("cached", [self.base.describe()])
+    }
+}
+
+impl<T, A, C> InfiniteVector<T> for CachedArray<T, A, C>
+where
+    A: InfiniteArray<T>,
+    C: CacheBackend<T>,
+{
+}
+
+/// The entries stored in a [`CachedArray`], in increasing index order
+///
+/// The base array is not part of the contents, since it is usually built
+/// from a closure; pair the contents with a base again with
+/// [`CachedArray::with_contents`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CacheContents<T> {
+    pub entries: Vec<(usize, T)>,
+}
+
+impl<T, A, C> CachedArray<T, A, C>
+where
+    C: CacheBackend<T>,
+{
+    /// The cached entries, sorted by index
+    pub fn contents(&self) -> CacheContents<T> {
+        let mut entries: Vec<(usize, T)> = self.cache.entries().collect();
+        entries.sort_by_key(|&(i, _)| i);
+        CacheContents { entries }
+    }
+}
+
+impl<T, A> CachedArray<T, A> {
+    /// A cached array over `base` holding previously saved `contents`
+    pub fn with_contents(base: A, contents: CacheContents<T>) -> Self {
+        Self {
+            base,
+            cache: contents.entries.into_iter().collect(),
+            counters: Counters::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Serializes the [`contents`](CachedArray::contents) of the cache
+#[cfg(feature = "serde")]
+impl<T, A, C> serde::Serialize for CachedArray<T, A, C>
+where
+    T: serde::Serialize,
+    C: CacheBackend<T>,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.contents().serialize(serializer)
+    }
 }
 
 /// Iterator over a cached array
-pub struct CachedArrayIter<'a, T, A> {
-    cached: &'a CachedArray<T, A>,
+pub struct CachedArrayIter<'a, T, A, C = HashMap<usize, T>> {
+    cached: &'a CachedArray<T, A, C>,
     index: usize,
 }
 
-impl<'a, T, A> Iterator for CachedArrayIter<'a, T, A>
+impl<'a, T, A, C> Iterator for CachedArrayIter<'a, T, A, C>
 where
-    T: Clone,
     A: InfiniteArray<T>,
+    C: CacheBackend<T>,
 {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
         let result = Some(self.cached.get(self.index));
-        self.index += 1;
+        self.index = self.index.saturating_add(1);
         result
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.index = self.index.saturating_add(n);
+        self.next()
+    }
+}
+
+impl<T, A, C> std::iter::FusedIterator for CachedArrayIter<'_, T, A, C>
+where
+    A: InfiniteArray<T>,
+    C: CacheBackend<T>,
+{
+}
+
+impl<'a, T, A, C> IntoIterator for &'a CachedArray<T, A, C>
+where
+    A: InfiniteArray<T>,
+    C: CacheBackend<T>,
+{
+    type Item = T;
+    type IntoIter = CachedArrayIter<'a, T, A, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::arrays::Ones;
+    use crate::arrays::{InfiniteArrayFromFn, Ones};
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_cached_array() {
-        let ones = Ones::new();
+        let ones: Ones<f64> = Ones::new();
         let mut cached = CachedArray::new(ones);
         
         assert_eq!(cached.get(0), 1.0);
@@ -100,9 +466,146 @@ mod tests {
         assert_eq!(cached.get(1), 1.0);
     }
 
+    #[test]
+    fn test_cached_array_stats() {
+        let mut cached = CachedArray::new(Ones::<f64>::new());
+        assert_eq!(cached.stats().hit_rate(), None);
+        cached.set(1, 2.0);
+        let _: Vec<f64> = cached.iter().take(4).collect();
+        *cached.get_mut(1) += 1.0;
+        *cached.get_mut(7) += 1.0;
+        let stats = cached.stats();
+        assert_eq!(stats, CacheStats { hits: 2, misses: 4, insertions: 2 });
+        assert_eq!(stats.hit_rate(), Some(1.0 / 3.0));
+        cached.reset_stats();
+        assert_eq!(cached.stats(), CacheStats::default());
+    }
+
+    #[test]
+    fn test_bounded_cache_evicts_cold_entries() {
+        let squares = crate::arrays::InfiniteArrayFromFn::new(|i| i * i);
+        let mut cached = CachedArray::bounded(&squares, 3);
+        for i in 0..3 {
+            cached.set(i, i * i + 1);
+        }
+        assert_eq!(cached.get(0), 1);
+        *cached.get_mut(10) += 1;
+        assert_eq!(cached.cache_size(), 3);
+        assert_eq!(cached.backend().evictions(), 1);
+        assert!(cached.backend().contains(0) && !cached.backend().contains(1));
+        assert_eq!((cached.get(1), cached.get(10)), (1, 101));
+        assert!(!cached.backend().contains(1));
+        for i in 20..30 {
+            *cached.get_mut(i) += 1;
+        }
+        assert_eq!(cached.cache_size(), 3);
+        assert_eq!(cached.backend().evictions(), 11);
+    }
+
+    #[test]
+    fn test_block_backend_ranges() {
+        let mut cached = CachedArray::with_backend(Ones::<f64>::new(), BlockBackend::new(4));
+        for i in 2..7 {
+            cached.set(i, i as f64);
+        }
+        cached.set(5, 0.5);
+        cached.set(1 << 40, 9.0);
+        assert_eq!((cached.cache_size(), cached.backend().blocks()), (6, 3));
+        let mut out = [0.0; 9];
+        cached.get_range(1, &mut out);
+        assert_eq!(out, [1.0, 2.0, 3.0, 4.0, 0.5, 6.0, 1.0, 1.0, 1.0]);
+        assert_eq!((cached.stats().hits, cached.stats().misses), (5, 4));
+        assert_eq!(cached.contents().entries.last(), Some(&(1 << 40, 9.0)));
+    }
+
+    #[test]
+    fn test_prefetch_fills_missing_entries() {
+        let mut cached = CachedArray::new(InfiniteArrayFromFn::new(|i| i * 2));
+        cached.set(3, 0);
+        assert_eq!(cached.prefetch(2..6), 3);
+        assert_eq!(cached.prefetch(0..6), 2);
+        assert_eq!(cached.get(3), 0);
+        assert_eq!(cached.stats(), CacheStats { hits: 1, misses: 0, insertions: 6 });
+    }
+
+    #[test]
+    fn test_get_or_compute_range() {
+        let mut cached = CachedArray::with_backend(InfiniteArrayFromFn::new(|i| i + 1), BlockBackend::new(4));
+        cached.set(2, 0);
+        assert_eq!(cached.get_or_compute_range(1..7), vec![2, 0, 4, 5, 6, 7]);
+        assert_eq!(cached.stats(), CacheStats { hits: 1, misses: 5, insertions: 6 });
+        assert_eq!(cached.cache_size(), 6);
+        assert!(cached.get_or_compute_range(3..3).is_empty());
+        #[cfg(feature = "rayon")]
+        assert_eq!(cached.get_or_compute_range_par(5..9), vec![6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_cached_array_composes() {
+        use crate::operations::{add_arrays, cumsum};
+        let mut cached = CachedArray::new(InfiniteArrayFromFn::new(|i| i as i64));
+        cached.set(1, 10);
+        let sums = cumsum(&cached);
+        assert_eq!(InfiniteArray::iter(&sums).take(4).collect::<Vec<_>>(), vec![0, 10, 12, 15]);
+        assert_eq!(add_arrays(&cached, Ones::new()).get(1), 11);
+        assert_eq!(cached.stats().hits, 4);
+        assert_eq!(format!("{:?}", InfiniteArray::describe(&cached)), "cached(from_fn)");
+    }
+
+    #[test]
+    fn test_fork_layers_over_parent() {
+        let mut parent = CachedArray::new(Ones::<f64>::new());
+        parent.set(1, 5.0);
+        parent.set(2, 6.0);
+        let mut child = parent.fork();
+        child.set(2, 7.0);
+        child.set(3, 8.0);
+        assert_eq!(child.iter().take(5).collect::<Vec<_>>(), vec![1.0, 5.0, 7.0, 8.0, 1.0]);
+        assert_eq!(child.cache_size(), 3);
+        assert_eq!(child.backend().own().len(), 2);
+        let grandchild = child.fork();
+        assert_eq!((grandchild.get(1), grandchild.cache_size()), (5.0, 3));
+        child.clear_cache();
+        assert_eq!((child.get(1), child.cache_size()), (1.0, 0));
+        assert_eq!(parent.contents().entries, vec![(1, 5.0), (2, 6.0)]);
+    }
+
+    #[test]
+    fn test_cached_array_count_prefix() {
+        let ones: Ones<f64> = Ones::new();
+        let mut cached = CachedArray::new(ones);
+        cached.set(3, 0.0);
+        cached.set(5, 0.0);
+        cached.set(2_000_000_000, 0.0);
+        assert_eq!(cached.count_prefix(|&x| x == 1.0, 1_000_000_000), 999_999_998);
+    }
+
+    #[test]
+    fn test_cached_array_contents() {
+        let mut cached = CachedArray::new(Ones::<f64>::new());
+        cached.set(9, 2.0);
+        cached.set(4, 3.0);
+        let contents = cached.contents();
+        assert_eq!(contents.entries, vec![(4, 3.0), (9, 2.0)]);
+        let restored = CachedArray::with_contents(Ones::new(), contents);
+        assert_eq!(restored.get(4), 3.0);
+        assert_eq!(restored.get(5), 1.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_cached_array_serde() {
+        let mut cached = CachedArray::new(Ones::<f64>::new());
+        cached.set(2, 0.5);
+        let json = serde_json::to_string(&cached).unwrap();
+        assert_eq!(json, r#"{"entries":[[2,0.5]]}"#);
+        let contents: CacheContents<f64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(CachedArray::with_contents(Ones::new(), contents).get(2), 0.5);
+    }
+
     #[test]
     fn test_cached_array_iter() {
-        let ones = Ones::new();
+        let ones: Ones<f64> = Ones::new();
         let mut cached = CachedArray::new(ones);
         cached.set(0, 5.0);
         
@@ -110,6 +613,37 @@ mod tests {
         assert_eq!(iter.next(), Some(5.0));
         assert_eq!(iter.next(), Some(1.0));
         assert_eq!(iter.next(), Some(1.0));
+        assert_eq!((&cached).into_iter().take(2).collect::<Vec<_>>(), vec![5.0, 1.0]);
+        assert_eq!(cached.iter().step_by(1000).nth(1), Some(1.0));
+        assert_eq!(cached.stats().misses, 4);
+    }
+
+    #[test]
+    fn test_cached_array_btree_backend() {
+        let mut cached = CachedArray::with_backend(Ones::<f64>::new(), BTreeMap::new());
+        cached.set(7, 0.0);
+        cached.set(2, 0.0);
+        assert_eq!(cached.backend().keys().collect::<Vec<_>>(), vec![&2, &7]);
+        assert_eq!(cached.count_prefix(|&x| x == 1.0, 10), 8);
+        assert_eq!(cached.contents().entries, vec![(2, 0.0), (7, 0.0)]);
+    }
+
+    #[cfg(feature = "sled")]
+    #[test]
+    fn test_cached_array_sled_backend() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let tree = db.open_tree("squares").unwrap();
+        let squares = InfiniteArrayFromFn::new(|i| (i * i) as u64);
+        let mut cached = CachedArray::with_backend(&squares, SledBackend::new(tree.clone()));
+        cached.set(1 << 40, 3);
+        cached.set(5, 0);
+        assert_eq!(cached.get(5), 0);
+        assert_eq!(cached.get(6), 36);
+
+        // A second cache over the same tree sees the stored entries
+        let reopened = CachedArray::with_backend(&squares, SledBackend::<u64>::new(tree));
+        assert_eq!(reopened.cache_size(), 2);
+        assert_eq!(reopened.contents().entries, vec![(5, 0), (1 << 40, 3)]);
     }
 }
 
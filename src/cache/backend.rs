@@ -0,0 +1,441 @@
+//! Storage for the entries of a [`CachedArray`](super::CachedArray)
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Where a [`CachedArray`](super::CachedArray) keeps its entries
+///
+/// Backends store values by index and hand out clones, so they can live in
+/// memory or in an external store.
+pub trait CacheBackend<T> {
+    /// The value stored at `index`, if any
+    fn get(&self, index: usize) -> Option<T>;
+
+    /// Store `value` at `index`, replacing any previous value
+    fn insert(&mut self, index: usize, value: T);
+
+    /// Remove every entry
+    fn clear(&mut self);
+
+    /// Number of stored entries
+    fn len(&self) -> usize;
+
+    /// Whether nothing is stored
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All stored entries, in no particular order
+    fn entries(&self) -> Box<dyn Iterator<Item = (usize, T)> + '_>;
+
+    /// The values stored at `start..start + out.len()`, with `None` where
+    /// nothing is stored
+    fn get_range(&self, start: usize, out: &mut [Option<T>]) {
+        for (offset, slot) in out.iter_mut().enumerate() {
+            *slot = self.get(start + offset);
+        }
+    }
+}
+
+impl<T: Clone> CacheBackend<T> for HashMap<usize, T> {
+    fn get(&self, index: usize) -> Option<T> {
+        HashMap::get(self, &index).cloned()
+    }
+
+    fn insert(&mut self, index: usize, value: T) {
+        HashMap::insert(self, index, value);
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self);
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (usize, T)> + '_> {
+        Box::new(self.iter().map(|(&i, v)| (i, v.clone())))
+    }
+}
+
+/// An ordered in-memory backend, whose entries come out sorted by index
+impl<T: Clone> CacheBackend<T> for BTreeMap<usize, T> {
+    fn get(&self, index: usize) -> Option<T> {
+        BTreeMap::get(self, &index).cloned()
+    }
+
+    fn insert(&mut self, index: usize, value: T) {
+        BTreeMap::insert(self, index, value);
+    }
+
+    fn clear(&mut self) {
+        BTreeMap::clear(self);
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (usize, T)> + '_> {
+        Box::new(self.iter().map(|(&i, v)| (i, v.clone())))
+    }
+}
+
+/// Number of entries in a block of a [`BlockBackend`] by default
+pub const DEFAULT_BLOCK_SIZE: usize = 256;
+
+/// A backend storing entries in fixed-size blocks of consecutive indices
+///
+/// A block is allocated the first time an index in it is stored, and one
+/// map lookup then serves a whole block, so dense caches use far less
+/// memory than a per-element map and ranges are copied a block at a time.
+#[derive(Debug, Clone)]
+pub struct BlockBackend<T> {
+    block_size: usize,
+    blocks: HashMap<usize, Vec<Option<T>>>,
+    len: usize,
+}
+
+impl<T> BlockBackend<T> {
+    /// # Panics
+    ///
+    /// Panics if `block_size` is zero.
+    pub fn new(block_size: usize) -> Self {
+        assert!(block_size > 0, "blocks need room for an entry");
+        Self {
+            block_size,
+            blocks: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Number of allocated blocks
+    pub fn blocks(&self) -> usize {
+        self.blocks.len()
+    }
+}
+
+impl<T> Default for BlockBackend<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_BLOCK_SIZE)
+    }
+}
+
+impl<T: Clone> CacheBackend<T> for BlockBackend<T> {
+    fn get(&self, index: usize) -> Option<T> {
+        self.blocks.get(&(index / self.block_size))?[index % self.block_size].clone()
+    }
+
+    fn insert(&mut self, index: usize, value: T) {
+        let block_size = self.block_size;
+        let block = self
+            .blocks
+            .entry(index / block_size)
+            .or_insert_with(|| vec![None; block_size]);
+        let slot = &mut block[index % block_size];
+        if slot.is_none() {
+            self.len += 1;
+        }
+        *slot = Some(value);
+    }
+
+    fn clear(&mut self) {
+        self.blocks.clear();
+        self.len = 0;
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (usize, T)> + '_> {
+        Box::new(self.blocks.iter().flat_map(move |(&block, values)| {
+            values
+                .iter()
+                .enumerate()
+                .filter_map(move |(offset, value)| Some((block * self.block_size + offset, value.clone()?)))
+        }))
+    }
+
+    fn get_range(&self, start: usize, out: &mut [Option<T>]) {
+        let mut done = 0;
+        while done < out.len() {
+            let index = start + done;
+            let offset = index % self.block_size;
+            let n = (self.block_size - offset).min(out.len() - done);
+            let chunk = &mut out[done..done + n];
+            match self.blocks.get(&(index / self.block_size)) {
+                Some(block) => chunk.clone_from_slice(&block[offset..offset + n]),
+                None => chunk.fill(None),
+            }
+            done += n;
+        }
+    }
+}
+
+/// The backend of a [`fork`](super::CachedArray::fork): entries of the
+/// parent are read through, and everything stored goes to a layer of its
+/// own
+#[derive(Debug)]
+pub struct ForkLayer<'a, T, C> {
+    parent: &'a C,
+    own: HashMap<usize, T>,
+    /// Set by `clear`, after which the parent's entries are hidden
+    detached: bool,
+}
+
+impl<'a, T, C> ForkLayer<'a, T, C> {
+    pub fn new(parent: &'a C) -> Self {
+        Self {
+            parent,
+            own: HashMap::new(),
+            detached: false,
+        }
+    }
+
+    /// The entries stored in the fork itself
+    pub fn own(&self) -> &HashMap<usize, T> {
+        &self.own
+    }
+}
+
+impl<T: Clone, C: CacheBackend<T>> CacheBackend<T> for ForkLayer<'_, T, C> {
+    fn get(&self, index: usize) -> Option<T> {
+        match self.own.get(&index) {
+            Some(value) => Some(value.clone()),
+            None if self.detached => None,
+            None => self.parent.get(index),
+        }
+    }
+
+    fn insert(&mut self, index: usize, value: T) {
+        self.own.insert(index, value);
+    }
+
+    fn clear(&mut self) {
+        self.own.clear();
+        self.detached = true;
+    }
+
+    fn len(&self) -> usize {
+        if self.detached {
+            return self.own.len();
+        }
+        let added = self.own.keys().filter(|&&i| self.parent.get(i).is_none()).count();
+        self.parent.len() + added
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (usize, T)> + '_> {
+        let own = self.own.iter().map(|(&i, v)| (i, v.clone()));
+        if self.detached {
+            return Box::new(own);
+        }
+        let inherited = self
+            .parent
+            .entries()
+            .filter(move |(i, _)| !self.own.contains_key(i));
+        Box::new(own.chain(inherited))
+    }
+}
+
+/// A backend holding at most `capacity` entries, evicting with the clock
+/// algorithm
+///
+/// Every entry has a reference bit that reads set. When a new entry needs
+/// room, a hand sweeps the slots, clearing set bits and evicting the first
+/// entry whose bit is already clear. This approximates least-recently-used
+/// eviction without reordering anything on reads, so `get` stays `&self`.
+///
+/// An evicted index reads from the base array again, so this suits caches
+/// of computed values rather than of overrides made with
+/// [`set`](super::CachedArray::set).
+///
+/// Entries are only stored by the `&mut` methods of
+/// [`CachedArray`](super::CachedArray), such as `get_mut` and `prefetch`;
+/// its plain `get` reads the backend and marks hits but never inserts.
+#[derive(Debug)]
+pub struct ClockBackend<T> {
+    capacity: usize,
+    slots: Vec<Slot<T>>,
+    positions: HashMap<usize, usize>,
+    hand: usize,
+    evictions: usize,
+}
+
+#[derive(Debug)]
+struct Slot<T> {
+    index: usize,
+    value: T,
+    referenced: AtomicBool,
+}
+
+impl<T> ClockBackend<T> {
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "a bounded cache needs room for an entry");
+        Self {
+            capacity,
+            slots: Vec::with_capacity(capacity),
+            positions: HashMap::with_capacity(capacity),
+            hand: 0,
+            evictions: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of entries evicted to make room so far
+    pub fn evictions(&self) -> usize {
+        self.evictions
+    }
+
+    /// Whether an entry is stored at `index`, without counting as a read
+    pub fn contains(&self, index: usize) -> bool {
+        self.positions.contains_key(&index)
+    }
+
+    /// The entry at `index`, marked as read
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let slot = &mut self.slots[*self.positions.get(&index)?];
+        *slot.referenced.get_mut() = true;
+        Some(&mut slot.value)
+    }
+
+    /// The slot to reuse for a new entry, after evicting its occupant
+    fn victim(&mut self) -> usize {
+        loop {
+            let slot = &self.slots[self.hand];
+            let position = self.hand;
+            self.hand = (self.hand + 1) % self.slots.len();
+            if !slot.referenced.swap(false, Ordering::Relaxed) {
+                self.positions.remove(&slot.index);
+                self.evictions += 1;
+                return position;
+            }
+        }
+    }
+}
+
+impl<T: Clone> CacheBackend<T> for ClockBackend<T> {
+    fn get(&self, index: usize) -> Option<T> {
+        let slot = &self.slots[*self.positions.get(&index)?];
+        slot.referenced.store(true, Ordering::Relaxed);
+        Some(slot.value.clone())
+    }
+
+    fn insert(&mut self, index: usize, value: T) {
+        if let Some(&position) = self.positions.get(&index) {
+            let slot = &mut self.slots[position];
+            slot.value = value;
+            slot.referenced.store(true, Ordering::Relaxed);
+            return;
+        }
+        let slot = Slot {
+            index,
+            value,
+            referenced: AtomicBool::new(false),
+        };
+        let position = if self.slots.len() < self.capacity {
+            self.slots.push(slot);
+            self.slots.len() - 1
+        } else {
+            let position = self.victim();
+            self.slots[position] = slot;
+            position
+        };
+        self.positions.insert(index, position);
+    }
+
+    fn clear(&mut self) {
+        self.slots.clear();
+        self.positions.clear();
+        self.hand = 0;
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (usize, T)> + '_> {
+        Box::new(self.slots.iter().map(|slot| (slot.index, slot.value.clone())))
+    }
+}
+
+#[cfg(feature = "sled")]
+pub use self::sled::SledBackend;
+
+#[cfg(feature = "sled")]
+mod sled {
+    use super::CacheBackend;
+    use crate::cache::Persist;
+    use std::marker::PhantomData;
+
+    /// A backend in an embedded [`sled`](::sled) tree, for caches larger
+    /// than memory that outlive the process
+    ///
+    /// Keys are big-endian `u64` indices and values use the [`Persist`]
+    /// encoding. Storage errors cannot be reported through
+    /// [`CacheBackend`], so they panic.
+    pub struct SledBackend<T> {
+        tree: ::sled::Tree,
+        _phantom: PhantomData<T>,
+    }
+
+    impl<T> SledBackend<T> {
+        pub fn new(tree: ::sled::Tree) -> Self {
+            Self {
+                tree,
+                _phantom: PhantomData,
+            }
+        }
+
+        /// The underlying tree, e.g. to flush it
+        pub fn tree(&self) -> &::sled::Tree {
+            &self.tree
+        }
+    }
+
+    fn decode<T: Persist>(bytes: &[u8]) -> T {
+        T::read_le(&mut &bytes[..]).expect("sled cache entry has the wrong size")
+    }
+
+    impl<T: Persist> CacheBackend<T> for SledBackend<T> {
+        fn get(&self, index: usize) -> Option<T> {
+            let value = self.tree.get((index as u64).to_be_bytes()).expect("sled read failed");
+            value.map(|bytes| decode(&bytes))
+        }
+
+        fn insert(&mut self, index: usize, value: T) {
+            let mut bytes = Vec::with_capacity(T::SIZE);
+            value.write_le(&mut bytes).expect("writing to a Vec cannot fail");
+            self.tree
+                .insert((index as u64).to_be_bytes(), bytes)
+                .expect("sled write failed");
+        }
+
+        fn clear(&mut self) {
+            self.tree.clear().expect("sled clear failed");
+        }
+
+        fn len(&self) -> usize {
+            self.tree.len()
+        }
+
+        fn entries(&self) -> Box<dyn Iterator<Item = (usize, T)> + '_> {
+            Box::new(self.tree.iter().map(|entry| {
+                let (key, value) = entry.expect("sled read failed");
+                let index = u64::from_be_bytes(key.as_ref().try_into().expect("sled cache key is a u64"));
+                (index as usize, decode(&value))
+            }))
+        }
+    }
+}
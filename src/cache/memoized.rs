@@ -0,0 +1,147 @@
+//! Memoization behind `&self`
+
+use crate::arrays::{Indexed, InfiniteArray, InfiniteVector};
+use crate::describe::Description;
+use num_traits::Zero;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// An array that remembers every element it computes
+///
+/// Unlike [`CachedArray`](super::CachedArray) it memoizes on plain reads
+/// and cannot be written to, so it can wrap any operand of an expression
+/// without the surrounding code needing `&mut`. The values are those of the
+/// base, and so are its support and periodicity. The cache sits in a
+/// `RefCell`; [`SyncMemoized`] can be shared between threads.
+#[derive(Debug)]
+pub struct Memoized<T, A> {
+    base: A,
+    cache: RefCell<HashMap<usize, T>>,
+}
+
+/// A [`Memoized`] array that is `Sync`, keeping its cache behind a `RwLock`
+///
+/// Every miss takes the one write lock; under heavy contention prefer the
+/// sharded [`SharedCachedArray`](super::SharedCachedArray).
+#[derive(Debug)]
+pub struct SyncMemoized<T, A> {
+    base: A,
+    cache: RwLock<HashMap<usize, T>>,
+}
+
+/// The methods of both memoizing arrays, given how each reads and writes
+/// its cache
+macro_rules! memoized_impls {
+    ($name:ident, [$($read:tt)+], [$($write:tt)+]) => {
+        impl<T, A> $name<T, A> {
+            pub fn new(base: A) -> Self {
+                Self {
+                    base,
+                    cache: Default::default(),
+                }
+            }
+
+            pub fn base(&self) -> &A {
+                &self.base
+            }
+
+            pub fn into_inner(self) -> A {
+                self.base
+            }
+
+            /// Number of elements remembered
+            pub fn cache_size(&self) -> usize {
+                self.cache.$($read)+.len()
+            }
+
+            /// Forget every remembered element
+            pub fn clear_cache(&self) {
+                self.cache.$($write)+.clear();
+            }
+        }
+
+        impl<T, A> InfiniteArray<T> for $name<T, A>
+        where
+            T: Clone,
+            A: InfiniteArray<T>,
+        {
+            fn get(&self, index: usize) -> T {
+                if let Some(value) = self.cache.$($read)+.get(&index) {
+                    return value.clone();
+                }
+                let value = self.base.get(index);
+                self.cache.$($write)+.entry(index).or_insert(value).clone()
+            }
+
+            fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+                Box::new(Indexed::new(move |n| self.get(n)))
+            }
+
+            fn len(&self) -> Option<usize> {
+                self.base.len()
+            }
+
+            fn support(&self) -> Option<usize>
+            where
+                T: Zero + PartialEq,
+            {
+                self.base.support()
+            }
+
+            fn periodicity(&self) -> Option<(usize, usize)> {
+                self.base.periodicity()
+            }
+
+            fn describe(&self) -> Description {
+                Description::new("memoized", [self.base.describe()])
+            }
+        }
+
+        impl<T, A> InfiniteVector<T> for $name<T, A>
+        where
+            T: Clone,
+            A: InfiniteArray<T>,
+        {
+        }
+    };
+}
+
+memoized_impls!(Memoized, [borrow()], [borrow_mut()]);
+memoized_impls!(
+    SyncMemoized,
+    [read().expect("memoized cache poisoned")],
+    [write().expect("memoized cache poisoned")]
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+    use crate::operations::cumsum;
+    use crate::profile::Profiled;
+
+    #[test]
+    fn test_memoized_makes_cumsum_linear() {
+        let x = Profiled::new(InfiniteArrayFromFn::new(|i| i as u64));
+        let memo = Memoized::new(&x);
+        let sums = cumsum(&memo);
+        assert_eq!(sums.iter().nth(9), Some(45));
+        assert_eq!((x.total(), x.recomputed()), (10, 0));
+        assert_eq!(memo.cache_size(), 10);
+        memo.clear_cache();
+        assert_eq!(memo.get(3), 3);
+        assert_eq!(format!("{:?}", memo.describe()), "memoized(profiled(from_fn))");
+    }
+
+    #[test]
+    fn test_sync_memoized_across_threads() {
+        let memo = SyncMemoized::new(InfiniteArrayFromFn::new(|i| i * i));
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| assert_eq!(memo.iter().take(50).sum::<usize>(), 40425));
+            }
+        });
+        assert_eq!(memo.cache_size(), 50);
+    }
+}
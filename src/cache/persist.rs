@@ -0,0 +1,373 @@
+//! A compact binary file format for [`CachedArray`] contents
+//!
+//! A file is the magic bytes `IACA`, a format version byte, the element size
+//! in bytes, the number of entries as a little-endian `u64`, and then each
+//! entry as its index (`u64`) followed by its value, in increasing index
+//! order. [`FileBackend`] appends entries out of order when it flushes, and
+//! a later entry for an index replaces an earlier one; reading a file sorts
+//! its entries and keeps the last one for each index.
+
+use super::{CacheBackend, CacheContents, CachedArray};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 4] = b"IACA";
+const VERSION: u8 = 1;
+/// Offset of the entry count, after the magic bytes, version and size
+const COUNT_OFFSET: u64 = 6;
+
+/// Values with a fixed-size little-endian encoding, which
+/// [`CachedArray::save`] can write
+pub trait Persist: Sized {
+    /// Encoded size in bytes
+    const SIZE: usize;
+
+    fn write_le<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    fn read_le<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! persist_numbers {
+    ($($t:ty),*) => {$(
+        impl Persist for $t {
+            const SIZE: usize = std::mem::size_of::<$t>();
+
+            fn write_le<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                writer.write_all(&self.to_le_bytes())
+            }
+
+            fn read_le<R: Read>(reader: &mut R) -> io::Result<Self> {
+                let mut bytes = [0; std::mem::size_of::<$t>()];
+                reader.read_exact(&mut bytes)?;
+                Ok(<$t>::from_le_bytes(bytes))
+            }
+        }
+    )*};
+}
+
+persist_numbers!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Persist for bool {
+    const SIZE: usize = 1;
+
+    fn write_le<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u8).write_le(writer)
+    }
+
+    fn read_le<R: Read>(reader: &mut R) -> io::Result<Self> {
+        match u8::read_le(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid("boolean entry is not 0 or 1")),
+        }
+    }
+}
+
+#[cfg(feature = "complex")]
+impl<T: Persist> Persist for num_complex::Complex<T> {
+    const SIZE: usize = 2 * T::SIZE;
+
+    fn write_le<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.re.write_le(writer)?;
+        self.im.write_le(writer)
+    }
+
+    fn read_le<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Self::new(T::read_le(reader)?, T::read_le(reader)?))
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl Persist for rust_decimal::Decimal {
+    const SIZE: usize = 16;
+
+    fn write_le<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.serialize())
+    }
+
+    fn read_le<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = [0; 16];
+        reader.read_exact(&mut bytes)?;
+        Ok(Self::deserialize(bytes))
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn write_file<'a, T, W, I>(mut writer: W, entries: I) -> io::Result<()>
+where
+    T: Persist + 'a,
+    W: Write,
+    I: ExactSizeIterator<Item = (usize, &'a T)>,
+{
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[VERSION, T::SIZE as u8])?;
+    (entries.len() as u64).write_le(&mut writer)?;
+    for (index, value) in entries {
+        (index as u64).write_le(&mut writer)?;
+        value.write_le(&mut writer)?;
+    }
+    writer.flush()
+}
+
+impl<T: Persist> CacheContents<T> {
+    /// Write the entries in the binary format described in this module
+    pub fn write<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_file(writer, self.entries.iter().map(|(index, value)| (*index, value)))
+    }
+
+    /// Read entries written by [`write`](Self::write) or a [`FileBackend`],
+    /// in increasing index order
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut header = [0; 6];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid("not a cached array file"));
+        }
+        if header[4] != VERSION {
+            return Err(invalid("unsupported cached array file version"));
+        }
+        if header[5] as usize != T::SIZE {
+            return Err(invalid("cached array file has a different element size"));
+        }
+        let count = u64::read_le(&mut reader)?;
+        let mut entries = (0..count)
+            .map(|_| {
+                let index = usize::try_from(u64::read_le(&mut reader)?).map_err(|_| invalid("index out of range"))?;
+                Ok((index, T::read_le(&mut reader)?))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        // Latest first, so that the stable sort and dedup keep the last
+        // entry written for each index
+        entries.reverse();
+        entries.sort_by_key(|&(index, _)| index);
+        entries.dedup_by_key(|&mut (index, _)| index);
+        Ok(Self { entries })
+    }
+}
+
+impl<T, A, C> CachedArray<T, A, C>
+where
+    T: Persist,
+    C: CacheBackend<T>,
+{
+    /// Save the cached entries to `path`; the base array is not saved
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.contents().write(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<T, A> CachedArray<T, A>
+where
+    T: Persist,
+{
+    /// A cached array over `base` with the entries saved at `path`
+    pub fn load<P: AsRef<Path>>(path: P, base: A) -> io::Result<Self> {
+        let contents = CacheContents::read(BufReader::new(File::open(path)?))?;
+        Ok(Self::with_contents(base, contents))
+    }
+}
+
+/// A backend kept in memory and checkpointed to a file
+///
+/// Entries stored since the last [`flush`](FileBackend::flush) are dirty,
+/// and flushing appends just those to the file, so a long computation can
+/// save its progress often without rewriting everything; only a
+/// [`clear`](CacheBackend::clear) makes the next flush rewrite the file.
+/// Unless turned off with [`set_auto_flush`](FileBackend::set_auto_flush),
+/// dropping the backend flushes it, ignoring errors.
+#[derive(Debug)]
+pub struct FileBackend<T: Persist> {
+    path: PathBuf,
+    entries: HashMap<usize, T>,
+    dirty: BTreeSet<usize>,
+    /// Number of entries written to the file, counting replaced ones
+    written: u64,
+    rewrite: bool,
+    auto_flush: bool,
+}
+
+impl<T: Persist> FileBackend<T> {
+    /// The entries saved at `path`, or a new empty file there
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let contents = match File::open(&path) {
+            Ok(file) => Some(CacheContents::read(BufReader::new(file))?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+        let rewrite = contents.is_none();
+        let contents = contents.unwrap_or(CacheContents { entries: Vec::new() });
+        let mut backend = Self {
+            path,
+            written: contents.entries.len() as u64,
+            entries: contents.entries.into_iter().collect(),
+            dirty: BTreeSet::new(),
+            rewrite,
+            auto_flush: true,
+        };
+        backend.flush()?;
+        Ok(backend)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of entries stored since the last flush
+    pub fn dirty(&self) -> usize {
+        self.dirty.len()
+    }
+
+    /// Whether dropping the backend flushes it, which is the default
+    pub fn set_auto_flush(&mut self, auto_flush: bool) {
+        self.auto_flush = auto_flush;
+    }
+
+    /// Write the dirty entries to the file
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.rewrite {
+            let mut entries: Vec<(usize, &T)> = self.entries.iter().map(|(&i, v)| (i, v)).collect();
+            entries.sort_by_key(|&(i, _)| i);
+            write_file(BufWriter::new(File::create(&self.path)?), entries.into_iter())?;
+            self.written = self.entries.len() as u64;
+            self.rewrite = false;
+        } else if !self.dirty.is_empty() {
+            let mut file = OpenOptions::new().read(true).write(true).open(&self.path)?;
+            let end = COUNT_OFFSET + 8 + self.written * (8 + T::SIZE as u64);
+            file.seek(SeekFrom::Start(end))?;
+            let mut writer = BufWriter::new(&mut file);
+            for &index in &self.dirty {
+                (index as u64).write_le(&mut writer)?;
+                self.entries[&index].write_le(&mut writer)?;
+            }
+            writer.flush()?;
+            drop(writer);
+            // The count goes last, so an interrupted flush leaves the file
+            // as it was at the previous one
+            self.written += self.dirty.len() as u64;
+            file.seek(SeekFrom::Start(COUNT_OFFSET))?;
+            self.written.write_le(&mut file)?;
+        }
+        self.dirty.clear();
+        Ok(())
+    }
+}
+
+impl<T: Persist + Clone> CacheBackend<T> for FileBackend<T> {
+    fn get(&self, index: usize) -> Option<T> {
+        self.entries.get(&index).cloned()
+    }
+
+    fn insert(&mut self, index: usize, value: T) {
+        self.entries.insert(index, value);
+        self.dirty.insert(index);
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.dirty.clear();
+        self.rewrite = true;
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = (usize, T)> + '_> {
+        Box::new(self.entries.iter().map(|(&i, v)| (i, v.clone())))
+    }
+}
+
+impl<T: Persist> Drop for FileBackend<T> {
+    fn drop(&mut self) {
+        if self.auto_flush {
+            let _ = self.flush();
+        }
+    }
+}
+
+impl<T, A> CachedArray<T, A, FileBackend<T>>
+where
+    T: Persist + Clone,
+    A: crate::arrays::InfiniteArray<T>,
+{
+    /// A cached array over `base` checkpointed to the file at `path`, see
+    /// [`FileBackend`]
+    pub fn open<P: AsRef<Path>>(path: P, base: A) -> io::Result<Self> {
+        Ok(Self::with_backend(base, FileBackend::open(path)?))
+    }
+
+    /// Append the entries stored since the last flush to the file
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.cache.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{InfiniteArrayFromFn, Zeros};
+
+    #[test]
+    fn test_save_and_load() {
+        // Mark primes below 50 over a base of "not computed" flags
+        let mut sieve = CachedArray::new(InfiniteArrayFromFn::new(|_| false));
+        for n in 2..50u64 {
+            sieve.set(n as usize, (2..n).all(|d| n % d != 0));
+        }
+        let path = std::env::temp_dir().join(format!("infinite-arrays-sieve-{}.bin", std::process::id()));
+        sieve.save(&path).unwrap();
+        let loaded = CachedArray::load(&path, InfiniteArrayFromFn::new(|_| false)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.contents(), sieve.contents());
+        assert!(loaded.get(47) && !loaded.get(49));
+    }
+
+    #[test]
+    fn test_file_backend_appends_dirty_entries() {
+        let path = std::env::temp_dir().join(format!("infinite-arrays-journal-{}.bin", std::process::id()));
+        let record = 8 + 4;
+        let squares = || InfiniteArrayFromFn::new(|i| (i * i) as u32);
+        {
+            let mut cached = CachedArray::open(&path, squares()).unwrap();
+            cached.prefetch(0..10);
+            assert_eq!(cached.backend().dirty(), 10);
+            cached.flush().unwrap();
+            assert_eq!(std::fs::metadata(&path).unwrap().len(), 14 + 10 * record);
+            cached.set(3, 0);
+            cached.set(20, 400);
+        }
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 14 + 12 * record);
+        let contents = CacheContents::<u32>::read(File::open(&path).unwrap()).unwrap();
+        let expected: Vec<_> = (0..10).map(|i| (i, if i == 3 { 0 } else { (i * i) as u32 })).chain([(20, 400)]).collect();
+        assert_eq!(contents.entries, expected);
+        let mut reopened = CachedArray::open(&path, squares()).unwrap();
+        assert_eq!((reopened.cache_size(), reopened.get(3), reopened.get(9)), (11, 0, 81));
+        reopened.clear_cache();
+        reopened.set(1, 7);
+        reopened.flush().unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 14 + record);
+        drop(reopened);
+        let contents = CacheContents::<u32>::read(File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(contents.entries, vec![(1, 7)]);
+    }
+
+    #[test]
+    fn test_read_rejects_bad_files() {
+        let mut bytes = Vec::new();
+        CacheContents { entries: vec![(3, 1.5f64)] }.write(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 6 + 8 + 16);
+        assert!(CacheContents::<f32>::read(bytes.as_slice()).is_err());
+        assert!(CacheContents::<f64>::read(&bytes[..20]).is_err());
+        assert!(CacheContents::<f64>::read(&b"nope"[..]).is_err());
+
+        let cached = CachedArray::with_contents(Zeros::<f64>::new(), CacheContents::read(bytes.as_slice()).unwrap());
+        assert_eq!(cached.get(3), 1.5);
+    }
+}
@@ -0,0 +1,275 @@
+//! A cache that threads share
+
+use crate::arrays::{Indexed, InfiniteArray};
+use crate::describe::Description;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread::JoinHandle;
+
+/// Number of independently locked shards by default
+pub const DEFAULT_SHARDS: usize = 16;
+
+/// A memoizing cache over an expensive array that many threads can read at
+/// once
+///
+/// Clones share the base array and the cache. Entries are spread over
+/// shards by index, each behind its own `RwLock`, so readers of different
+/// indices rarely wait for each other, and cached reads only take a read
+/// lock. An element is computed outside any lock; when two threads miss the
+/// same index at once both compute it, and the first value stored wins.
+pub struct SharedCachedArray<T, A> {
+    inner: Arc<Shared<T, A>>,
+}
+
+struct Shared<T, A> {
+    base: A,
+    shards: Box<[RwLock<HashMap<usize, T>>]>,
+}
+
+impl<T, A> Clone for SharedCachedArray<T, A> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T, A> SharedCachedArray<T, A> {
+    pub fn new(base: A) -> Self {
+        Self::with_shards(base, DEFAULT_SHARDS)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    pub fn with_shards(base: A, shards: usize) -> Self {
+        assert!(shards > 0, "a shared cache needs at least one shard");
+        Self {
+            inner: Arc::new(Shared {
+                base,
+                shards: (0..shards).map(|_| RwLock::new(HashMap::new())).collect(),
+            }),
+        }
+    }
+
+    pub fn base(&self) -> &A {
+        &self.inner.base
+    }
+
+    fn shard(&self, index: usize) -> &RwLock<HashMap<usize, T>> {
+        &self.inner.shards[index % self.inner.shards.len()]
+    }
+
+    /// Store `value` at `index`, replacing any cached value
+    pub fn set(&self, index: usize, value: T) {
+        self.shard(index).write().expect("cache shard poisoned").insert(index, value);
+    }
+
+    /// Number of cached entries
+    pub fn cache_size(&self) -> usize {
+        self.inner
+            .shards
+            .iter()
+            .map(|shard| shard.read().expect("cache shard poisoned").len())
+            .sum()
+    }
+
+    /// Remove every cached entry
+    pub fn clear_cache(&self) {
+        for shard in self.inner.shards.iter() {
+            shard.write().expect("cache shard poisoned").clear();
+        }
+    }
+}
+
+impl<T, A> SharedCachedArray<T, A>
+where
+    T: Clone,
+    A: InfiniteArray<T>,
+{
+    /// Compute and store every element of `range` that is not cached yet,
+    /// returning how many were computed
+    pub fn prefetch(&self, range: Range<usize>) -> usize {
+        range.filter(|&index| self.fill(index)).count()
+    }
+
+    /// Compute element `index` unless it is cached; whether it was computed
+    fn fill(&self, index: usize) -> bool {
+        let shard = self.shard(index);
+        if shard.read().expect("cache shard poisoned").contains_key(&index) {
+            return false;
+        }
+        let value = self.inner.base.get(index);
+        shard.write().expect("cache shard poisoned").entry(index).or_insert(value);
+        true
+    }
+
+    /// Start a background thread keeping the `ahead` elements from a cursor
+    /// cached, see [`Warmer`]
+    pub fn warm_ahead(&self, ahead: usize) -> Warmer
+    where
+        T: Send + Sync + 'static,
+        A: Send + Sync + 'static,
+    {
+        let state = Arc::new((Mutex::new(WarmerState { cursor: 0, stop: false }), Condvar::new()));
+        let cache = self.clone();
+        let shared = Arc::clone(&state);
+        let handle = std::thread::spawn(move || {
+            let (lock, wake) = &*shared;
+            let mut next = 0;
+            loop {
+                let index = {
+                    let mut state = lock.lock().expect("warmer state poisoned");
+                    while !state.stop && next >= state.cursor + ahead {
+                        state = wake.wait(state).expect("warmer state poisoned");
+                    }
+                    if state.stop {
+                        return;
+                    }
+                    next = next.max(state.cursor);
+                    next
+                };
+                cache.fill(index);
+                next += 1;
+            }
+        });
+        Warmer {
+            state,
+            handle: Some(handle),
+        }
+    }
+}
+
+struct WarmerState {
+    cursor: usize,
+    stop: bool,
+}
+
+/// A background thread filling a [`SharedCachedArray`] ahead of a reader
+///
+/// The thread computes the elements `cursor..cursor + ahead` in order and
+/// then sleeps until [`advance`](Warmer::advance) moves the cursor. A
+/// sequential reader advancing as it goes finds its elements cached once
+/// the warmer keeps pace. Dropping the warmer stops the thread after the
+/// element it is computing.
+pub struct Warmer {
+    state: Arc<(Mutex<WarmerState>, Condvar)>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Warmer {
+    /// Move the cursor to `index`; elements before it are no longer warmed
+    pub fn advance(&self, index: usize) {
+        let (lock, wake) = &*self.state;
+        lock.lock().expect("warmer state poisoned").cursor = index;
+        wake.notify_one();
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.state.0.lock().expect("warmer state poisoned").cursor
+    }
+}
+
+impl Drop for Warmer {
+    fn drop(&mut self) {
+        let (lock, wake) = &*self.state;
+        lock.lock().expect("warmer state poisoned").stop = true;
+        wake.notify_one();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl<T, A> InfiniteArray<T> for SharedCachedArray<T, A>
+where
+    T: Clone,
+    A: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        let shard = self.shard(index);
+        if let Some(value) = shard.read().expect("cache shard poisoned").get(&index) {
+            return value.clone();
+        }
+        let value = self.inner.base.get(index);
+        shard.write().expect("cache shard poisoned").entry(index).or_insert(value).clone()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.inner.base.len()
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("shared_cached", [self.inner.base.describe()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_shared_cache_across_threads() {
+        let evaluations = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&evaluations);
+        let squares = SharedCachedArray::new(InfiniteArrayFromFn::new(move |i| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            (i * i) as u64
+        }));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let squares = squares.clone();
+                std::thread::spawn(move || (0..100).map(|i| squares.get(i)).sum::<u64>())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 328_350);
+        }
+        assert_eq!(squares.cache_size(), 100);
+        let before = evaluations.load(Ordering::Relaxed);
+        assert!((100..=400).contains(&before));
+        assert_eq!(squares.get(99), 9801);
+        assert_eq!(evaluations.load(Ordering::Relaxed), before);
+    }
+
+    #[test]
+    fn test_warmer_runs_ahead_of_cursor() {
+        let evaluations = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&evaluations);
+        let cache = SharedCachedArray::new(InfiniteArrayFromFn::new(move |i| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            i * 3
+        }));
+        assert_eq!(cache.prefetch(0..4), 4);
+        let warmer = cache.warm_ahead(8);
+        let settles_at = |size| {
+            (0..1000).any(|_| {
+                std::thread::sleep(std::time::Duration::from_millis(2));
+                cache.cache_size() == size
+            })
+        };
+        assert!(settles_at(8));
+        warmer.advance(10);
+        assert!(settles_at(16));
+        drop(warmer);
+        assert_eq!(evaluations.load(Ordering::Relaxed), 16);
+        assert_eq!(cache.prefetch(8..10), 2);
+        assert_eq!(cache.get(17), 51);
+    }
+
+    #[test]
+    fn test_shared_cache_overrides() {
+        let cache = SharedCachedArray::with_shards(InfiniteArrayFromFn::new(|i| i), 1);
+        cache.set(3, 30);
+        assert_eq!(cache.iter().take(5).collect::<Vec<_>>(), vec![0, 1, 2, 30, 4]);
+        cache.clear_cache();
+        assert_eq!((cache.cache_size(), cache.get(3)), (0, 3));
+    }
+}
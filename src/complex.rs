@@ -0,0 +1,174 @@
+//! Arrays of complex numbers
+//!
+//! `Complex<T>` from `num-complex` is an ordinary element type: the
+//! structured arrays, element-wise operations and caches work with it as
+//! they do with floats. This module adds the parts that only make sense for
+//! complex elements, and broadcasting of a complex scalar over a real array,
+//! as needed for Fourier coefficients and spectra.
+
+use crate::arrays::{InfiniteArray, InfiniteArrayFromFn, InfiniteVector};
+use crate::describe::Description;
+use num_complex::Complex;
+use num_traits::{Float, Num, Zero};
+
+/// `f(arr[i])` for a part or transform of a complex element, see [`conj`],
+/// [`re`], [`im`] and [`abs`]
+#[derive(Debug, Clone)]
+pub struct ComplexMap<TIn, TOut, A> {
+    arr: A,
+    f: fn(TIn) -> TOut,
+    op: &'static str,
+}
+
+impl<TIn, TOut, A> InfiniteArray<TOut> for ComplexMap<TIn, TOut, A>
+where
+    A: InfiniteArray<TIn>,
+{
+    fn get(&self, index: usize) -> TOut {
+        (self.f)(self.arr.get(index))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = TOut> + '_> {
+        Box::new(self.arr.iter().map(self.f))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.arr.len()
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        self.arr.periodicity()
+    }
+
+    fn describe(&self) -> Description {
+        Description::new(self.op, [self.arr.describe()])
+    }
+}
+
+impl<TIn, TOut, A: InfiniteArray<TIn>> InfiniteVector<TOut> for ComplexMap<TIn, TOut, A> {}
+
+/// Complex conjugates of the elements
+pub fn conj<T, A>(arr: A) -> ComplexMap<Complex<T>, Complex<T>, A>
+where
+    T: Clone + Num + std::ops::Neg<Output = T>,
+    A: InfiniteArray<Complex<T>>,
+{
+    ComplexMap {
+        arr,
+        f: |z| z.conj(),
+        op: "conj",
+    }
+}
+
+/// Real parts of the elements
+pub fn re<T, A>(arr: A) -> ComplexMap<Complex<T>, T, A>
+where
+    A: InfiniteArray<Complex<T>>,
+{
+    ComplexMap {
+        arr,
+        f: |z| z.re,
+        op: "re",
+    }
+}
+
+/// Imaginary parts of the elements
+pub fn im<T, A>(arr: A) -> ComplexMap<Complex<T>, T, A>
+where
+    A: InfiniteArray<Complex<T>>,
+{
+    ComplexMap {
+        arr,
+        f: |z| z.im,
+        op: "im",
+    }
+}
+
+/// Moduli `|z|` of the elements
+pub fn abs<T, A>(arr: A) -> ComplexMap<Complex<T>, T, A>
+where
+    T: Float,
+    A: InfiniteArray<Complex<T>>,
+{
+    ComplexMap {
+        arr,
+        f: |z| z.norm(),
+        op: "abs",
+    }
+}
+
+/// A real array as complex numbers with zero imaginary part
+pub fn to_complex<T, A>(arr: A) -> ComplexMap<T, Complex<T>, A>
+where
+    T: Zero,
+    A: InfiniteArray<T>,
+{
+    ComplexMap {
+        arr,
+        f: |x| Complex::new(x, T::zero()),
+        op: "to_complex",
+    }
+}
+
+/// `arr + z` for a real `arr` and a complex scalar `z`
+pub fn add_complex_scalar<T, A>(arr: A, z: Complex<T>) -> InfiniteArrayFromFn<impl Fn(usize) -> Complex<T>, Complex<T>>
+where
+    T: Num + Clone,
+    A: InfiniteArray<T>,
+{
+    let description = Description::new("add_scalar", [arr.describe()]);
+    InfiniteArrayFromFn::new(move |i| Complex::new(arr.get(i) + z.re.clone(), z.im.clone())).with_description(description)
+}
+
+/// `arr * z` for a real `arr` and a complex scalar `z`, e.g. a phase factor
+pub fn mul_complex_scalar<T, A>(arr: A, z: Complex<T>) -> InfiniteArrayFromFn<impl Fn(usize) -> Complex<T>, Complex<T>>
+where
+    T: Num + Clone,
+    A: InfiniteArray<T>,
+{
+    let description = Description::new("mul_scalar", [arr.describe()]);
+    InfiniteArrayFromFn::new(move |i| z.clone().scale(arr.get(i))).with_description(description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{EventuallyPeriodic, Fill, Ones, PaddedArray, Zeros};
+    use crate::cache::CachedArray;
+    use crate::operations::{add_arrays, cumsum, mul_arrays, mul_scalar};
+
+    type C = Complex<f64>;
+
+    #[test]
+    fn test_structured_arrays_and_operations() {
+        let i = C::i();
+        let ones: Ones<C> = Ones::new();
+        let zeros: Zeros<C> = Zeros::new();
+        assert_eq!(ones.get(5), C::new(1.0, 0.0));
+        assert_eq!(zeros.support(), Some(0));
+
+        let padded = PaddedArray::new(vec![i, C::new(2.0, 1.0)], C::zero());
+        assert_eq!(padded.support(), Some(2));
+        let periodic = EventuallyPeriodic::new(vec![], vec![i, -i]);
+        let rotated = mul_arrays(&periodic, Fill::new(i));
+        assert_eq!(mul_scalar(&rotated, C::new(2.0, 0.0)).get(1), C::new(2.0, 0.0));
+        let sums = add_arrays(&padded, &ones);
+        assert_eq!(cumsum(&sums).get(2), C::new(5.0, 2.0));
+
+        let mut cached = CachedArray::new(mul_complex_scalar(Ones::<f64>::new(), i));
+        assert_eq!(cached.get_or_compute_range(3..4), vec![i]);
+        assert_eq!(cached.cache_size(), 1);
+        assert_eq!(add_complex_scalar(Zeros::<f64>::new(), i).get(0), i);
+    }
+
+    #[test]
+    fn test_complex_parts() {
+        let z = PaddedArray::new(vec![C::new(3.0, 4.0), C::new(-1.0, 2.0)], C::zero());
+        assert_eq!(conj(&z).get(0), C::new(3.0, -4.0));
+        assert_eq!(re(&z).iter().take(3).collect::<Vec<_>>(), vec![3.0, -1.0, 0.0]);
+        assert_eq!(im(&z).get(1), 2.0);
+        assert_eq!(abs(&z).get(0), 5.0);
+        assert_eq!(re(to_complex(Fill::new(2.5))).get(9), 2.5);
+        assert_eq!(format!("{:?}", abs(conj(&z)).describe()), "abs(conj(PaddedArray))");
+    }
+}
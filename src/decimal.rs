@@ -0,0 +1,112 @@
+//! Arrays of decimal numbers
+//!
+//! `rust_decimal::Decimal` is an ordinary element type: scalar operations,
+//! element-wise operations and `cumsum` are exact as long as the results
+//! fit in 28 significant digits, so a schedule of payments or accruals adds
+//! up to the cent. [`round_dp`] rounds a schedule to a currency's precision.
+
+use crate::arrays::{Indexed, InfiniteArray, InfiniteVector};
+use crate::describe::Description;
+use num_traits::Zero;
+use rust_decimal::Decimal;
+
+/// An array rounded to a number of decimal places, see [`round_dp`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundDp<A> {
+    arr: A,
+    dp: u32,
+}
+
+/// Every element rounded to `dp` decimal places, with halves rounded to
+/// even as is usual for money
+pub fn round_dp<A>(arr: A, dp: u32) -> RoundDp<A>
+where
+    A: InfiniteArray<Decimal>,
+{
+    RoundDp { arr, dp }
+}
+
+impl<A> InfiniteArray<Decimal> for RoundDp<A>
+where
+    A: InfiniteArray<Decimal>,
+{
+    fn get(&self, index: usize) -> Decimal {
+        self.arr.get(index).round_dp(self.dp)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Decimal> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.arr.len()
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        Decimal: Zero + PartialEq,
+    {
+        self.arr.support()
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        self.arr.periodicity()
+    }
+
+    fn get_range(&self, start: usize, out: &mut [Decimal]) {
+        self.arr.get_range(start, out);
+        for x in out.iter_mut() {
+            *x = x.round_dp(self.dp);
+        }
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("round_dp", [self.arr.describe()])
+    }
+}
+
+impl<A: InfiniteArray<Decimal>> InfiniteVector<Decimal> for RoundDp<A> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArrayFromFn, PaddedArray};
+    use crate::cache::CachedArray;
+    use crate::operations::{add_arrays, cumsum, mul_scalar};
+    use crate::simplify::StructuredCumsum;
+    use rust_decimal::prelude::FromPrimitive;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_payment_schedule_is_exact() {
+        let payments = Fill::new(dec("0.10"));
+        let paid = cumsum(&payments);
+        assert_eq!(paid.get(9), dec("1.00"));
+        assert_eq!(paid.get(999), dec("100.00"));
+        assert_eq!(payments.cumsum().get(999), dec("100.00"));
+        assert_ne!((0..1000).map(|_| 0.1f64).sum::<f64>(), 100.0);
+
+        let fees = PaddedArray::zero_padded(vec![dec("25.00")]);
+        let due = add_arrays(&payments, &fees);
+        assert_eq!(cumsum(&due).get(2), dec("25.30"));
+        assert_eq!(mul_scalar(&payments, dec("1.5")).get(4), dec("0.150"));
+    }
+
+    #[test]
+    fn test_rounded_accruals() {
+        // Simple daily interest of 5% a year on 1000.00
+        let rate = dec("0.05") / Decimal::from_u32(365).unwrap();
+        let interest = round_dp(InfiniteArrayFromFn::new(move |_| dec("1000.00") * rate), 2);
+        assert_eq!(interest.get(0), dec("0.14"));
+        assert_eq!(round_dp(Fill::new(dec("0.125")), 2).get(0), dec("0.12"));
+        assert_eq!(round_dp(Fill::new(dec("0.135")), 2).get(0), dec("0.14"));
+
+        let mut cached = CachedArray::new(&interest);
+        assert_eq!(cached.get_or_compute_range(0..3), vec![dec("0.14"); 3]);
+        assert_eq!(format!("{:?}", interest.describe()), "round_dp(from_fn)");
+    }
+}
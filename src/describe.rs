@@ -0,0 +1,130 @@
+//! Descriptions of what composed arrays compute
+//!
+//! Every array answers [`InfiniteArray::describe`] with a small tree of
+//! operation names. Combinators record their operands when they are built,
+//! so even an array hidden behind closures can say how it was made. `Debug`
+//! prints a description on one line, e.g. `add(mul_scalar(Ones), from_fn)`,
+//! and `Display` prints it as an indented tree.
+
+use std::fmt;
+use std::sync::Arc;
+
+/// A tree of operation names and their operands
+///
+/// Subtrees are shared, so describing a deeply composed array is cheap.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Description(Arc<Node>);
+
+#[derive(PartialEq, Eq, Hash)]
+struct Node {
+    op: String,
+    operands: Vec<Description>,
+}
+
+impl Description {
+    /// An operation applied to `operands`
+    pub fn new<I>(op: impl Into<String>, operands: I) -> Self
+    where
+        I: IntoIterator<Item = Description>,
+    {
+        Self(Arc::new(Node {
+            op: op.into(),
+            operands: operands.into_iter().collect(),
+        }))
+    }
+
+    /// An array with no operands
+    pub fn leaf(op: impl Into<String>) -> Self {
+        Self::new(op, [])
+    }
+
+    /// A leaf named after the type `A`, without its module path or generic
+    /// parameters
+    pub fn of_type<A: ?Sized>() -> Self {
+        let name = std::any::type_name::<A>();
+        let name = name.split('<').next().unwrap_or(name);
+        Self::leaf(name.rsplit("::").next().unwrap_or(name))
+    }
+
+    pub fn op(&self) -> &str {
+        &self.0.op
+    }
+
+    pub fn operands(&self) -> &[Description] {
+        &self.0.operands
+    }
+
+    /// Number of operations on the longest path to a leaf, counting both ends
+    pub fn depth(&self) -> usize {
+        1 + self.operands().iter().map(Description::depth).max().unwrap_or(0)
+    }
+
+    /// Number of nodes in the tree
+    pub fn size(&self) -> usize {
+        1 + self.operands().iter().map(Description::size).sum::<usize>()
+    }
+
+    fn write_tree(&self, f: &mut fmt::Formatter<'_>, indent: &str) -> fmt::Result {
+        let count = self.operands().len();
+        for (k, operand) in self.operands().iter().enumerate() {
+            let last = k + 1 == count;
+            writeln!(f)?;
+            write!(f, "{}{}{}", indent, if last { "└── " } else { "├── " }, operand.op())?;
+            operand.write_tree(f, &format!("{}{}", indent, if last { "    " } else { "│   " }))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Description {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.op())?;
+        if !self.operands().is_empty() {
+            write!(f, "(")?;
+            for (k, operand) in self.operands().iter().enumerate() {
+                if k > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{:?}", operand)?;
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Description {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.op())?;
+        self.write_tree(f, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArray, InfiniteArrayFromFn, Ones};
+    use crate::operations::{add_arrays, cumsum, mul_scalar};
+
+    #[test]
+    fn test_describe_composition() {
+        let x = InfiniteArrayFromFn::new(|i| i as f64);
+        let doubled = mul_scalar(Ones::new(), 2.0);
+        let sum = add_arrays(&doubled, &x);
+        let composed = cumsum(&sum);
+        let description = composed.describe();
+        assert_eq!(format!("{:?}", description), "cumsum(add(mul_scalar(Ones), from_fn))");
+        assert_eq!(format!("{:?}", composed), "InfiniteArrayFromFn(cumsum(add(mul_scalar(Ones), from_fn)))");
+        assert_eq!((description.depth(), description.size()), (4, 5));
+        assert_eq!(
+            description.to_string(),
+            "cumsum\n└── add\n    ├── mul_scalar\n    │   └── Ones\n    └── from_fn"
+        );
+    }
+
+    #[test]
+    fn test_describe_defaults_to_type_name() {
+        assert_eq!(Fill::new(1u8).describe().op(), "Fill");
+        assert_eq!(Description::of_type::<crate::kron::Kron<Ones<f64>, Ones<f64>>>().op(), "Kron");
+    }
+}
@@ -0,0 +1,136 @@
+//! Printing infinite arrays without iterating forever
+//!
+//! Arrays print a short prefix followed by an ellipsis, e.g.
+//! `[1.0, 1.0, 1.0, 1.0, 1.0, …]`. Elements use their `Debug` form and the
+//! precision of the format, so `{:.2}` prints every element with two
+//! decimals. Arrays with a closed form print it with the alternate flag:
+//! `format!("{:#}", Fill::new(2.0))` is `Fill(2.0)`.
+
+use crate::arrays::{EventuallyPeriodic, Fill, InfiniteArray, InfiniteArrayFromFn, Ones, PaddedArray, Zeros};
+use crate::ranges::{FromIndex, InfGeometricRange, InfStepRange, InfUnitRange, OneToInf};
+use num_traits::{Float, One, Zero};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul};
+
+/// Number of elements printed by the `Display` impls of arrays
+pub const PREVIEW_LEN: usize = 5;
+
+/// Write `[a, b, c, …]` with at most `n` elements, leaving out the
+/// ellipsis when a known `len` has been reached
+fn write_preview<T, I>(f: &mut fmt::Formatter<'_>, elements: I, len: Option<usize>, n: usize) -> fmt::Result
+where
+    T: fmt::Debug,
+    I: Iterator<Item = T>,
+{
+    let shown = len.map_or(n, |len| len.min(n));
+    write!(f, "[")?;
+    for (k, x) in elements.take(shown).enumerate() {
+        if k > 0 {
+            write!(f, ", ")?;
+        }
+        fmt::Debug::fmt(&x, f)?;
+    }
+    if len.is_none_or(|len| len > shown) {
+        write!(f, "{}…", if shown > 0 { ", " } else { "" })?;
+    }
+    write!(f, "]")
+}
+
+/// Displays the first elements of an array, made by [`preview`]
+pub struct Preview<'a, T, A: ?Sized> {
+    array: &'a A,
+    len: usize,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+/// Display the first `len` elements of `array` followed by an ellipsis
+pub fn preview<T, A>(array: &A, len: usize) -> Preview<'_, T, A>
+where
+    A: InfiniteArray<T> + ?Sized,
+{
+    Preview {
+        array,
+        len,
+        _phantom: PhantomData,
+    }
+}
+
+impl<T, A> fmt::Display for Preview<'_, T, A>
+where
+    T: fmt::Debug,
+    A: InfiniteArray<T> + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_preview(f, self.array.iter(), self.array.len(), self.len)
+    }
+}
+
+/// Implement `Display` as a preview, or as the closed form given by
+/// `$closed` with the alternate flag
+macro_rules! display_array {
+    ($ty:ty, [$($bound:tt)*], |$this:ident, $f:ident| $closed:expr) => {
+        impl<T: fmt::Debug + $($bound)*> fmt::Display for $ty {
+            fn fmt(&self, $f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if $f.alternate() {
+                    let $this = self;
+                    $closed
+                } else {
+                    write_preview($f, self.iter(), None, PREVIEW_LEN)
+                }
+            }
+        }
+    };
+}
+
+display_array!(Ones<T>, [One + Clone], |_this, f| write!(f, "Ones"));
+display_array!(Zeros<T>, [Zero + Clone], |_this, f| write!(f, "Zeros"));
+display_array!(Fill<T>, [Clone], |this, f| write!(f, "Fill({:?})", this.value()));
+display_array!(PaddedArray<T>, [Clone], |this, f| write!(f, "PaddedArray({:?}, {:?})", this.data(), this.tail()));
+display_array!(EventuallyPeriodic<T>, [Clone], |this, f| {
+    write!(f, "EventuallyPeriodic({:?}, {:?})", this.prefix(), this.period())
+});
+display_array!(OneToInf<T>, [FromIndex + Add<Output = T> + One + Clone], |_this, f| write!(f, "OneToInf"));
+display_array!(InfUnitRange<T>, [FromIndex + Add<Output = T> + One + Clone], |this, f| {
+    write!(f, "InfUnitRange({:?})", this.start())
+});
+display_array!(InfStepRange<T>, [FromIndex + Add<Output = T> + Mul<Output = T> + Clone], |this, f| {
+    write!(f, "InfStepRange({:?}, {:?})", this.start(), this.step())
+});
+display_array!(InfGeometricRange<T>, [Float + FromIndex], |this, f| {
+    write!(f, "InfGeometricRange({:?}, {:?})", this.start(), this.ratio())
+});
+
+impl<F, T> fmt::Display for InfiniteArrayFromFn<F, T>
+where
+    F: Fn(usize) -> T,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_preview(f, self.iter(), None, PREVIEW_LEN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_preview() {
+        assert_eq!(Ones::<f64>::new().to_string(), "[1.0, 1.0, 1.0, 1.0, 1.0, …]");
+        assert_eq!(format!("{:.2}", PaddedArray::new(vec![0.5], 0.0)), "[0.50, 0.00, 0.00, 0.00, 0.00, …]");
+        assert_eq!(OneToInf::<usize>::new().to_string(), "[1, 2, 3, 4, 5, …]");
+        let squares = InfiniteArrayFromFn::new(|i| i * i);
+        assert_eq!(preview(&squares, 3).to_string(), "[0, 1, 4, …]");
+        assert_eq!(preview(&squares, 0).to_string(), "[…]");
+    }
+
+    #[test]
+    fn test_display_closed_form() {
+        assert_eq!(format!("{:#}", Fill::new(2.0)), "Fill(2.0)");
+        assert_eq!(format!("{:#}", Zeros::<i32>::new()), "Zeros");
+        assert_eq!(format!("{:#}", EventuallyPeriodic::new(vec![1], vec![2, 3])), "EventuallyPeriodic([1], [2, 3])");
+        assert_eq!(format!("{:#}", InfStepRange::new(0, 2)), "InfStepRange(0, 2)");
+        assert_eq!(InfGeometricRange::new(1.0, 2.0).to_string(), "[1.0, 2.0, 4.0, 8.0, 16.0, …]");
+    }
+}
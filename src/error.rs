@@ -0,0 +1,73 @@
+//! Errors of fallible element access
+//!
+//! [`InfiniteArray::try_get`](crate::InfiniteArray::try_get) reports why an
+//! element cannot be read instead of panicking. Arrays over files, streams
+//! or partial data override it, and the combinators in
+//! [`fallible`](crate::fallible) pass errors of their operands on.
+
+use crate::budget::BudgetExhausted;
+use std::fmt;
+
+/// Why an element of an array could not be read
+#[derive(Debug)]
+pub enum Error {
+    /// The source behind the array failed, e.g. a file or a connection
+    Source(Box<dyn std::error::Error + Send + Sync>),
+    /// Element `index` does not fit in the element type
+    Overflow { index: usize },
+    /// Element `index` divides by zero, see [`DivPolicy`](crate::fallible::DivPolicy)
+    DivisionByZero { index: usize },
+    /// An evaluation budget ran out
+    Budget(BudgetExhausted),
+    /// Element `index` has not been produced yet
+    Unavailable { index: usize },
+    /// Element `index` is past the end of an array of length `len`
+    OutOfBounds { index: usize, len: usize },
+}
+
+impl Error {
+    /// Wrap a failure of the source behind an array
+    pub fn from_source<E>(err: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Error::Source(err.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Source(err) => write!(f, "array source failed: {}", err),
+            Error::Overflow { index } => write!(f, "element {} overflows its type", index),
+            Error::DivisionByZero { index } => write!(f, "element {} divides by zero", index),
+            Error::Budget(err) => err.fmt(f),
+            Error::Unavailable { index } => write!(f, "element {} is not available yet", index),
+            Error::OutOfBounds { index, len } => {
+                write!(f, "index {} out of bounds for an array of length {}", index, len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Source(err) => Some(err.as_ref()),
+            Error::Budget(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<BudgetExhausted> for Error {
+    fn from(err: BudgetExhausted) -> Self {
+        Error::Budget(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::from_source(err)
+    }
+}
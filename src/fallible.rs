@@ -0,0 +1,345 @@
+//! Operations that pass errors on
+//!
+//! The combinators in [`operations`](crate::operations) read their operands
+//! with `get`, so an operand that cannot produce an element panics. The
+//! ones here answer [`InfiniteArray::try_get`] by combining the `try_get`
+//! of their operands, so the first error reaches the caller; their `get`
+//! panics with that error.
+
+use crate::arrays::{Fill, Indexed, InfiniteArray, InfiniteVector};
+use crate::describe::Description;
+use crate::error::Error;
+use num_traits::{CheckedAdd, Zero};
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Sub};
+
+/// `f(a[i], b[i])`, see [`try_add_arrays`] and friends
+#[derive(Debug, Clone)]
+pub struct ZipWith<A, B, F> {
+    a: A,
+    b: B,
+    f: F,
+    op: &'static str,
+}
+
+impl<A, B, F> ZipWith<A, B, F> {
+    pub(crate) fn new(a: A, b: B, f: F, op: &'static str) -> Self {
+        Self { a, b, f, op }
+    }
+}
+
+impl<T, A, B, F> InfiniteArray<T> for ZipWith<A, B, F>
+where
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+    F: Fn(T, T) -> T,
+{
+    fn get(&self, index: usize) -> T {
+        self.try_get(index).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        match (self.a.len(), self.b.len()) {
+            (Some(m), Some(n)) => Some(m.min(n)),
+            (m, n) => m.or(n),
+        }
+    }
+
+    fn try_get(&self, index: usize) -> Result<T, Error> {
+        Ok((self.f)(self.a.try_get(index)?, self.b.try_get(index)?))
+    }
+
+    fn describe(&self) -> Description {
+        Description::new(self.op, [self.a.describe(), self.b.describe()])
+    }
+}
+
+impl<T, A, B, F> InfiniteVector<T> for ZipWith<A, B, F>
+where
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+    F: Fn(T, T) -> T,
+{
+}
+
+macro_rules! try_binary {
+    ($($(#[$doc:meta])* $name:ident, $bound:ident, $op:literal, $f:expr;)*) => {$(
+        $(#[$doc])*
+        pub fn $name<T, A, B>(a: A, b: B) -> ZipWith<A, B, fn(T, T) -> T>
+        where
+            T: $bound<Output = T>,
+            A: InfiniteArray<T>,
+            B: InfiniteArray<T>,
+        {
+            ZipWith::new(a, b, $f, $op)
+        }
+    )*};
+}
+
+try_binary! {
+    /// Element-wise `a + b`, passing errors of either operand on
+    try_add_arrays, Add, "add", |x, y| x + y;
+    /// Element-wise `a - b`, passing errors of either operand on
+    try_sub_arrays, Sub, "sub", |x, y| x - y;
+    /// Element-wise `a * b`, passing errors of either operand on
+    try_mul_arrays, Mul, "mul", |x, y| x * y;
+    /// Element-wise `a / b`, passing errors of either operand on
+    try_div_arrays, Div, "div", |x, y| x / y;
+}
+
+/// What a division does where the divisor is zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DivPolicy<T> {
+    /// Divide anyway, giving NaN or an infinity for floats and panicking
+    /// for integers
+    Ieee,
+    /// Give this value instead
+    Fill(T),
+    /// Fail with [`Error::DivisionByZero`]
+    Fail,
+}
+
+/// `a[i] / b[i]` with a [`DivPolicy`] for zero divisors, see
+/// [`div_arrays_with`]
+#[derive(Debug, Clone)]
+pub struct Quotient<T, A, B> {
+    a: A,
+    b: B,
+    policy: DivPolicy<T>,
+}
+
+/// Element-wise `a / b`, following `policy` where `b` is zero
+pub fn div_arrays_with<T, A, B>(a: A, b: B, policy: DivPolicy<T>) -> Quotient<T, A, B>
+where
+    T: Div<Output = T> + Zero + PartialEq + Clone,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    Quotient { a, b, policy }
+}
+
+/// `arr / divisor`, following `policy` if `divisor` is zero
+pub fn div_scalar_with<T, A>(arr: A, divisor: T, policy: DivPolicy<T>) -> Quotient<T, A, Fill<T>>
+where
+    T: Div<Output = T> + Zero + PartialEq + Clone,
+    A: InfiniteArray<T>,
+{
+    Quotient {
+        a: arr,
+        b: Fill::new(divisor),
+        policy,
+    }
+}
+
+impl<T, A, B> InfiniteArray<T> for Quotient<T, A, B>
+where
+    T: Div<Output = T> + Zero + PartialEq + Clone,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        self.try_get(index).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        match (self.a.len(), self.b.len()) {
+            (Some(m), Some(n)) => Some(m.min(n)),
+            (m, n) => m.or(n),
+        }
+    }
+
+    fn try_get(&self, index: usize) -> Result<T, Error> {
+        let (x, y) = (self.a.try_get(index)?, self.b.try_get(index)?);
+        match &self.policy {
+            _ if !y.is_zero() => Ok(x / y),
+            DivPolicy::Ieee => Ok(x / y),
+            DivPolicy::Fill(value) => Ok(value.clone()),
+            DivPolicy::Fail => Err(Error::DivisionByZero { index }),
+        }
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("div", [self.a.describe(), self.b.describe()])
+    }
+}
+
+impl<T, A, B> InfiniteVector<T> for Quotient<T, A, B>
+where
+    T: Div<Output = T> + Zero + PartialEq + Clone,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+}
+
+/// `f(arr[i])`, see [`try_broadcast`]
+#[derive(Clone)]
+pub struct Broadcast<TIn, A, F> {
+    arr: A,
+    f: F,
+    _phantom: PhantomData<fn(TIn)>,
+}
+
+/// Apply `f` to every element, passing errors of `arr` on
+pub fn try_broadcast<TIn, TOut, A, F>(arr: A, f: F) -> Broadcast<TIn, A, F>
+where
+    A: InfiniteArray<TIn>,
+    F: Fn(TIn) -> TOut,
+{
+    Broadcast {
+        arr,
+        f,
+        _phantom: PhantomData,
+    }
+}
+
+impl<TIn, TOut, A, F> InfiniteArray<TOut> for Broadcast<TIn, A, F>
+where
+    A: InfiniteArray<TIn>,
+    F: Fn(TIn) -> TOut,
+{
+    fn get(&self, index: usize) -> TOut {
+        self.try_get(index).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = TOut> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.arr.len()
+    }
+
+    fn try_get(&self, index: usize) -> Result<TOut, Error> {
+        self.arr.try_get(index).map(&self.f)
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("broadcast", [self.arr.describe()])
+    }
+}
+
+/// How a [`Cumsum`] adds the next element to the running sum
+pub trait SumMode<T> {
+    /// `sum + x`, or `None` on overflow
+    fn add(&self, sum: &T, x: &T) -> Option<T>;
+}
+
+/// Addition failing on overflow, see [`try_cumsum`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Checked;
+
+impl<T: CheckedAdd> SumMode<T> for Checked {
+    fn add(&self, sum: &T, x: &T) -> Option<T> {
+        sum.checked_add(x)
+    }
+}
+
+/// Cumulative sums with an addition that may overflow, see [`try_cumsum`]
+/// and [`overflow`](crate::overflow)
+#[derive(Debug, Clone)]
+pub struct Cumsum<A, M = Checked> {
+    arr: A,
+    mode: M,
+}
+
+impl<A, M> Cumsum<A, M> {
+    pub(crate) fn with_mode(arr: A, mode: M) -> Self {
+        Self { arr, mode }
+    }
+}
+
+/// Cumulative sums of `arr`, failing with [`Error::Overflow`] at the first
+/// sum that overflows and passing errors of `arr` on
+pub fn try_cumsum<T, A>(arr: A) -> Cumsum<A>
+where
+    T: Zero + CheckedAdd,
+    A: InfiniteArray<T>,
+{
+    Cumsum::with_mode(arr, Checked)
+}
+
+impl<T, A, M> InfiniteArray<T> for Cumsum<A, M>
+where
+    T: Zero,
+    A: InfiniteArray<T>,
+    M: SumMode<T>,
+{
+    fn get(&self, index: usize) -> T {
+        self.try_get(index).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.arr.len()
+    }
+
+    fn try_get(&self, index: usize) -> Result<T, Error> {
+        (0..=index).try_fold(T::zero(), |sum, i| {
+            self.mode.add(&sum, &self.arr.try_get(i)?).ok_or(Error::Overflow { index: i })
+        })
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("cumsum", [self.arr.describe()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{InfiniteArrayFromFn, PaddedArray};
+    use crate::budget::Budgeted;
+    use crate::streamed::IteratorArray;
+
+    #[test]
+    fn test_errors_pass_through_operations() {
+        let short = IteratorArray::new(vec![1.0, 2.0]);
+        let sum = try_add_arrays(&short, Fill::new(10.0));
+        assert_eq!(sum.try_get(1).unwrap(), 12.0);
+        assert!(matches!(sum.try_get(2), Err(Error::OutOfBounds { index: 2, len: 2 })));
+        assert_eq!(sum.len(), Some(2));
+
+        let budgeted = Budgeted::new(InfiniteArrayFromFn::new(|i| i as f64), 1);
+        let halves = try_broadcast(try_div_arrays(&budgeted, Fill::new(2.0)), |x: f64| x.floor());
+        assert_eq!(halves.try_get(3).unwrap(), 1.0);
+        let err = halves.try_get(4).unwrap_err();
+        assert_eq!(err.to_string(), "evaluation budget of 1 elements exhausted reading index 4");
+        assert!(std::error::Error::source(&err).is_some());
+        assert_eq!(format!("{:?}", halves.describe()), "broadcast(div(budgeted(from_fn), Fill))");
+    }
+
+    #[test]
+    fn test_division_policies() {
+        let divisors = PaddedArray::new(vec![2, 0, 5], 0);
+        let numerators = InfiniteArrayFromFn::new(|i| 10 * i as i32);
+        let filled = div_arrays_with(&numerators, &divisors, DivPolicy::Fill(-1));
+        assert_eq!(filled.iter().take(4).collect::<Vec<_>>(), vec![0, -1, 4, -1]);
+        let failing = div_arrays_with(&numerators, &divisors, DivPolicy::Fail);
+        assert_eq!(failing.try_get(2).unwrap(), 4);
+        assert!(matches!(failing.try_get(3), Err(Error::DivisionByZero { index: 3 })));
+
+        let ieee = div_scalar_with(Fill::new(1.0), 0.0, DivPolicy::Ieee);
+        assert_eq!(ieee.get(7), f64::INFINITY);
+        assert_eq!(div_scalar_with(Fill::new(1.0), 4.0, DivPolicy::Fail).try_get(0).unwrap(), 0.25);
+    }
+
+    #[test]
+    #[should_panic(expected = "element 2 overflows its type")]
+    fn test_cumsum_reports_overflow() {
+        let sums = try_cumsum(Fill::new(100u8));
+        assert_eq!(sums.try_get(1).unwrap(), 200);
+        assert!(matches!(sums.try_get(5), Err(Error::Overflow { index: 2 })));
+        sums.get(2);
+    }
+}
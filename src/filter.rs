@@ -0,0 +1,342 @@
+//! Lazy filtering views over infinite arrays
+
+use crate::arrays::{Indexed, InfiniteArray, InfiniteVector};
+use std::cell::RefCell;
+
+struct ScanState {
+    next: usize,
+    found: Vec<usize>,
+}
+
+/// Increasing indices at which a predicate holds, see [`argwhere`]
+pub struct ArgWhere<T, A, P> {
+    arr: A,
+    pred: P,
+    state: RefCell<ScanState>,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T, A, P> ArgWhere<T, A, P>
+where
+    A: InfiniteArray<T>,
+    P: Fn(&T) -> bool,
+{
+    /// The `k`-th matching index, scanning no further than index `limit`
+    ///
+    /// Returns `None` if fewer than `k + 1` matches occur before `limit`.
+    pub fn get_within(&self, k: usize, limit: usize) -> Option<usize> {
+        let mut state = self.state.borrow_mut();
+        while state.found.len() <= k && state.next < limit {
+            let i = state.next;
+            if (self.pred)(&self.arr.get(i)) {
+                state.found.push(i);
+            }
+            state.next += 1;
+        }
+        state.found.get(k).copied()
+    }
+
+    /// Number of elements of the source array scanned so far
+    pub fn scanned(&self) -> usize {
+        self.state.borrow().next
+    }
+}
+
+impl<T, A, P> InfiniteArray<usize> for ArgWhere<T, A, P>
+where
+    A: InfiniteArray<T>,
+    P: Fn(&T) -> bool,
+{
+    fn get(&self, index: usize) -> usize {
+        self.get_within(index, usize::MAX)
+            .expect("argwhere: fewer matches than requested")
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+}
+
+impl<T, A, P> InfiniteVector<usize> for ArgWhere<T, A, P>
+where
+    A: InfiniteArray<T>,
+    P: Fn(&T) -> bool,
+{
+}
+
+/// The increasing sequence of indices `i` with `pred(&arr[i])`
+///
+/// Matches found so far are cached together with the scan position, so
+/// `get(k)` resumes scanning from the last match instead of starting over.
+/// If only finitely many elements match, `get` past the last match never
+/// returns; use [`ArgWhere::get_within`] to bound the scan.
+pub fn argwhere<T, A, P>(arr: A, pred: P) -> ArgWhere<T, A, P>
+where
+    A: InfiniteArray<T>,
+    P: Fn(&T) -> bool,
+{
+    ArgWhere {
+        arr,
+        pred,
+        state: RefCell::new(ScanState {
+            next: 0,
+            found: Vec::new(),
+        }),
+        _phantom: std::marker::PhantomData,
+    }
+}
+
+fn is_set(flag: &bool) -> bool {
+    *flag
+}
+
+/// Elements of an array selected by a boolean mask, see [`compress`]
+pub struct Compress<V, M> {
+    values: V,
+    indices: ArgWhere<bool, M, fn(&bool) -> bool>,
+}
+
+impl<V, M> Compress<V, M>
+where
+    M: InfiniteArray<bool>,
+{
+    /// The `k`-th selected element, scanning the mask no further than `limit`
+    pub fn get_within<T>(&self, k: usize, limit: usize) -> Option<T>
+    where
+        V: InfiniteArray<T>,
+    {
+        self.indices.get_within(k, limit).map(|i| self.values.get(i))
+    }
+
+    /// The source indices of the selected elements
+    pub fn indices(&self) -> &ArgWhere<bool, M, fn(&bool) -> bool> {
+        &self.indices
+    }
+}
+
+impl<T, V, M> InfiniteArray<T> for Compress<V, M>
+where
+    V: InfiniteArray<T>,
+    M: InfiniteArray<bool>,
+{
+    fn get(&self, index: usize) -> T {
+        self.values.get(self.indices.get(index))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+}
+
+impl<T, V, M> InfiniteVector<T> for Compress<V, M>
+where
+    V: InfiniteArray<T>,
+    M: InfiniteArray<bool>,
+{
+}
+
+/// The lazy subsequence of `values` at positions where `mask` is true
+///
+/// Selected positions are tracked with [`argwhere`], so the same caching and
+/// termination caveats apply.
+pub fn compress<V, M>(values: V, mask: M) -> Compress<V, M>
+where
+    M: InfiniteArray<bool>,
+{
+    Compress {
+        values,
+        indices: argwhere(mask, is_set as fn(&bool) -> bool),
+    }
+}
+
+/// Cached boundaries of the runs of equal consecutive elements
+struct RunScanner<T> {
+    starts: Vec<usize>,
+    values: Vec<T>,
+    next: usize,
+}
+
+impl<T> RunScanner<T>
+where
+    T: PartialEq + Clone,
+{
+    fn new() -> Self {
+        Self {
+            starts: Vec::new(),
+            values: Vec::new(),
+            next: 0,
+        }
+    }
+
+    /// Scan until the starts of at least `count` runs are known
+    fn ensure<A: InfiniteArray<T>>(&mut self, arr: &A, count: usize) {
+        while self.starts.len() < count {
+            let value = arr.get(self.next);
+            if self.values.last() != Some(&value) {
+                self.starts.push(self.next);
+                self.values.push(value);
+            }
+            self.next += 1;
+        }
+    }
+}
+
+/// An array with runs of equal consecutive elements collapsed, see [`dedup`]
+pub struct Dedup<T, A> {
+    arr: A,
+    runs: RefCell<RunScanner<T>>,
+}
+
+impl<T, A> Dedup<T, A>
+where
+    T: PartialEq + Clone,
+    A: InfiniteArray<T>,
+{
+    /// Index in the source array where the `k`-th run starts
+    pub fn run_start(&self, k: usize) -> usize {
+        let mut runs = self.runs.borrow_mut();
+        runs.ensure(&self.arr, k + 1);
+        runs.starts[k]
+    }
+}
+
+impl<T, A> InfiniteArray<T> for Dedup<T, A>
+where
+    T: PartialEq + Clone,
+    A: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        let mut runs = self.runs.borrow_mut();
+        runs.ensure(&self.arr, index + 1);
+        runs.values[index].clone()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+}
+
+impl<T, A> InfiniteVector<T> for Dedup<T, A>
+where
+    T: PartialEq + Clone,
+    A: InfiniteArray<T>,
+{
+}
+
+/// Collapse runs of equal consecutive elements into single elements
+///
+/// Run boundaries are found by a cached forward scan. If the source ends in
+/// a constant tail, accessing the run after it never returns.
+pub fn dedup<T, A>(arr: A) -> Dedup<T, A>
+where
+    T: PartialEq + Clone,
+    A: InfiniteArray<T>,
+{
+    Dedup {
+        arr,
+        runs: RefCell::new(RunScanner::new()),
+    }
+}
+
+/// `(value, run_length)` pairs of an array, see [`run_lengths`]
+pub struct RunLengths<T, A> {
+    arr: A,
+    runs: RefCell<RunScanner<T>>,
+}
+
+impl<T, A> InfiniteArray<(T, usize)> for RunLengths<T, A>
+where
+    T: PartialEq + Clone,
+    A: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> (T, usize) {
+        let mut runs = self.runs.borrow_mut();
+        runs.ensure(&self.arr, index + 2);
+        (runs.values[index].clone(), runs.starts[index + 1] - runs.starts[index])
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (T, usize)> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+}
+
+impl<T, A> InfiniteVector<(T, usize)> for RunLengths<T, A>
+where
+    T: PartialEq + Clone,
+    A: InfiniteArray<T>,
+{
+}
+
+/// Run-length encoding of an array as `(value, run_length)` pairs
+///
+/// Shares its cached scan with [`dedup`]'s machinery; the length of run `k`
+/// is known once run `k + 1` has been found, so a run that never ends
+/// cannot be read.
+pub fn run_lengths<T, A>(arr: A) -> RunLengths<T, A>
+where
+    T: PartialEq + Clone,
+    A: InfiniteArray<T>,
+{
+    RunLengths {
+        arr,
+        runs: RefCell::new(RunScanner::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{InfiniteArrayFromFn, PaddedArray};
+
+    #[test]
+    fn test_argwhere() {
+        let squares = InfiniteArrayFromFn::new(|i| i * i);
+        let odd = argwhere(squares, |x| x % 2 == 1);
+        assert_eq!(odd.get(0), 1);
+        assert_eq!(odd.get(3), 7);
+        assert_eq!(odd.scanned(), 8);
+        assert_eq!(odd.get(1), 3);
+        assert_eq!(odd.scanned(), 8);
+    }
+
+    #[test]
+    fn test_argwhere_finite() {
+        let padded = PaddedArray::zero_padded(vec![0, 5, 0, 7]);
+        let nonzero = argwhere(padded, |&x| x != 0);
+        assert_eq!(nonzero.get_within(1, 1000), Some(3));
+        assert_eq!(nonzero.get_within(2, 1000), None);
+    }
+
+    #[test]
+    fn test_compress() {
+        let values = InfiniteArrayFromFn::new(|i| i * 10);
+        let mask = InfiniteArrayFromFn::new(|i| i % 3 == 0);
+        let selected = compress(values, mask);
+        assert_eq!(selected.get(0), 0);
+        assert_eq!(selected.get(2), 60);
+        assert_eq!(selected.indices().get(2), 6);
+
+        let sparse = compress(InfiniteArrayFromFn::new(|i| i), PaddedArray::new(vec![false, true], false));
+        assert_eq!(sparse.get_within(0, 100), Some(1));
+        assert_eq!(sparse.get_within(1, 100), None);
+    }
+
+    #[test]
+    fn test_dedup() {
+        // 0, 1, 1, 2, 2, 2, 3, 3, 3, 3, ...
+        let triangular = InfiniteArrayFromFn::new(|i| ((((8 * i + 1) as f64).sqrt() - 1.0) / 2.0) as usize);
+        let distinct = dedup(triangular);
+        assert_eq!(distinct.get(0), 0);
+        assert_eq!(distinct.get(4), 4);
+        assert_eq!(distinct.run_start(3), 6);
+        assert_eq!(distinct.run_start(4), 10);
+    }
+
+    #[test]
+    fn test_run_lengths() {
+        let arr = PaddedArray::new(vec!['a', 'a', 'b', 'c', 'c', 'c'], 'd');
+        let runs = run_lengths(arr);
+        let prefix: Vec<(char, usize)> = runs.iter().take(3).collect();
+        assert_eq!(prefix, vec![('a', 2), ('b', 1), ('c', 3)]);
+    }
+}
@@ -0,0 +1,276 @@
+//! Functions on an interval as Chebyshev coefficient arrays
+//!
+//! A [`Fun`] stores `f(x) = Σ cₖ Tₖ(x)` by its coefficient array, which is
+//! infinite in principle and zero beyond the point where it was chopped.
+//! Coefficients are found adaptively by sampling on Chebyshev grids of
+//! doubling size until the tail has decayed below the tolerance.
+
+use crate::arrays::{InfiniteArray, InfiniteVector};
+use crate::series::SeriesError;
+use num_traits::{Float, Zero};
+use std::ops::{Add, Mul, Neg, Sub};
+
+mod operators;
+
+pub use operators::{conversion, derivative, dirichlet_basis, integration, Band, CoefficientOperator};
+
+/// Smallest sampling grid tried by [`Fun::on`]
+const FIRST_GRID: usize = 17;
+
+/// Largest sampling grid tried by [`Fun::on`]
+const LAST_GRID: usize = 4097;
+
+/// A function on `[a, b]` as a chopped Chebyshev series
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fun<T> {
+    coefficients: Vec<T>,
+    domain: (T, T),
+    tol: T,
+}
+
+impl<T: Float> Fun<T> {
+    /// Resolve `f` on `[-1, 1]` to relative accuracy `tol`
+    pub fn new<F>(f: F, tol: T) -> Result<Self, SeriesError>
+    where
+        F: Fn(T) -> T,
+    {
+        Self::on(f, (-T::one(), T::one()), tol)
+    }
+
+    /// Resolve `f` on `[a, b]` to relative accuracy `tol`
+    ///
+    /// Grids of `2ᵏ + 1` Chebyshev points are tried from 17 points up to
+    /// 4097; a grid is accepted once its trailing eighth of coefficients is
+    /// below `tol` times the largest coefficient.
+    pub fn on<F>(f: F, domain: (T, T), tol: T) -> Result<Self, SeriesError>
+    where
+        F: Fn(T) -> T,
+    {
+        let (a, b) = domain;
+        let two = T::one() + T::one();
+        let mut n = FIRST_GRID;
+        loop {
+            let values: Vec<T> = chebyshev_points(n)
+                .into_iter()
+                .map(|x| f((b - a) / two * x + (a + b) / two))
+                .collect();
+            let coefficients = values_to_coefficients(&values);
+            let scale = max_abs(&coefficients);
+            let tail = (n / 8).max(2);
+            if coefficients[n - tail..].iter().all(|c| c.abs() <= tol * scale) {
+                return Ok(Self {
+                    coefficients: chop(coefficients, tol),
+                    domain,
+                    tol,
+                });
+            }
+            if n >= LAST_GRID {
+                return Err(SeriesError::BudgetExhausted { terms: n });
+            }
+            n = 2 * n - 1;
+        }
+    }
+
+    /// A function from its Chebyshev coefficients on `[a, b]`
+    pub fn from_coefficients(coefficients: Vec<T>, domain: (T, T), tol: T) -> Self {
+        Self {
+            coefficients: chop(coefficients, tol),
+            domain,
+            tol,
+        }
+    }
+
+    /// The coefficients kept after chopping
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+    /// The interval the function lives on
+    pub fn domain(&self) -> (T, T) {
+        self.domain
+    }
+
+    /// The relative tolerance used for chopping
+    pub fn tolerance(&self) -> T {
+        self.tol
+    }
+
+    /// Evaluate at `x` in the domain with Clenshaw's algorithm
+    pub fn evaluate(&self, x: T) -> T {
+        let (a, b) = self.domain;
+        let two = T::one() + T::one();
+        let t = (two * x - a - b) / (b - a);
+        let (mut b1, mut b2) = (T::zero(), T::zero());
+        for &c in self.coefficients.iter().skip(1).rev() {
+            let next = two * t * b1 - b2 + c;
+            b2 = b1;
+            b1 = next;
+        }
+        let c0 = self.coefficients.first().copied().unwrap_or_else(T::zero);
+        t * b1 - b2 + c0
+    }
+
+    fn combine(&self, other: &Self) -> (Vec<T>, (T, T), T) {
+        assert!(
+            self.domain == other.domain,
+            "Funs must share a domain to be combined"
+        );
+        let n = self.coefficients.len().max(other.coefficients.len());
+        let sum = (0..n).map(|k| self.get(k) + other.get(k)).collect();
+        (sum, self.domain, self.tol.max(other.tol))
+    }
+}
+
+/// Chebyshev points of the second kind `cos(jπ/(n-1))`, from 1 down to -1
+fn chebyshev_points<T: Float>(n: usize) -> Vec<T> {
+    let pi = T::from(std::f64::consts::PI).unwrap();
+    let m = T::from(n - 1).unwrap();
+    (0..n).map(|j| (T::from(j).unwrap() * pi / m).cos()).collect()
+}
+
+/// Chebyshev coefficients of the interpolant through values at
+/// [`chebyshev_points`], by a direct cosine transform
+fn values_to_coefficients<T: Float>(values: &[T]) -> Vec<T> {
+    let n = values.len();
+    let m = n - 1;
+    let pi = T::from(std::f64::consts::PI).unwrap();
+    let two = T::one() + T::one();
+    let mf = T::from(m).unwrap();
+    (0..n)
+        .map(|k| {
+            let sum = values.iter().enumerate().fold(T::zero(), |acc, (j, &v)| {
+                let weight = if j == 0 || j == m { T::one() / two } else { T::one() };
+                let angle = T::from((j * k) % (2 * m)).unwrap() * pi / mf;
+                acc + weight * v * angle.cos()
+            });
+            let c = two * sum / mf;
+            if k == 0 || k == m {
+                c / two
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+fn max_abs<T: Float>(values: &[T]) -> T {
+    values.iter().fold(T::zero(), |acc, c| acc.max(c.abs()))
+}
+
+/// Drop trailing coefficients below `tol` times the largest one
+fn chop<T: Float>(mut coefficients: Vec<T>, tol: T) -> Vec<T> {
+    let threshold = tol * max_abs(&coefficients);
+    let keep = coefficients
+        .iter()
+        .rposition(|c| c.abs() > threshold)
+        .map_or(0, |last| last + 1);
+    coefficients.truncate(keep.max(1));
+    coefficients
+}
+
+impl<T: Float> InfiniteArray<T> for Fun<T> {
+    fn get(&self, index: usize) -> T {
+        self.coefficients.get(index).copied().unwrap_or_else(T::zero)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(self.coefficients.iter().copied().chain(std::iter::repeat(T::zero())))
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        Some(self.coefficients.len())
+    }
+}
+
+impl<T: Float> InfiniteVector<T> for Fun<T> {}
+
+impl<T: Float> Add for Fun<T> {
+    type Output = Fun<T>;
+
+    fn add(self, other: Fun<T>) -> Fun<T> {
+        let (sum, domain, tol) = self.combine(&other);
+        Fun::from_coefficients(sum, domain, tol)
+    }
+}
+
+impl<T: Float> Neg for Fun<T> {
+    type Output = Fun<T>;
+
+    fn neg(mut self) -> Fun<T> {
+        self.coefficients.iter_mut().for_each(|c| *c = -*c);
+        self
+    }
+}
+
+impl<T: Float> Sub for Fun<T> {
+    type Output = Fun<T>;
+
+    fn sub(self, other: Fun<T>) -> Fun<T> {
+        self + (-other)
+    }
+}
+
+impl<T: Float> Mul for Fun<T> {
+    type Output = Fun<T>;
+
+    /// The product, using `Tₘ Tₙ = (T_{m+n} + T_{|m-n|}) / 2`
+    fn mul(self, other: Fun<T>) -> Fun<T> {
+        let (_, domain, tol) = self.combine(&other);
+        let (p, q) = (&self.coefficients, &other.coefficients);
+        let half = T::one() / (T::one() + T::one());
+        let mut product = vec![T::zero(); p.len() + q.len() - 1];
+        for (m, &a) in p.iter().enumerate() {
+            for (n, &b) in q.iter().enumerate() {
+                let term = a * b * half;
+                product[m + n] = product[m + n] + term;
+                product[m.abs_diff(n)] = product[m.abs_diff(n)] + term;
+            }
+        }
+        Fun::from_coefficients(product, domain, tol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fun_resolves_and_evaluates() {
+        let f = Fun::new(|x: f64| x.exp(), 1e-14).unwrap();
+        assert!(f.coefficients().len() < 20);
+        for &x in &[-1.0, -0.3, 0.0, 0.7, 1.0] {
+            assert!((f.evaluate(x) - x.exp()).abs() < 1e-13);
+        }
+        assert_eq!(f.get(1000), 0.0);
+        assert_eq!(f.support(), Some(f.coefficients().len()));
+
+        let g = Fun::on(|x: f64| x.ln(), (1.0, 3.0), 1e-13).unwrap();
+        assert!((g.evaluate(2.0) - 2f64.ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fun_arithmetic() {
+        let x = Fun::new(|x: f64| x, 1e-15).unwrap();
+        let square = x.clone() * x.clone();
+        assert_eq!(square.coefficients().len(), 3);
+        assert!((square.get(0) - 0.5).abs() < 1e-15 && (square.get(2) - 0.5).abs() < 1e-15);
+
+        let sin = Fun::new(|x: f64| x.sin(), 1e-14).unwrap();
+        let cos = Fun::new(|x: f64| x.cos(), 1e-14).unwrap();
+        let product = sin * cos;
+        let sum = product.clone() + x;
+        for &t in &[-0.9, 0.1, 0.5] {
+            assert!((product.evaluate(t) - (2.0 * t).sin() / 2.0).abs() < 1e-13);
+            assert!((sum.evaluate(t) - ((2.0 * t).sin() / 2.0 + t)).abs() < 1e-13);
+        }
+    }
+
+    #[test]
+    fn test_fun_unresolved() {
+        let result = Fun::new(|x: f64| x.abs().sqrt(), 1e-15);
+        assert_eq!(result, Err(SeriesError::BudgetExhausted { terms: 4097 }));
+    }
+}
@@ -0,0 +1,134 @@
+//! Banded operators on Chebyshev and ultraspherical coefficient arrays
+//!
+//! Differentiating a Chebyshev series `Σ cₖ Tₖ` is dense in the Chebyshev
+//! basis but banded when the result is expressed in the ultraspherical
+//! basis `C^{(λ)}`, and the conversions `C^{(λ)} → C^{(λ+1)}` are banded as
+//! well. A differential equation of order `λ` is therefore assembled in
+//! `C^{(λ)}` coefficients, with lower-order terms converted up, and solved
+//! with [`solve`](crate::linalg::solve). All operators act on `[-1, 1]`; on
+//! `[a, b]` the `λ`-th derivative picks up a factor `(2 / (b - a))^λ`.
+
+use crate::arrays::{Fill, InfiniteArray, InfiniteArrayFromFn};
+use crate::matrix::BandedMatrix;
+use num_traits::Float;
+use std::rc::Rc;
+
+/// A shared diagonal of a [`CoefficientOperator`]
+pub type Band<T> = Rc<dyn InfiniteArray<T>>;
+
+/// A banded operator on coefficient arrays
+pub type CoefficientOperator<T> = BandedMatrix<Band<T>>;
+
+fn zero_band<T: Float + 'static>() -> Band<T> {
+    Rc::new(Fill::new(T::zero()))
+}
+
+fn band<T, F>(f: F) -> Band<T>
+where
+    T: Float + 'static,
+    F: Fn(usize) -> T + 'static,
+{
+    Rc::new(InfiniteArrayFromFn::new(f))
+}
+
+/// The `order`-th derivative, from Chebyshev `T` coefficients to
+/// `C^{(order)}` coefficients
+///
+/// Uses `dᵏ/dxᵏ Tₙ = 2ᵏ⁻¹ (k - 1)! n C^{(k)}_{n-k}`, so the only nonzero
+/// band is the `order`-th superdiagonal. Order zero is the identity.
+pub fn derivative<T: Float + 'static>(order: usize) -> CoefficientOperator<T> {
+    if order == 0 {
+        return BandedMatrix::new(0, 0, vec![band(|_| T::one())]);
+    }
+    let scale = (1..order).fold(T::one(), |acc, k| acc * T::from(2 * k).unwrap());
+    let mut bands: Vec<Band<T>> = (0..order).map(|_| zero_band()).collect();
+    bands.push(band(move |i| scale * T::from(i + order).unwrap()));
+    BandedMatrix::new(0, order, bands)
+}
+
+/// Conversion from `C^{(λ)}` to `C^{(λ+1)}` coefficients, where `λ = 0`
+/// stands for the Chebyshev `T` basis
+///
+/// Upper triangular with bandwidths `(0, 2)`.
+pub fn conversion<T: Float + 'static>(lambda: usize) -> CoefficientOperator<T> {
+    let half = T::from(0.5).unwrap();
+    let (main, sup): (Band<T>, Band<T>) = if lambda == 0 {
+        (band(move |i| if i == 0 { T::one() } else { half }), band(move |_| -half))
+    } else {
+        let l = T::from(lambda).unwrap();
+        (
+            band(move |i| l / (l + T::from(i).unwrap())),
+            band(move |i| -l / (l + T::from(i + 2).unwrap())),
+        )
+    };
+    BandedMatrix::new(0, 2, vec![main, zero_band(), sup])
+}
+
+/// Indefinite integration of Chebyshev `T` coefficients
+///
+/// Uses `∫T₀ = T₁`, `∫T₁ = T₂ / 4` and
+/// `∫Tₙ = T_{n+1} / (2(n + 1)) - T_{n-1} / (2(n - 1))`, with the constant
+/// of integration chosen so that row zero vanishes. Bandwidths `(1, 1)`.
+pub fn integration<T: Float + 'static>() -> CoefficientOperator<T> {
+    let two = T::from(2).unwrap();
+    let sub = band(move |j| if j == 0 { T::one() } else { T::one() / (two * T::from(j + 1).unwrap()) });
+    let sup = band(move |i| if i == 0 { T::zero() } else { -T::one() / (two * T::from(i).unwrap()) });
+    BandedMatrix::new(1, 1, vec![sub, zero_band(), sup])
+}
+
+/// Chebyshev `T` coefficients of a series in the basis
+/// `φₙ = T_{n+2} - Tₙ`, whose members all vanish at `±1`
+///
+/// Solving for coefficients in this basis imposes homogeneous Dirichlet
+/// conditions while keeping the system banded. Bandwidths `(2, 0)`.
+pub fn dirichlet_basis<T: Float + 'static>() -> CoefficientOperator<T> {
+    BandedMatrix::new(2, 0, vec![band(|_| T::one()), zero_band(), band(|_| -T::one())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::PaddedArray;
+    use crate::fun::Fun;
+    use crate::linalg::solve;
+    use crate::matrix::InfiniteMatrix;
+
+    #[test]
+    fn test_operators_on_basis_vectors() {
+        // T₃' = 3 U₂ and T₃'' = 6 C^{(2)}_1
+        assert_eq!(derivative::<f64>(1).get(2, 3), 3.0);
+        assert_eq!(derivative::<f64>(2).get(1, 3), 6.0);
+        assert_eq!(derivative::<f64>(2).bandwidths(), Some((0, 2)));
+
+        // T₂ = (U₂ - U₀) / 2 and U₂ = (C^{(2)}_2 - C^{(2)}_0) / 3
+        let s0 = conversion::<f64>(0);
+        assert_eq!((s0.get(0, 2), s0.get(2, 2)), (-0.5, 0.5));
+        let s1 = conversion::<f64>(1);
+        assert!((s1.get(0, 2) + 1.0 / 3.0).abs() < 1e-15 && (s1.get(2, 2) - 1.0 / 3.0).abs() < 1e-15);
+
+        // ∫T₂ = T₃ / 6 - T₁ / 2
+        let q = integration::<f64>();
+        assert!((q.get(3, 2) - 1.0 / 6.0).abs() < 1e-15);
+        assert_eq!(q.get(1, 2), -0.5);
+        assert_eq!(q.get(1, 0), 1.0);
+    }
+
+    #[test]
+    fn test_dirichlet_poisson() {
+        // u'' = -(π/2)² cos(πx/2) with u(±1) = 0 has u = cos(πx/2)
+        let k = std::f64::consts::FRAC_PI_2;
+        let f = Fun::new(|x: f64| -k * k * (k * x).cos(), 1e-15).unwrap();
+        let convert = conversion::<f64>(1) * conversion(0);
+        let n = f.coefficients().len();
+        let rhs = PaddedArray::zero_padded((0..n).map(|i| (0..n).map(|j| convert.get(i, j) * f.get(j)).sum()).collect());
+
+        let a = solve(derivative(2) * dirichlet_basis(), &rhs, 1e-13).unwrap();
+        let basis = dirichlet_basis::<f64>();
+        let size = a.size();
+        let coefficients = (0..size + 2).map(|i| (0..size).map(|j| basis.get(i, j) * a.get(j)).sum()).collect();
+        let u = Fun::from_coefficients(coefficients, (-1.0, 1.0), 1e-15);
+        for &x in &[-1.0, -0.4, 0.0, 0.8, 1.0] {
+            assert!((u.evaluate(x) - (k * x).cos()).abs() < 1e-12);
+        }
+    }
+}
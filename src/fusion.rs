@@ -0,0 +1,210 @@
+//! Fusing chains of scalar operations into one evaluation
+//!
+//! Each of [`add_scalar`](crate::add_scalar) and
+//! [`mul_scalar`](crate::mul_scalar) wraps its operand in another closure,
+//! so a chain of them calls one closure per level for every element.
+//! [`Affine`] instead keeps a chain in the normal form `scale * x + offset`:
+//! every scalar addition, subtraction, multiplication or negation is
+//! rewritten into new coefficients when it is applied, and an element costs
+//! one read of the base array, one multiplication and one addition however
+//! long the chain was.
+//!
+//! Rewriting reassociates the arithmetic, so floating-point results can
+//! differ from the unfused chain in the last bits.
+
+use crate::arrays::InfiniteArray;
+use crate::describe::Description;
+use num_traits::{One, Zero};
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A scalar operation applied to every element
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScalarOp<T> {
+    Add(T),
+    Sub(T),
+    Mul(T),
+    Neg,
+}
+
+/// `scale * base[i] + offset`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Affine<T, A> {
+    base: A,
+    scale: T,
+    offset: T,
+}
+
+/// Start a fused chain of scalar operations on `base`
+pub fn fuse<T, A>(base: A) -> Affine<T, A>
+where
+    T: Zero + One,
+{
+    Affine {
+        base,
+        scale: T::one(),
+        offset: T::zero(),
+    }
+}
+
+/// Apply `ops` in order to every element of `base`, fused into one [`Affine`]
+pub fn fuse_chain<T, A, I>(base: A, ops: I) -> Affine<T, A>
+where
+    T: Zero + One + Clone + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Neg<Output = T>,
+    I: IntoIterator<Item = ScalarOp<T>>,
+{
+    ops.into_iter().fold(fuse(base), Affine::then)
+}
+
+impl<T, A> Affine<T, A>
+where
+    T: Clone + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Neg<Output = T>,
+{
+    /// Rewrite `op` applied after this chain into new coefficients
+    pub fn then(self, op: ScalarOp<T>) -> Self {
+        let (scale, offset) = match op {
+            ScalarOp::Add(c) => (self.scale, self.offset + c),
+            ScalarOp::Sub(c) => (self.scale, self.offset - c),
+            ScalarOp::Mul(c) => (self.scale * c.clone(), self.offset * c),
+            ScalarOp::Neg => (-self.scale, -self.offset),
+        };
+        Self {
+            base: self.base,
+            scale,
+            offset,
+        }
+    }
+
+    pub fn add_scalar(self, c: T) -> Self {
+        self.then(ScalarOp::Add(c))
+    }
+
+    pub fn sub_scalar(self, c: T) -> Self {
+        self.then(ScalarOp::Sub(c))
+    }
+
+    pub fn mul_scalar(self, c: T) -> Self {
+        self.then(ScalarOp::Mul(c))
+    }
+}
+
+impl<T, A> Affine<T, A> {
+    pub fn scale(&self) -> &T {
+        &self.scale
+    }
+
+    pub fn offset(&self) -> &T {
+        &self.offset
+    }
+
+    pub fn base(&self) -> &A {
+        &self.base
+    }
+}
+
+macro_rules! affine_ops {
+    ($($trait:ident, $method:ident, $variant:ident;)*) => {$(
+        impl<T, A> $trait<T> for Affine<T, A>
+        where
+            T: Clone + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Neg<Output = T>,
+        {
+            type Output = Self;
+
+            fn $method(self, c: T) -> Self {
+                self.then(ScalarOp::$variant(c))
+            }
+        }
+    )*};
+}
+
+affine_ops! {
+    Add, add, Add;
+    Sub, sub, Sub;
+    Mul, mul, Mul;
+}
+
+impl<T, A> Neg for Affine<T, A>
+where
+    T: Clone + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Neg<Output = T>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.then(ScalarOp::Neg)
+    }
+}
+
+impl<T, A> InfiniteArray<T> for Affine<T, A>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T>,
+    A: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        self.scale.clone() * self.base.get(index) + self.offset.clone()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(self.base.iter().map(move |x| self.scale.clone() * x + self.offset.clone()))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.base.len()
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        if self.offset.is_zero() {
+            self.base.support()
+        } else {
+            None
+        }
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        self.base.periodicity()
+    }
+
+    fn get_range(&self, start: usize, out: &mut [T]) {
+        self.base.get_range(start, out);
+        for x in out.iter_mut() {
+            *x = self.scale.clone() * x.clone() + self.offset.clone();
+        }
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("affine", [self.base.describe()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{InfiniteArrayFromFn, PaddedArray};
+    use crate::operations::{add_scalar, mul_scalar};
+
+    #[test]
+    fn test_fused_chain_matches_unfused() {
+        let x = InfiniteArrayFromFn::new(|i| i as i64);
+        let inner = add_scalar(&x, 3);
+        let unfused = mul_scalar(&inner, -2);
+        let fused = (fuse(&x) + 3) * -2;
+        assert_eq!((*fused.scale(), *fused.offset()), (-2, -6));
+        assert!((0..50).all(|i| fused.get(i) == unfused.get(i)));
+        assert_eq!(format!("{:?}", fused.describe()), "affine(from_fn)");
+
+        let chained = fuse_chain(&x, [ScalarOp::Sub(1), ScalarOp::Neg, ScalarOp::Mul(5), ScalarOp::Add(2)]);
+        assert_eq!(chained.get(10), -(10 - 1) * 5 + 2);
+    }
+
+    #[test]
+    fn test_affine_structure() {
+        let padded = PaddedArray::new(vec![1.0, 2.0], 0.0);
+        let scaled = fuse(&padded).mul_scalar(4.0);
+        assert_eq!(scaled.support(), Some(2));
+        assert_eq!(scaled.add_scalar(1.0).support(), None);
+        let mut out = [0.0; 3];
+        fuse(&padded).sub_scalar(0.5).get_range(1, &mut out);
+        assert_eq!(out, [1.5, -0.5, -0.5]);
+    }
+}
@@ -0,0 +1,337 @@
+//! Evaluating element-wise expressions over large index ranges on the GPU
+//!
+//! A [`GpuExpr`] is a small expression graph in the index `i` built from
+//! constants, ranges and scalar arithmetic. It is an [`InfiniteArray`] of
+//! `f32` evaluated on the CPU, and [`GpuEvaluator::evaluate`] compiles it
+//! into a WGSL compute shader that fills a finite prefix in one pass.
+
+use crate::arrays::{Indexed, InfiniteArray};
+use std::fmt;
+use std::future::Future;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+/// Threads per workgroup in the generated shader
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Most elements computed by one dispatch
+const MAX_BATCH: usize = 65_535 * WORKGROUP_SIZE as usize;
+
+/// An element-wise expression in the index `i`
+#[derive(Debug, Clone, PartialEq)]
+pub enum GpuExpr {
+    /// The index itself, as an `f32`
+    Index,
+    /// The same value everywhere
+    Constant(f32),
+    Neg(Box<GpuExpr>),
+    Add(Box<GpuExpr>, Box<GpuExpr>),
+    Sub(Box<GpuExpr>, Box<GpuExpr>),
+    Mul(Box<GpuExpr>, Box<GpuExpr>),
+    Div(Box<GpuExpr>, Box<GpuExpr>),
+}
+
+impl GpuExpr {
+    /// The index `i`
+    pub fn index() -> Self {
+        GpuExpr::Index
+    }
+
+    /// `value` at every index
+    pub fn fill(value: f32) -> Self {
+        GpuExpr::Constant(value)
+    }
+
+    /// `start + step * i`
+    pub fn range(start: f32, step: f32) -> Self {
+        GpuExpr::Constant(start) + GpuExpr::Constant(step) * GpuExpr::Index
+    }
+
+    /// The value at index `i`, computed on the CPU
+    pub fn eval(&self, i: usize) -> f32 {
+        match self {
+            GpuExpr::Index => i as f32,
+            GpuExpr::Constant(c) => *c,
+            GpuExpr::Neg(a) => -a.eval(i),
+            GpuExpr::Add(a, b) => a.eval(i) + b.eval(i),
+            GpuExpr::Sub(a, b) => a.eval(i) - b.eval(i),
+            GpuExpr::Mul(a, b) => a.eval(i) * b.eval(i),
+            GpuExpr::Div(a, b) => a.eval(i) / b.eval(i),
+        }
+    }
+
+    /// The expression in WGSL, reading the index from a variable `i: f32`
+    ///
+    /// WGSL has no literals for infinities and NaN, so a non-finite constant
+    /// is a [`GpuError::NonFinite`].
+    pub fn to_wgsl(&self) -> Result<String, GpuError> {
+        Ok(match self {
+            GpuExpr::Index => "i".to_string(),
+            GpuExpr::Constant(c) if !c.is_finite() => return Err(GpuError::NonFinite(*c)),
+            GpuExpr::Constant(c) => format!("{:?}", c),
+            GpuExpr::Neg(a) => format!("(-({}))", a.to_wgsl()?),
+            GpuExpr::Add(a, b) => format!("({} + {})", a.to_wgsl()?, b.to_wgsl()?),
+            GpuExpr::Sub(a, b) => format!("({} - {})", a.to_wgsl()?, b.to_wgsl()?),
+            GpuExpr::Mul(a, b) => format!("({} * {})", a.to_wgsl()?, b.to_wgsl()?),
+            GpuExpr::Div(a, b) => format!("({} / {})", a.to_wgsl()?, b.to_wgsl()?),
+        })
+    }
+
+    /// A compute shader writing the expression at `params.start + id` into
+    /// `output[id]` for every `id < params.count`
+    pub fn shader(&self) -> Result<String, GpuError> {
+        Ok(format!(
+            "struct Params {{ start: u32, count: u32 }}\n\
+             @group(0) @binding(0) var<storage, read_write> output: array<f32>;\n\
+             @group(0) @binding(1) var<uniform> params: Params;\n\
+             @compute @workgroup_size({})\n\
+             fn main(@builtin(global_invocation_id) id: vec3<u32>) {{\n\
+             \x20   if (id.x >= params.count) {{ return; }}\n\
+             \x20   let i = f32(params.start + id.x);\n\
+             \x20   output[id.x] = {};\n\
+             }}\n",
+            WORKGROUP_SIZE,
+            self.to_wgsl()?
+        ))
+    }
+}
+
+macro_rules! gpu_expr_ops {
+    ($($trait:ident, $method:ident, $variant:ident;)*) => {$(
+        impl $trait for GpuExpr {
+            type Output = GpuExpr;
+
+            fn $method(self, rhs: GpuExpr) -> GpuExpr {
+                GpuExpr::$variant(Box::new(self), Box::new(rhs))
+            }
+        }
+
+        impl $trait<f32> for GpuExpr {
+            type Output = GpuExpr;
+
+            fn $method(self, rhs: f32) -> GpuExpr {
+                GpuExpr::$variant(Box::new(self), Box::new(GpuExpr::Constant(rhs)))
+            }
+        }
+    )*};
+}
+
+gpu_expr_ops! {
+    Add, add, Add;
+    Sub, sub, Sub;
+    Mul, mul, Mul;
+    Div, div, Div;
+}
+
+impl Neg for GpuExpr {
+    type Output = GpuExpr;
+
+    fn neg(self) -> GpuExpr {
+        GpuExpr::Neg(Box::new(self))
+    }
+}
+
+impl InfiniteArray<f32> for GpuExpr {
+    fn get(&self, index: usize) -> f32 {
+        self.eval(index)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = f32> + '_> {
+        Box::new(Indexed::new(move |i| self.eval(i)))
+    }
+}
+
+/// Errors from setting up or running a GPU evaluation
+#[derive(Debug, Clone, PartialEq)]
+pub enum GpuError {
+    /// No suitable adapter was found
+    NoAdapter(String),
+    /// The adapter refused to create a device
+    Device(String),
+    /// The indices do not fit the shader's 32-bit index
+    IndexRange,
+    /// A constant is infinite or NaN, which WGSL cannot express
+    NonFinite(f32),
+    /// Reading the results back failed
+    Readback(String),
+}
+
+impl fmt::Display for GpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuError::NoAdapter(reason) => write!(f, "no GPU adapter: {}", reason),
+            GpuError::Device(reason) => write!(f, "could not create GPU device: {}", reason),
+            GpuError::IndexRange => write!(f, "indices must be below 2^32 for GPU evaluation"),
+            GpuError::NonFinite(value) => write!(f, "constant {} cannot be written in WGSL", value),
+            GpuError::Readback(reason) => write!(f, "could not read GPU results: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for GpuError {}
+
+/// A GPU device ready to evaluate [`GpuExpr`]s
+pub struct GpuEvaluator {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+}
+
+impl GpuEvaluator {
+    /// Open the default adapter
+    pub fn new() -> Result<Self, GpuError> {
+        let instance = wgpu::Instance::default();
+        let adapter = block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+            .map_err(|e| GpuError::NoAdapter(e.to_string()))?;
+        let (device, queue) = block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+            .map_err(|e| GpuError::Device(e.to_string()))?;
+        Ok(Self { device, queue })
+    }
+
+    /// Elements `start..start + n` of `expr`, computed on the GPU
+    pub fn evaluate(&self, expr: &GpuExpr, start: usize, n: usize) -> Result<Vec<f32>, GpuError> {
+        if start.checked_add(n).is_none_or(|end| end > u32::MAX as usize + 1) {
+            return Err(GpuError::IndexRange);
+        }
+        let module = self.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("infinite-arrays expression"),
+            source: wgpu::ShaderSource::Wgsl(expr.shader()?.into()),
+        });
+        let pipeline = self.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        let mut values = Vec::with_capacity(n);
+        let mut offset = 0;
+        while offset < n {
+            let count = (n - offset).min(MAX_BATCH);
+            values.extend(self.dispatch(&pipeline, (start + offset) as u32, count)?);
+            offset += count;
+        }
+        Ok(values)
+    }
+
+    fn dispatch(&self, pipeline: &wgpu::ComputePipeline, start: u32, count: usize) -> Result<Vec<f32>, GpuError> {
+        use wgpu::util::DeviceExt;
+
+        let size = (count * std::mem::size_of::<f32>()) as wgpu::BufferAddress;
+        let output = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let params: Vec<u8> = [start, count as u32].iter().flat_map(|x| x.to_le_bytes()).collect();
+        let params = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &params,
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: output.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: params.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups((count as u32).div_ceil(WORKGROUP_SIZE), 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&output, 0, &readback, 0, size);
+        self.queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|e| GpuError::Readback(e.to_string()))?;
+        receiver
+            .recv()
+            .map_err(|e| GpuError::Readback(e.to_string()))?
+            .map_err(|e| GpuError::Readback(e.to_string()))?;
+        let bytes = slice.get_mapped_range().map_err(|e| GpuError::Readback(format!("{:?}", e)))?;
+        Ok(bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect())
+    }
+}
+
+/// Wakes the thread waiting in [`block_on`]
+struct ThreadWaker(std::thread::Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run a future to completion on the current thread
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpu_expr_cpu_and_wgsl() {
+        let expr = (GpuExpr::range(1.0, 0.5) * GpuExpr::index() - GpuExpr::fill(2.0)) / 4.0;
+        assert_eq!(expr.get(4), ((1.0 + 0.5 * 4.0) * 4.0 - 2.0) / 4.0);
+        assert_eq!(expr.to_wgsl().unwrap(), "((((1.0 + (0.5 * i)) * i) - 2.0) / 4.0)");
+        assert!(expr.shader().unwrap().contains("output[id.x] = ((((1.0 + (0.5 * i)) * i) - 2.0) / 4.0);"));
+        assert_eq!((-GpuExpr::fill(-2.0)).to_wgsl().unwrap(), "(-(-2.0))");
+        let infinite = GpuExpr::index() / GpuExpr::fill(f32::INFINITY);
+        assert_eq!(infinite.to_wgsl(), Err(GpuError::NonFinite(f32::INFINITY)));
+        assert_eq!(infinite.get(3), 0.0);
+        assert_eq!(GpuExpr::fill(f32::NAN).shader().unwrap_err().to_string(), "constant NaN cannot be written in WGSL");
+    }
+
+    #[test]
+    fn test_gpu_evaluate_matches_cpu() {
+        // Sandboxes and CI machines often have no GPU at all
+        let gpu = match GpuEvaluator::new() {
+            Ok(gpu) => gpu,
+            Err(_) => return,
+        };
+        let expr = -GpuExpr::range(3.0, 0.25) * GpuExpr::index();
+        let values = gpu.evaluate(&expr, 1000, 300).unwrap();
+        assert_eq!(values.len(), 300);
+        for (k, value) in values.iter().enumerate() {
+            assert!((value - expr.get(1000 + k)).abs() <= 1e-3 * value.abs());
+        }
+        assert_eq!(gpu.evaluate(&expr, u32::MAX as usize, 2), Err(GpuError::IndexRange));
+    }
+}
@@ -0,0 +1,180 @@
+//! An unbounded two-dimensional grid of cells
+//!
+//! A grid is a constant background with finitely many cells overridden.
+//! Local update rules map such a grid to another one, since only cells next
+//! to an override can see anything but the background.
+
+use std::collections::{HashMap, HashSet};
+
+/// Offsets of the eight cells surrounding a cell, row by row
+pub const MOORE: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+/// An infinite grid indexed by `(x, y)`, equal to a background value except
+/// at finitely many cells
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfiniteGrid<T> {
+    background: T,
+    cells: HashMap<(isize, isize), T>,
+}
+
+impl<T: Clone + PartialEq> InfiniteGrid<T> {
+    /// A grid with every cell equal to `background`
+    pub fn new(background: T) -> Self {
+        Self {
+            background,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// The value of every cell that is not overridden
+    pub fn background(&self) -> &T {
+        &self.background
+    }
+
+    /// The value at `(x, y)`
+    pub fn get(&self, x: isize, y: isize) -> T {
+        self.cells.get(&(x, y)).unwrap_or(&self.background).clone()
+    }
+
+    /// Set the value at `(x, y)`; setting the background value removes the
+    /// override
+    pub fn set(&mut self, x: isize, y: isize, value: T) {
+        if value == self.background {
+            self.cells.remove(&(x, y));
+        } else {
+            self.cells.insert((x, y), value);
+        }
+    }
+
+    /// Number of cells that differ from the background
+    pub fn overrides(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The cells that differ from the background, in no particular order
+    pub fn cells(&self) -> impl Iterator<Item = ((isize, isize), &T)> + '_ {
+        self.cells.iter().map(|(&position, value)| (position, value))
+    }
+
+    /// Smallest and largest corner of the box containing every override
+    pub fn bounds(&self) -> Option<((isize, isize), (isize, isize))> {
+        self.cells.keys().fold(None, |bounds, &(x, y)| match bounds {
+            None => Some(((x, y), (x, y))),
+            Some(((x0, y0), (x1, y1))) => Some(((x0.min(x), y0.min(y)), (x1.max(x), y1.max(y)))),
+        })
+    }
+
+    /// The eight neighbours of `(x, y)` with their positions, in the order
+    /// of [`MOORE`], read lazily
+    pub fn neighbors(&self, x: isize, y: isize) -> impl Iterator<Item = ((isize, isize), T)> + '_ {
+        MOORE.iter().map(move |&(dx, dy)| ((x + dx, y + dy), self.get(x + dx, y + dy)))
+    }
+
+    /// How many of the eight neighbours of `(x, y)` satisfy `pred`
+    pub fn count_neighbors<P>(&self, x: isize, y: isize, pred: P) -> usize
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.neighbors(x, y).filter(|(_, value)| pred(value)).count()
+    }
+
+    /// Apply a local rule to every cell at once
+    ///
+    /// `rule` receives a cell and its neighbours in the order of [`MOORE`].
+    /// It is evaluated once on the background to get the new background,
+    /// and then only on overridden cells and their neighbours.
+    pub fn step<F>(&self, rule: F) -> Self
+    where
+        F: Fn(&T, &[T; 8]) -> T,
+    {
+        let neighborhood = |x: isize, y: isize| MOORE.map(|(dx, dy)| self.get(x + dx, y + dy));
+        let background = rule(&self.background, &std::array::from_fn(|_| self.background.clone()));
+        let candidates: HashSet<(isize, isize)> = self
+            .cells
+            .keys()
+            .flat_map(|&(x, y)| MOORE.iter().map(move |&(dx, dy)| (x + dx, y + dy)).chain(std::iter::once((x, y))))
+            .collect();
+        let mut next = Self::new(background);
+        for (x, y) in candidates {
+            let value = rule(&self.get(x, y), &neighborhood(x, y));
+            next.set(x, y, value);
+        }
+        next
+    }
+
+    /// Apply [`step`](Self::step) `n` times
+    pub fn steps<F>(&self, n: usize, rule: F) -> Self
+    where
+        F: Fn(&T, &[T; 8]) -> T,
+    {
+        (0..n).fold(self.clone(), |grid, _| grid.step(&rule))
+    }
+}
+
+impl InfiniteGrid<bool> {
+    /// One generation of Conway's Game of Life
+    pub fn life_step(&self) -> Self {
+        self.step(|&alive, neighbors| {
+            let count = neighbors.iter().filter(|&&n| n).count();
+            count == 3 || (alive && count == 2)
+        })
+    }
+}
+
+impl<T: Clone + PartialEq + Default> FromIterator<((isize, isize), T)> for InfiniteGrid<T> {
+    /// A grid on the default background with the given cells set
+    fn from_iter<I: IntoIterator<Item = ((isize, isize), T)>>(cells: I) -> Self {
+        let mut grid = Self::new(T::default());
+        for ((x, y), value) in cells {
+            grid.set(x, y, value);
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alive(cells: &[(isize, isize)]) -> InfiniteGrid<bool> {
+        cells.iter().map(|&position| (position, true)).collect()
+    }
+
+    #[test]
+    fn test_grid_overrides() {
+        let mut grid = InfiniteGrid::new(0u8);
+        grid.set(-5, 7, 3);
+        grid.set(2, -1, 1);
+        assert_eq!(grid.get(-5, 7), 3);
+        assert_eq!(grid.get(1_000_000, -1_000_000), 0);
+        assert_eq!(grid.bounds(), Some(((-5, -1), (2, 7))));
+        assert_eq!(grid.count_neighbors(-4, 6, |&v| v > 0), 1);
+        grid.set(-5, 7, 0);
+        assert_eq!(grid.overrides(), 1);
+    }
+
+    #[test]
+    fn test_life_blinker_and_glider() {
+        let blinker = alive(&[(-1, 0), (0, 0), (1, 0)]);
+        assert_eq!(blinker.life_step(), alive(&[(0, -1), (0, 0), (0, 1)]));
+        assert_eq!(blinker.life_step().life_step(), blinker);
+
+        // A glider moves one cell diagonally every four generations
+        let glider = alive(&[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+        let moved = (0..4).fold(glider, |grid, _| grid.life_step());
+        assert_eq!(moved, alive(&[(2, 1), (3, 2), (1, 3), (2, 3), (3, 3)]));
+    }
+
+    #[test]
+    fn test_step_evolves_background() {
+        // Every cell counts its neighbours above zero, so the background
+        // becomes 8 and cells near the single override see fewer
+        let mut grid = InfiniteGrid::new(1u32);
+        grid.set(0, 0, 0);
+        let next = grid.steps(1, |_, neighbors| neighbors.iter().filter(|&&n| n > 0).count() as u32);
+        assert_eq!(*next.background(), 8);
+        assert_eq!(next.get(1, 1), 7);
+        assert_eq!(next.get(0, 0), 8);
+        assert_eq!(next.overrides(), 8);
+    }
+}
@@ -0,0 +1,274 @@
+//! Identification of closed-form structure from a prefix of an array
+
+use crate::arrays::{Indexed, InfiniteArray, InfiniteVector};
+use num_traits::Float;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A closed-form description of a sequence, usable as an infinite array
+#[derive(Debug, Clone, PartialEq)]
+pub enum Structure<T> {
+    /// `a[i] = start + step·i`
+    Arithmetic { start: T, step: T },
+    /// `a[i] = start·ratioⁱ`
+    Geometric { start: T, ratio: T },
+    /// `a[i] = Σ_k C(i, k) Δᵏa[0]`, stored as the leading forward differences
+    Polynomial { differences: Vec<T> },
+    /// `a[i] = Σ_j coefficients[j]·a[i-1-j]`, seeded with `initial`
+    LinearRecurrence { coefficients: Vec<T>, initial: Vec<T> },
+}
+
+impl<T: Float> Structure<T> {
+    /// Degree of the polynomial, if the structure is polynomial
+    pub fn degree(&self) -> Option<usize> {
+        match self {
+            Structure::Arithmetic { step, .. } if *step == T::zero() => Some(0),
+            Structure::Arithmetic { .. } => Some(1),
+            Structure::Polynomial { differences } => Some(differences.len() - 1),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Float> InfiniteArray<T> for Structure<T> {
+    fn get(&self, index: usize) -> T {
+        match self {
+            Structure::Arithmetic { start, step } => *start + *step * T::from(index).unwrap(),
+            Structure::Geometric { start, ratio } => {
+                let power = match i32::try_from(index) {
+                    Ok(n) => ratio.powi(n),
+                    Err(_) => ratio.powf(T::from(index).unwrap()),
+                };
+                *start * power
+            }
+            Structure::Polynomial { differences } => {
+                // Newton forward form, accumulating C(index, k) incrementally
+                let mut binomial = T::one();
+                let mut acc = T::zero();
+                for (k, d) in differences.iter().enumerate() {
+                    acc = acc + binomial * *d;
+                    binomial = binomial * T::from(index as f64 - k as f64).unwrap()
+                        / T::from(k + 1).unwrap();
+                }
+                acc
+            }
+            Structure::LinearRecurrence { coefficients, initial } => {
+                if index < initial.len() {
+                    return initial[index];
+                }
+                let mut window: VecDeque<T> = initial.iter().copied().collect();
+                for _ in initial.len()..=index {
+                    let next = coefficients
+                        .iter()
+                        .zip(window.iter().rev())
+                        .fold(T::zero(), |acc, (c, a)| acc + *c * *a);
+                    window.pop_front();
+                    window.push_back(next);
+                }
+                window[window.len() - 1]
+            }
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+}
+
+impl<T: Float> InfiniteVector<T> for Structure<T> {}
+
+impl<T: fmt::Display> fmt::Display for Structure<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Structure::Arithmetic { start, step } => write!(f, "a[i] = {} + {}·i", start, step),
+            Structure::Geometric { start, ratio } => write!(f, "a[i] = {}·{}^i", start, ratio),
+            Structure::Polynomial { differences } => {
+                write!(f, "a[i] =")?;
+                for (k, d) in differences.iter().enumerate() {
+                    let sep = if k == 0 { " " } else { " + " };
+                    write!(f, "{}{}·C(i, {})", sep, d, k)?;
+                }
+                Ok(())
+            }
+            Structure::LinearRecurrence { coefficients, .. } => {
+                write!(f, "a[i] =")?;
+                for (j, c) in coefficients.iter().enumerate() {
+                    let sep = if j == 0 { " " } else { " + " };
+                    write!(f, "{}{}·a[i-{}]", sep, c, j + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Approximate equality relative to the magnitude of the operands, with a
+/// tiny absolute floor so that zeros compare equal
+fn close<T: Float>(a: T, b: T) -> bool {
+    (a - b).abs() <= T::epsilon().sqrt() * a.abs().max(b.abs()) + T::min_positive_value()
+}
+
+/// Detect closed-form structure in the first `n` terms of `arr`
+///
+/// Candidates are tried from the most to the least specific: arithmetic,
+/// geometric, polynomial (via the difference table) and finally a linear
+/// recurrence found by Berlekamp–Massey. Every candidate must be confirmed
+/// by at least one term beyond those needed to determine it, so a structure
+/// is only reported when the prefix overdetermines it. Returns `None` when
+/// nothing fits.
+pub fn identify<T, A>(arr: &A, n: usize) -> Option<Structure<T>>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    if n < 3 {
+        return None;
+    }
+    let terms: Vec<T> = (0..n).map(|i| arr.get(i)).collect();
+
+    let step = terms[1] - terms[0];
+    if terms.windows(2).all(|w| close(w[1] - w[0], step)) {
+        return Some(Structure::Arithmetic { start: terms[0], step });
+    }
+
+    if terms[0] != T::zero() {
+        let ratio = terms[1] / terms[0];
+        if terms.windows(2).all(|w| close(w[0] * ratio, w[1])) {
+            return Some(Structure::Geometric { start: terms[0], ratio });
+        }
+    }
+
+    if let Some(differences) = polynomial_differences(&terms) {
+        return Some(Structure::Polynomial { differences });
+    }
+
+    let coefficients = berlekamp_massey(&terms);
+    let order = coefficients.len();
+    if order > 0 && 2 * order < n {
+        return Some(Structure::LinearRecurrence {
+            coefficients,
+            initial: terms[..order].to_vec(),
+        });
+    }
+
+    None
+}
+
+/// Leading forward differences of a polynomial fitting `terms`
+///
+/// The table must reach a constant row while at least two entries remain.
+fn polynomial_differences<T: Float>(terms: &[T]) -> Option<Vec<T>> {
+    let mut row = terms.to_vec();
+    let mut differences = Vec::new();
+    while row.len() >= 2 {
+        differences.push(row[0]);
+        if row.iter().all(|x| close(*x, row[0])) {
+            return Some(differences);
+        }
+        row = row.windows(2).map(|w| w[1] - w[0]).collect();
+    }
+    None
+}
+
+/// Shortest linear recurrence generating `terms`, via Berlekamp–Massey
+///
+/// Returns `c` such that `terms[i] = Σ_j c[j]·terms[i-1-j]`.
+fn berlekamp_massey<T: Float>(terms: &[T]) -> Vec<T> {
+    let mut current = vec![T::one()];
+    let mut previous = vec![T::one()];
+    let mut length = 0;
+    let mut shift = 1;
+    let mut last_discrepancy = T::one();
+
+    for i in 0..terms.len() {
+        // The discrepancy is zero up to the rounding of the sum forming it
+        let (discrepancy, magnitude) =
+            (1..=length).fold((terms[i], terms[i].abs()), |(acc, magnitude), j| {
+                let term = current.get(j).copied().unwrap_or_else(T::zero) * terms[i - j];
+                (acc + term, magnitude + term.abs())
+            });
+        if discrepancy.abs() <= T::epsilon().sqrt() * magnitude {
+            shift += 1;
+            continue;
+        }
+
+        let factor = discrepancy / last_discrepancy;
+        let mut updated = current.clone();
+        if updated.len() < previous.len() + shift {
+            updated.resize(previous.len() + shift, T::zero());
+        }
+        for (j, p) in previous.iter().enumerate() {
+            updated[j + shift] = updated[j + shift] - factor * *p;
+        }
+
+        if 2 * length <= i {
+            previous = current;
+            length = i + 1 - length;
+            last_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+        current = updated;
+    }
+
+    current.resize(length + 1, T::zero());
+    current[1..].iter().map(|c| -*c).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+
+    #[test]
+    fn test_identify_arithmetic_and_geometric() {
+        let arith = InfiniteArrayFromFn::new(|i| 3.0 + 2.0 * i as f64);
+        assert_eq!(
+            identify(&arith, 10),
+            Some(Structure::Arithmetic { start: 3.0, step: 2.0 })
+        );
+
+        let geom = InfiniteArrayFromFn::new(|i| 5.0 * 3f64.powi(i as i32));
+        assert_eq!(
+            identify(&geom, 10),
+            Some(Structure::Geometric { start: 5.0, ratio: 3.0 })
+        );
+        let tiny = InfiniteArrayFromFn::new(|i| 1e-10 * 2f64.powi(i as i32));
+        assert_eq!(identify(&tiny, 10), Some(Structure::Geometric { start: 1e-10, ratio: 2.0 }));
+        let decay = Structure::Geometric { start: 2.0, ratio: 1.0 - 1e-12 };
+        assert_eq!(decay.get(1 << 40), 2.0 * (1.0f64 - 1e-12).powf(2f64.powi(40)));
+    }
+
+    #[test]
+    fn test_identify_polynomial() {
+        let squares = InfiniteArrayFromFn::new(|i| (i * i) as f64);
+        let structure = identify(&squares, 8).unwrap();
+        assert_eq!(structure.degree(), Some(2));
+        assert_eq!(structure.get(1000), 1_000_000.0);
+    }
+
+    #[test]
+    fn test_identify_fibonacci() {
+        let fib = InfiniteArrayFromFn::new(|i| {
+            let (mut a, mut b) = (0.0, 1.0);
+            for _ in 0..i {
+                let next = a + b;
+                a = b;
+                b = next;
+            }
+            a
+        });
+        let structure = identify(&fib, 12).unwrap();
+        assert!(matches!(structure, Structure::LinearRecurrence { ref coefficients, .. }
+            if coefficients.len() == 2));
+        assert_eq!(structure.get(30), 832_040.0);
+        assert_eq!(structure.to_string(), "a[i] = 1·a[i-1] + 1·a[i-2]");
+    }
+
+    #[test]
+    fn test_identify_unstructured() {
+        let arr = InfiniteArrayFromFn::new(|i| ((i * 7919) % 13) as f64);
+        assert_eq!(identify(&arr, 8), None);
+    }
+}
@@ -0,0 +1,98 @@
+//! Optional `tracing` instrumentation
+//!
+//! With the `tracing` feature, expensive operations report to the `tracing`
+//! ecosystem: adaptive solves and matrix functions open debug spans,
+//! parallel cumsum checkpoints are built inside one, and cache misses emit
+//! trace events. Without the feature the macros below expand to nothing.
+
+/// Enter a debug span until the end of the enclosing block
+macro_rules! debug_span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($($arg)*).entered();
+    };
+}
+
+/// Emit a debug event
+macro_rules! debug_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+/// Emit a trace event
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+pub(crate) use {debug_event, debug_span, trace_event};
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use crate::arrays::{Fill, InfiniteArrayFromFn, PaddedArray};
+    use crate::cache::CachedArray;
+    use crate::linalg::solve;
+    use crate::matrix::Tridiagonal;
+    use std::sync::{Arc, Mutex};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Records the names of spans and the messages of events
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    struct Message<'a>(&'a mut String);
+
+    impl tracing::field::Visit for Message<'_> {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                *self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            self.0.lock().unwrap().push(format!("span {}", span.metadata().name()));
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut message = String::new();
+            event.record(&mut Message(&mut message));
+            self.0.lock().unwrap().push(format!("event {}", message));
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_instrumented_operations_report() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let a = Tridiagonal::new(Fill::new(1.0), Fill::new(4.0), Fill::new(1.0));
+            solve(a, &PaddedArray::zero_padded(vec![1.0]), 1e-10).unwrap();
+            let mut cached = CachedArray::new(InfiniteArrayFromFn::new(|i| i));
+            cached.set(0, 7);
+            assert_eq!((cached.get(0), cached.get(1)), (7, 1));
+        });
+        let log = recorder.0.lock().unwrap();
+        assert_eq!(log.first().map(String::as_str), Some("span solve"));
+        assert!(log.iter().any(|line| line == "event converged"));
+        assert_eq!(log.iter().filter(|line| *line == "event cache miss").count(), 1);
+    }
+}
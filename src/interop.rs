@@ -0,0 +1,16 @@
+//! Conversions to and from other array libraries and file formats
+//!
+//! Infinite arrays and matrices are converted by their leading sections;
+//! finite arrays come back as padded infinite arrays. Each conversion that
+//! needs another crate is behind a feature of the same name.
+
+pub mod csv;
+
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
+
+#[cfg(feature = "nalgebra")]
+pub mod nalgebra;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
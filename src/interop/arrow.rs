@@ -0,0 +1,181 @@
+//! Export of infinite array prefixes to Arrow record batches and Parquet
+//!
+//! Columns are read chunk by chunk, so at most one chunk of each column is
+//! held in memory at a time.
+
+use crate::arrays::InfiniteArray;
+use ::arrow::array::{ArrayRef, PrimitiveArray};
+use ::arrow::datatypes::{ArrowPrimitiveType, Field, Schema, SchemaRef};
+use ::arrow::error::ArrowError;
+use ::arrow::record_batch::RecordBatch;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// A column that can be exported chunk by chunk, see [`column`]
+pub trait ExportColumn {
+    /// The Arrow field describing the column
+    fn field(&self) -> Field;
+
+    /// The entries at `range` as an Arrow array
+    fn chunk(&self, range: Range<usize>) -> ArrayRef;
+}
+
+/// An infinite array exported as a column of Arrow primitive type `P`
+pub struct PrefixColumn<P, A> {
+    name: String,
+    array: A,
+    _phantom: PhantomData<P>,
+}
+
+/// Export `array` as a non-nullable column named `name`, e.g.
+/// `column::<Float64Type, _>("x", arr)`
+pub fn column<P, A>(name: &str, array: A) -> PrefixColumn<P, A>
+where
+    P: ArrowPrimitiveType,
+    A: InfiniteArray<P::Native>,
+{
+    PrefixColumn {
+        name: name.to_string(),
+        array,
+        _phantom: PhantomData,
+    }
+}
+
+impl<P, A> ExportColumn for PrefixColumn<P, A>
+where
+    P: ArrowPrimitiveType,
+    A: InfiniteArray<P::Native>,
+{
+    fn field(&self) -> Field {
+        Field::new(&self.name, P::DATA_TYPE, false)
+    }
+
+    fn chunk(&self, range: Range<usize>) -> ArrayRef {
+        Arc::new(PrimitiveArray::<P>::from_iter_values(range.map(|i| self.array.get(i))))
+    }
+}
+
+/// The first `n` rows of a set of columns as record batches of at most
+/// `chunk` rows, see [`record_batches`]
+pub struct PrefixBatches<'a> {
+    schema: SchemaRef,
+    columns: Vec<Box<dyn ExportColumn + 'a>>,
+    n: usize,
+    chunk: usize,
+    position: usize,
+}
+
+impl PrefixBatches<'_> {
+    /// The schema shared by every batch
+    pub fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
+impl Iterator for PrefixBatches<'_> {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.n {
+            return None;
+        }
+        let end = (self.position + self.chunk).min(self.n);
+        let arrays = self.columns.iter().map(|c| c.chunk(self.position..end)).collect();
+        self.position = end;
+        Some(RecordBatch::try_new(self.schema(), arrays))
+    }
+}
+
+/// Stream the first `n` rows of `columns` as record batches of at most
+/// `chunk` rows
+///
+/// # Panics
+///
+/// Panics if `chunk` is zero.
+pub fn record_batches<'a>(columns: Vec<Box<dyn ExportColumn + 'a>>, n: usize, chunk: usize) -> PrefixBatches<'a> {
+    assert!(chunk > 0, "chunk size must be positive");
+    let schema = Arc::new(Schema::new(columns.iter().map(|c| c.field()).collect::<Vec<_>>()));
+    PrefixBatches {
+        schema,
+        columns,
+        n,
+        chunk,
+        position: 0,
+    }
+}
+
+/// The first `n` rows of `columns` as a single record batch
+pub fn record_batch<'a>(columns: Vec<Box<dyn ExportColumn + 'a>>, n: usize) -> Result<RecordBatch, ArrowError> {
+    let mut batches = record_batches(columns, n, n.max(1));
+    match batches.next() {
+        Some(batch) => batch,
+        None => Ok(RecordBatch::new_empty(batches.schema())),
+    }
+}
+
+/// Write the first `n` rows of `columns` to a Parquet file, `chunk` rows
+/// at a time
+#[cfg(feature = "parquet")]
+pub fn write_parquet<'a, W>(
+    writer: W,
+    columns: Vec<Box<dyn ExportColumn + 'a>>,
+    n: usize,
+    chunk: usize,
+) -> Result<(), ::parquet::errors::ParquetError>
+where
+    W: std::io::Write + Send,
+{
+    let batches = record_batches(columns, n, chunk);
+    let mut writer = ::parquet::arrow::ArrowWriter::try_new(writer, batches.schema(), None)?;
+    for batch in batches {
+        writer.write(&batch?)?;
+    }
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+    use ::arrow::array::{Array, Float64Array, Int64Array};
+    use ::arrow::datatypes::{Float64Type, Int64Type};
+
+    fn columns() -> Vec<Box<dyn ExportColumn + 'static>> {
+        vec![
+            Box::new(column::<Int64Type, _>("n", InfiniteArrayFromFn::new(|i| i as i64 + 1))),
+            Box::new(column::<Float64Type, _>("sqrt", InfiniteArrayFromFn::new(|i| (i as f64).sqrt()))),
+        ]
+    }
+
+    #[test]
+    fn test_record_batches_in_chunks() {
+        let batches: Vec<RecordBatch> = record_batches(columns(), 10, 4).collect::<Result<_, _>>().unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(), vec![4, 4, 2]);
+        let last = batches[2].column(0).as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(last.value(1), 10);
+
+        let single = record_batch(columns(), 5).unwrap();
+        assert_eq!(single.schema().field(1).name(), "sqrt");
+        let roots = single.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(roots.value(4), 2.0);
+        assert_eq!(record_batch(columns(), 0).unwrap().num_rows(), 0);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_round_trip() {
+        use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let path = std::env::temp_dir().join(format!("infinite-arrays-{}.parquet", std::process::id()));
+        write_parquet(std::fs::File::create(&path).unwrap(), columns(), 1000, 128).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(rows, 1000);
+    }
+}
@@ -0,0 +1,146 @@
+//! CSV export of array prefixes and import of single columns
+
+use crate::arrays::{InfiniteArray, PaddedArray};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::str::FromStr;
+
+/// Errors from reading a CSV column
+#[derive(Debug)]
+pub enum CsvError {
+    /// The underlying reader failed
+    Io(io::Error),
+    /// The field on 1-based line `line` could not be parsed
+    Parse { line: usize },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(error) => write!(f, "failed to read CSV: {}", error),
+            CsvError::Parse { line } => write!(f, "cannot parse CSV field on line {}", line),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvError::Io(error) => Some(error),
+            CsvError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(error: io::Error) -> Self {
+        CsvError::Io(error)
+    }
+}
+
+/// Quote a field if it contains a separator, quote or line break
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// The first field of a record, unquoted
+fn first_field(line: &str) -> String {
+    let Some(quoted) = line.strip_prefix('"') else {
+        return line.split(',').next().unwrap_or("").trim().to_string();
+    };
+    let mut field = String::new();
+    let mut chars = quoted.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => break,
+            c => field.push(c),
+        }
+    }
+    field
+}
+
+/// Write the first `n` elements of named arrays as CSV columns, with a
+/// header row of the names
+///
+/// Rows are written one at a time, so nothing but the current row is held
+/// in memory.
+pub fn write_prefix_csv<T, W>(arrays: &[(&str, &dyn InfiniteArray<T>)], n: usize, mut writer: W) -> io::Result<()>
+where
+    T: fmt::Display,
+    W: Write,
+{
+    let header: Vec<String> = arrays.iter().map(|(name, _)| escape(name)).collect();
+    writeln!(writer, "{}", header.join(","))?;
+    for i in 0..n {
+        let row: Vec<String> = arrays.iter().map(|(_, array)| escape(&array.get(i).to_string())).collect();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+    writer.flush()
+}
+
+/// Read the first column of a CSV file into a [`PaddedArray`] continued by
+/// `tail`
+///
+/// A first line whose field does not parse as `T` is taken as a header and
+/// skipped. Blank lines are skipped.
+pub fn from_csv_column<T, R>(reader: R, tail: T) -> Result<PaddedArray<T>, CsvError>
+where
+    T: FromStr,
+    R: Read,
+{
+    let mut data = Vec::new();
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match first_field(&line).parse() {
+            Ok(value) => data.push(value),
+            Err(_) if index == 0 => {}
+            Err(_) => return Err(CsvError::Parse { line: index + 1 }),
+        }
+    }
+    Ok(PaddedArray::new(data, tail))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArrayFromFn};
+
+    #[test]
+    fn test_write_prefix_csv() {
+        let squares = InfiniteArrayFromFn::new(|i| i * i);
+        let sevens = Fill::new(7);
+        let mut out = Vec::new();
+        write_prefix_csv(&[("n²", &squares), ("a,b", &sevens)], 3, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "n²,\"a,b\"\n0,7\n1,7\n4,7\n");
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let halves = InfiniteArrayFromFn::new(|i| i as f64 / 2.0);
+        let mut out = Vec::new();
+        write_prefix_csv(&[("x", &halves), ("y", &halves)], 4, &mut out).unwrap();
+        let column: PaddedArray<f64> = from_csv_column(out.as_slice(), 0.0).unwrap();
+        assert_eq!(column.data(), &[0.0, 0.5, 1.0, 1.5]);
+        assert_eq!(column.get(10), 0.0);
+
+        let quoted = from_csv_column::<i32, _>("\"1\",x\n\n2\n".as_bytes(), -1).unwrap();
+        assert_eq!(quoted.data(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_csv_parse_error() {
+        let error = from_csv_column::<i32, _>("value\n1\ntwo\n".as_bytes(), 0).unwrap_err();
+        assert!(matches!(error, CsvError::Parse { line: 3 }));
+    }
+}
@@ -0,0 +1,147 @@
+//! Interoperability with the `nalgebra` crate
+
+use crate::arrays::{InfiniteArray, PaddedArray};
+use crate::matrix::InfiniteMatrix;
+use ::nalgebra::{ComplexField, DMatrix, DVector, Scalar};
+use num_traits::Zero;
+
+/// The first `n` elements of an infinite array as a [`DVector`]
+pub fn to_dvector<T, A>(arr: &A, n: usize) -> DVector<T>
+where
+    T: Scalar,
+    A: InfiniteArray<T>,
+{
+    DVector::from_fn(n, |i, _| arr.get(i))
+}
+
+/// The leading `rows × cols` section of an infinite matrix as a
+/// [`DMatrix`]
+pub fn to_dmatrix<T, M>(matrix: &M, rows: usize, cols: usize) -> DMatrix<T>
+where
+    T: Scalar,
+    M: InfiniteMatrix<T>,
+{
+    DMatrix::from_fn(rows, cols, |i, j| matrix.get(i, j))
+}
+
+/// A [`DVector`] followed by `tail` repeated forever
+pub fn padded_from_dvector<T: Scalar>(vector: DVector<T>, tail: T) -> PaddedArray<T> {
+    PaddedArray::new(vector.as_slice().to_vec(), tail)
+}
+
+impl<T: Scalar + Zero> From<DVector<T>> for PaddedArray<T> {
+    /// The vector followed by zeros
+    fn from(vector: DVector<T>) -> Self {
+        PaddedArray::zero_padded(vector.as_slice().to_vec())
+    }
+}
+
+/// A dense nalgebra matrix is a finite matrix
+impl<T: Scalar> InfiniteMatrix<T> for DMatrix<T> {
+    fn get(&self, i: usize, j: usize) -> T {
+        self[(i, j)].clone()
+    }
+
+    fn rows(&self) -> Option<usize> {
+        Some(self.nrows())
+    }
+
+    fn cols(&self) -> Option<usize> {
+        Some(self.ncols())
+    }
+}
+
+/// The leading `n × n` section of an infinite operator, for use with
+/// nalgebra's dense solvers
+///
+/// Products with vectors read only the section's entries, skipping those
+/// outside the band of a banded operator; [`to_dmatrix`](Self::to_dmatrix)
+/// materializes the section for factorizations.
+#[derive(Debug, Clone, Copy)]
+pub struct Truncation<'a, M> {
+    matrix: &'a M,
+    n: usize,
+}
+
+impl<'a, M> Truncation<'a, M> {
+    pub fn new(matrix: &'a M, n: usize) -> Self {
+        Self { matrix, n }
+    }
+
+    /// The size of the section
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    /// The section as a [`DMatrix`]
+    pub fn to_dmatrix<T>(&self) -> DMatrix<T>
+    where
+        T: Scalar,
+        M: InfiniteMatrix<T>,
+    {
+        to_dmatrix(self.matrix, self.n, self.n)
+    }
+
+    /// The product of the section with `x`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` does not have length `n`.
+    pub fn mul<T>(&self, x: &DVector<T>) -> DVector<T>
+    where
+        T: ComplexField,
+        M: InfiniteMatrix<T>,
+    {
+        assert_eq!(x.len(), self.n, "vector length must match the truncation size");
+        let band = self.matrix.bandwidths();
+        DVector::from_fn(self.n, |i, _| {
+            let (first, last) = match band {
+                Some((lower, upper)) => (i.saturating_sub(lower), (i + upper).min(self.n - 1)),
+                None => (0, self.n - 1),
+            };
+            (first..=last).fold(T::zero(), |acc, j| acc + self.matrix.get(i, j) * x[j].clone())
+        })
+    }
+
+    /// Solve the truncated system `A_n x = b` with nalgebra's LU
+    /// decomposition, or `None` if the section is singular
+    pub fn solve<T>(&self, b: &DVector<T>) -> Option<DVector<T>>
+    where
+        T: ComplexField,
+        M: InfiniteMatrix<T>,
+    {
+        self.to_dmatrix().lu().solve(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArrayFromFn};
+    use crate::matrix::Tridiagonal;
+
+    #[test]
+    fn test_conversions() {
+        let squares = InfiniteArrayFromFn::new(|i| (i * i) as f64);
+        assert_eq!(to_dvector(&squares, 3), DVector::from_vec(vec![0.0, 1.0, 4.0]));
+
+        let padded: PaddedArray<f64> = DVector::from_vec(vec![1.0, 2.0]).into();
+        assert_eq!(padded.get(7), 0.0);
+        assert_eq!(padded_from_dvector(DVector::from_vec(vec![1, 2]), 9).get(2), 9);
+
+        let dense = DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(InfiniteMatrix::get(&dense, 1, 0), 3.0);
+        assert_eq!(to_dmatrix(&dense, 2, 2), dense);
+    }
+
+    #[test]
+    fn test_truncation_solve() {
+        let a = Tridiagonal::new(Fill::new(-1.0), Fill::new(2.0), Fill::new(-1.0));
+        let section = Truncation::new(&a, 5);
+        let x = DVector::from_fn(5, |i, _| (i + 1) as f64);
+        let b = section.mul(&x);
+        assert_eq!(b, section.to_dmatrix() * &x);
+        let solved = section.solve(&b).unwrap();
+        assert!((solved - x).norm() < 1e-12);
+    }
+}
@@ -0,0 +1,59 @@
+//! Interoperability with the `ndarray` crate
+
+use crate::arrays::{InfiniteArray, PaddedArray};
+use crate::matrix::InfiniteMatrix;
+use ::ndarray::{Array1, Array2};
+use num_traits::Zero;
+
+/// The first `n` elements of an infinite array as an [`Array1`]
+pub fn to_array1<T, A>(arr: &A, n: usize) -> Array1<T>
+where
+    A: InfiniteArray<T>,
+{
+    Array1::from_shape_fn(n, |i| arr.get(i))
+}
+
+/// The leading `rows × cols` section of an infinite matrix as an [`Array2`]
+pub fn to_array2<T, M>(matrix: &M, rows: usize, cols: usize) -> Array2<T>
+where
+    M: InfiniteMatrix<T>,
+{
+    Array2::from_shape_fn((rows, cols), |(i, j)| matrix.get(i, j))
+}
+
+/// An [`Array1`] followed by `tail` repeated forever
+pub fn padded_from_array1<T: Clone>(array: Array1<T>, tail: T) -> PaddedArray<T> {
+    PaddedArray::new(array.to_vec(), tail)
+}
+
+impl<T: Clone + Zero> From<Array1<T>> for PaddedArray<T> {
+    /// The array followed by zeros
+    fn from(array: Array1<T>) -> Self {
+        PaddedArray::zero_padded(array.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+    use crate::matrix::Toeplitz;
+    use ::ndarray::array;
+
+    #[test]
+    fn test_prefix_conversions() {
+        let squares = InfiniteArrayFromFn::new(|i| (i * i) as f64);
+        assert_eq!(to_array1(&squares, 4), array![0.0, 1.0, 4.0, 9.0]);
+
+        let toeplitz = Toeplitz::new(PaddedArray::zero_padded(vec![2.0, 1.0]), PaddedArray::zero_padded(vec![2.0, -1.0]));
+        assert_eq!(to_array2(&toeplitz, 2, 3), array![[2.0, -1.0, 0.0], [1.0, 2.0, -1.0]]);
+    }
+
+    #[test]
+    fn test_array1_to_padded() {
+        let padded: PaddedArray<f64> = array![1.0, 2.0].into();
+        assert_eq!(padded.get(1), 2.0);
+        assert_eq!(padded.get(100), 0.0);
+        assert_eq!(padded_from_array1(array![1, 2], 7).get(5), 7);
+    }
+}
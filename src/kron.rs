@@ -0,0 +1,301 @@
+//! Kronecker products of infinite vectors and matrices
+//!
+//! A Kronecker product of two infinite index sets needs an enumeration of
+//! index pairs. When the second factor is finite (or has finite support) the
+//! usual block layout `n = i * m + j` is kept, which preserves supports and
+//! bandwidths; when only the first factor is finite its indices cycle
+//! fastest, `n = i + j * m`; otherwise pairs are enumerated along
+//! anti-diagonals with the Cantor pairing function.
+
+use crate::arrays::{Indexed, InfiniteArray, InfiniteVector};
+use crate::describe::Description;
+use crate::matrix::InfiniteMatrix;
+use num_traits::Zero;
+use std::ops::Mul;
+
+/// The Cantor pairing `(i, j) ↦ (i + j)(i + j + 1)/2 + j`
+pub fn pair(i: usize, j: usize) -> usize {
+    let w = i + j;
+    w * (w + 1) / 2 + j
+}
+
+/// The inverse of [`pair`]
+pub fn unpair(n: usize) -> (usize, usize) {
+    // Largest w with w(w + 1)/2 <= n, corrected for floating-point rounding;
+    // in u128, since 8n and the triangular numbers near it pass usize::MAX
+    let n = n as u128;
+    let triangle = |w: u128| w * (w + 1) / 2;
+    let mut w = ((8.0 * n as f64 + 1.0).sqrt() as u128).saturating_sub(1) / 2;
+    while triangle(w + 1) <= n {
+        w += 1;
+    }
+    while triangle(w) > n {
+        w -= 1;
+    }
+    let j = n - triangle(w);
+    ((w - j) as usize, j as usize)
+}
+
+/// How the index of a Kronecker product splits into a pair of indices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pairing {
+    /// Block layout `n = i * m + j` for a second factor of size `m`
+    Blocked(usize),
+    /// Layout `n = i + j * m` for a first factor of length `m`
+    Interleaved(usize),
+    /// Anti-diagonal enumeration with [`pair`] and [`unpair`]
+    Cantor,
+}
+
+impl Pairing {
+    /// Split a product index into the indices of the two factors
+    pub fn split(self, n: usize) -> (usize, usize) {
+        match self {
+            Pairing::Blocked(m) => (n / m, n % m),
+            Pairing::Interleaved(m) => (n % m, n / m),
+            Pairing::Cantor => unpair(n),
+        }
+    }
+
+    /// Combine the indices of the two factors into a product index
+    pub fn join(self, i: usize, j: usize) -> usize {
+        match self {
+            Pairing::Blocked(m) => {
+                assert!(j < m, "index {} outside a block of size {}", j, m);
+                i * m + j
+            }
+            Pairing::Interleaved(m) => {
+                assert!(i < m, "index {} outside a block of size {}", i, m);
+                j * m + i
+            }
+            Pairing::Cantor => pair(i, j),
+        }
+    }
+}
+
+/// The Kronecker product of two vectors, see [`kron`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Kron<A, B> {
+    a: A,
+    b: B,
+    pairing: Pairing,
+    support: Option<usize>,
+}
+
+impl<A, B> Kron<A, B> {
+    /// The enumeration of index pairs in use
+    pub fn pairing(&self) -> Pairing {
+        self.pairing
+    }
+}
+
+impl<T, A, B> InfiniteArray<T> for Kron<A, B>
+where
+    T: Mul<Output = T>,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        let (i, j) = self.pairing.split(index);
+        self.a.get(i) * self.b.get(j)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("kron", [self.a.describe(), self.b.describe()])
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.a.len()?.checked_mul(self.b.len()?)
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        self.support
+    }
+}
+
+impl<T, A, B> InfiniteVector<T> for Kron<A, B>
+where
+    T: Mul<Output = T>,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+}
+
+/// The Kronecker product `a ⊗ b`, with entries `a[i] * b[j]`
+///
+/// If `b` has a finite length or support `m`, entry `i * m + j` is
+/// `a[i] * b[j]` as for finite vectors, and a finite support of `a` carries
+/// over to the product. If instead `a` has a finite length `m`, entry
+/// `i + j * m` is `a[i] * b[j]`, and a finite support of `b` carries over.
+/// Otherwise the pairs `(i, j)` are enumerated with the Cantor pairing, so
+/// every product appears at a finite index.
+pub fn kron<T, A, B>(a: A, b: B) -> Kron<A, B>
+where
+    T: Zero + PartialEq,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    // A finite `a` must not be read past its end, so its length takes
+    // precedence over a finite support of an infinite `b`
+    let pairing = match (b.len(), a.len()) {
+        (Some(m), _) if m > 0 => Pairing::Blocked(m),
+        (None, Some(m)) if m > 0 => Pairing::Interleaved(m),
+        _ => match b.support() {
+            Some(m) if m > 0 && a.len().is_none() => Pairing::Blocked(m),
+            _ => Pairing::Cantor,
+        },
+    };
+    let support = match pairing {
+        Pairing::Blocked(m) => a.support().and_then(|s| s.checked_mul(m)),
+        Pairing::Interleaved(m) => b.support().and_then(|s| s.checked_mul(m)),
+        Pairing::Cantor => None,
+    };
+    Kron {
+        a,
+        b,
+        pairing,
+        support,
+    }
+}
+
+/// The Kronecker product of two matrices, see [`kron_matrix`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KronMatrix<A, B> {
+    a: A,
+    b: B,
+    row_pairing: Pairing,
+    col_pairing: Pairing,
+}
+
+impl<A, B> KronMatrix<A, B> {
+    /// The enumerations of row and column index pairs in use
+    pub fn pairings(&self) -> (Pairing, Pairing) {
+        (self.row_pairing, self.col_pairing)
+    }
+}
+
+impl<T, A, B> InfiniteMatrix<T> for KronMatrix<A, B>
+where
+    T: Mul<Output = T>,
+    A: InfiniteMatrix<T>,
+    B: InfiniteMatrix<T>,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        let (ia, ib) = self.row_pairing.split(i);
+        let (ja, jb) = self.col_pairing.split(j);
+        self.a.get(ia, ja) * self.b.get(ib, jb)
+    }
+
+    fn rows(&self) -> Option<usize> {
+        self.a.rows()?.checked_mul(self.b.rows()?)
+    }
+
+    fn cols(&self) -> Option<usize> {
+        self.a.cols()?.checked_mul(self.b.cols()?)
+    }
+
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        // With square n × n blocks, i - j = (ia - ja) n + (ib - jb)
+        let n = match (self.row_pairing, self.col_pairing) {
+            (Pairing::Blocked(r), Pairing::Blocked(c)) if r == c => r,
+            _ => return None,
+        };
+        let (la, ua) = self.a.bandwidths()?;
+        let (lb, ub) = self.b.bandwidths().unwrap_or((n - 1, n - 1));
+        Some((la * n + lb.min(n - 1), ua * n + ub.min(n - 1)))
+    }
+}
+
+/// The lazy Kronecker product `A ⊗ B`
+///
+/// Rows and columns are paired independently as in [`kron`]: a finite row
+/// (column) count of `B` gives the usual block layout, a finite count of
+/// `A` alone the interleaved one, and otherwise the Cantor pairing is used. With a square finite `B`, a banded `A` gives a banded
+/// product.
+pub fn kron_matrix<T, A, B>(a: A, b: B) -> KronMatrix<A, B>
+where
+    A: InfiniteMatrix<T>,
+    B: InfiniteMatrix<T>,
+{
+    let layout = |a_size: Option<usize>, b_size: Option<usize>| match (b_size, a_size) {
+        (Some(m), _) if m > 0 => Pairing::Blocked(m),
+        (None, Some(m)) if m > 0 => Pairing::Interleaved(m),
+        _ => Pairing::Cantor,
+    };
+    KronMatrix {
+        row_pairing: layout(a.rows(), b.rows()),
+        col_pairing: layout(a.cols(), b.cols()),
+        a,
+        b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArrayFromFn, PaddedArray};
+    use crate::matrix::{BandedMatrix, InfiniteMatrixFromFn};
+    use crate::streamed::IteratorArray;
+
+    #[test]
+    fn test_pairing_roundtrip() {
+        for n in 0..1000 {
+            let (i, j) = unpair(n);
+            assert_eq!(pair(i, j), n);
+        }
+        assert_eq!(unpair(0), (0, 0));
+        assert_eq!(unpair(4), (1, 1));
+        let (i, j) = unpair(usize::MAX);
+        assert_eq!((i as u128 + j as u128) * (i as u128 + j as u128 + 1) / 2 + j as u128, usize::MAX as u128);
+    }
+
+    #[test]
+    fn test_kron_vectors() {
+        let a = PaddedArray::zero_padded(vec![1, 2]);
+        let b = PaddedArray::zero_padded(vec![1, 10, 100]);
+        let k = kron(a, b);
+        assert_eq!(k.pairing(), Pairing::Blocked(3));
+        let prefix: Vec<i32> = k.iter().take(7).collect();
+        assert_eq!(prefix, vec![1, 10, 100, 2, 20, 200, 0]);
+        assert_eq!(k.support(), Some(6));
+
+        let k = kron(InfiniteArrayFromFn::new(|i| i), InfiniteArrayFromFn::new(|j| j));
+        assert_eq!(k.pairing(), Pairing::Cantor);
+        assert_eq!(k.get(pair(7, 3)), 21);
+
+        let finite = IteratorArray::new(vec![1, 2, 3]);
+        let k = kron(&finite, PaddedArray::zero_padded(vec![1, 10]));
+        assert_eq!(k.pairing(), Pairing::Interleaved(3));
+        assert_eq!(k.iter().take(7).collect::<Vec<_>>(), vec![1, 2, 3, 10, 20, 30, 0]);
+        assert_eq!((k.len(), k.support()), (None, Some(6)));
+        assert_eq!(k.pairing().join(2, 1), 5);
+    }
+
+    #[test]
+    fn test_kron_matrix() {
+        let d = BandedMatrix::new(0, 1, vec![Fill::new(-1.0), Fill::new(1.0)]);
+        let b = vec![vec![1.0, 2.0], vec![3.0, 4.0]];
+        let k = kron_matrix(d, b);
+        assert_eq!(k.get(2, 3), -2.0);
+        assert_eq!(k.get(1, 2), 3.0);
+        assert_eq!(k.get(0, 4), 0.0);
+        assert_eq!(k.bandwidths(), Some((1, 3)));
+
+        let hilbert = |i, j| 1.0 / (i + j + 1) as f64;
+        let k = kron_matrix(InfiniteMatrixFromFn::new(hilbert), InfiniteMatrixFromFn::new(hilbert));
+        assert_eq!(k.pairings(), (Pairing::Cantor, Pairing::Cantor));
+        assert_eq!(k.get(pair(1, 0), pair(0, 2)), 0.5 * (1.0 / 3.0));
+        assert_eq!(k.bandwidths(), None);
+
+        let k = kron_matrix(vec![vec![1.0, 2.0]], InfiniteMatrixFromFn::new(hilbert));
+        assert_eq!(k.pairings(), (Pairing::Interleaved(1), Pairing::Interleaved(2)));
+        assert_eq!(k.get(1, 5), 2.0 * hilbert(1, 2));
+    }
+}
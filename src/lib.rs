@@ -10,12 +10,12 @@
 //! use infinite_arrays::*;
 //!
 //! // Create an infinite vector of ones
-//! let ones = Ones::new();
+//! let ones: Ones<f64> = Ones::new();
 //! assert_eq!(ones.get(0), 1.0);
 //! assert_eq!(ones.get(100), 1.0);
 //!
 //! // Create an infinite range
-//! let range = OneToInf::new();
+//! let range: OneToInf<usize> = OneToInf::new();
 //! assert_eq!(range.get(0), 1);
 //! assert_eq!(range.get(1), 2);
 //!
@@ -25,15 +25,115 @@
 //! assert_eq!(cumsum_result.get(1), 2.0);
 //! ```
 
+mod instrument;
 pub mod ranges;
 pub mod arrays;
 pub mod operations;
 pub mod cache;
+pub mod series;
+pub mod reductions;
+pub mod identify;
+pub mod search;
+pub mod sorted;
+pub mod filter;
+pub mod prefix;
+pub mod matrix;
+pub mod kron;
+pub mod linalg;
+pub mod orthopoly;
+pub mod fun;
+pub mod automaton;
+pub mod grid;
+pub mod tensor;
+pub mod interop;
+pub mod streamed;
+pub mod asynchronous;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod simd;
+pub mod display;
+pub mod describe;
+pub mod fusion;
+pub mod simplify;
+pub mod testing;
+pub mod profile;
+pub mod budget;
+pub mod error;
+pub mod fallible;
+pub mod overflow;
+pub mod nan;
+#[cfg(feature = "complex")]
+pub mod complex;
+#[cfg(feature = "decimal")]
+pub mod decimal;
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+#[cfg(feature = "wgpu")]
+pub mod gpu;
+#[cfg(any(feature = "ffi", feature = "python", feature = "wasm-bindgen"))]
+pub mod bindings;
+#[cfg(feature = "mmap")]
+pub mod mapped;
 
-pub use ranges::{OneToInf, InfUnitRange, InfStepRange};
-pub use arrays::{Ones, Zeros, InfiniteArray, InfiniteVector, InfiniteArrayFromFn};
-pub use operations::{cumsum, broadcast, add_scalar, mul_scalar, add_arrays, sub_arrays, mul_arrays, div_arrays};
-pub use cache::CachedArray;
+pub use ranges::{FromIndex, OneToInf, InfUnitRange, InfStepRange, InfGeometricRange};
+pub use arrays::{Ones, Zeros, Fill, PaddedArray, EventuallyPeriodic, Indexed, InfiniteArray, InfiniteVector, InfiniteArrayFromFn};
+pub use operations::{cumsum, broadcast, add_scalar, mul_scalar, add_arrays, sub_arrays, mul_arrays, div_arrays, matvec, MatVec};
+pub use cache::{BlockBackend, CacheBackend, CacheContents, CacheStats, CachedArray, ClockBackend, FileBackend, ForkLayer, Memoized, Persist, SharedCachedArray, SyncMemoized, Warmer};
+#[cfg(feature = "sled")]
+pub use cache::SledBackend;
+pub use reductions::{norm, dot, inner_product, is_orthogonal};
+pub use identify::{identify, Structure};
+pub use search::{find, position_of, search_sorted, contains, Monotone};
+pub use sorted::{merge_sorted, union, intersection, difference, MergeSorted, SetOperation, SetKind};
+pub use filter::{argwhere, compress, dedup, run_lengths, ArgWhere, Compress, Dedup, RunLengths};
+pub use prefix::{all_prefix, any_prefix, forall, exists, count_prefix, histogram, quantiles, top_k, Bins, Histogram};
+pub use matrix::{InfiniteMatrix, InfiniteMatrixFromFn, Eye, Diagonal, MatrixSum, Toeplitz, Hankel, BandedMatrix, Tridiagonal, BlockDiagonal, outer, Outer};
+pub use kron::{kron, kron_matrix, Kron, KronMatrix, Pairing};
+pub use linalg::{apply_expm, apply_resolvent, eigenvalues, lu, lu_solve, qr, solve, spectrum, LinalgError, LuFactorization, QrFactorization, Solution, SpectrumEstimate};
+pub use orthopoly::{JacobiOperator, PolynomialFamily, Recurrence};
+pub use fun::{conversion, derivative, dirichlet_basis, integration, CoefficientOperator, Fun};
+pub use automaton::{CellularAutomaton, Generation};
+pub use grid::InfiniteGrid;
+pub use streamed::{Checkpoint, Fetch, GeneratorArray, IteratorArray, StreamedArray};
+pub use asynchronous::{AsyncArray, AsyncCached, AsyncFromFn, AsyncInfiniteArray};
+#[cfg(feature = "futures")]
+pub use asynchronous::{ArrayStream, ChunkedStream, IntoStream};
+pub use tensor::{einsum, Contraction, InfiniteTensor, Lane, Length, Shape, ShapeError};
+pub use describe::Description;
+pub use fusion::{fuse, fuse_chain, Affine, ScalarOp};
+pub use simplify::StructuredCumsum;
+pub use testing::eq_within;
+pub use profile::Profiled;
+pub use budget::{materialize_with_deadline, BudgetExhausted, Budgeted, PartialPrefix};
+pub use error::Error;
+pub use fallible::{div_arrays_with, div_scalar_with, DivPolicy, Checked, SumMode, try_add_arrays, try_broadcast, try_cumsum, try_div_arrays, try_mul_arrays, try_sub_arrays};
+pub use overflow::{checked_add_arrays, checked_cumsum, checked_mul_arrays, checked_sub_arrays, saturating_add_arrays, saturating_cumsum, saturating_mul_arrays, saturating_sub_arrays, Saturating, Wrapping, wrapping_add_arrays, wrapping_cumsum, wrapping_mul_arrays, wrapping_sub_arrays};
+pub use nan::{is_nan_mask, nan_to, nanmax_prefix, nanmin_prefix, nansum_prefix, NanMask, NanTo};
+#[cfg(feature = "complex")]
+pub use complex::{abs, add_complex_scalar, conj, im, mul_complex_scalar, re, to_complex, ComplexMap};
+#[cfg(feature = "decimal")]
+pub use decimal::{round_dp, RoundDp};
+pub use display::{preview, Preview, PREVIEW_LEN};
+pub use simd::{add_simd, div_simd, materialize, mul_simd, sub_simd, Elementwise};
+#[cfg(feature = "wgpu")]
+pub use gpu::{GpuError, GpuEvaluator, GpuExpr};
+#[cfg(feature = "python")]
+pub use bindings::python::PyInfiniteArray;
+#[cfg(feature = "wasm-bindgen")]
+pub use bindings::wasm::LazyArray;
+#[cfg(feature = "rayon")]
+pub use parallel::{cumsum_par, materialize_par, scan_par, CumsumCheckpoint, ParallelPrefix};
+#[cfg(feature = "mmap")]
+pub use mapped::{write_prefix, MappedArray};
+pub use interop::csv::{from_csv_column, write_prefix_csv, CsvError};
+#[cfg(feature = "ndarray")]
+pub use interop::ndarray::{padded_from_array1, to_array1, to_array2};
+#[cfg(feature = "nalgebra")]
+pub use interop::nalgebra::{padded_from_dvector, to_dmatrix, to_dvector, Truncation};
+#[cfg(feature = "arrow")]
+pub use interop::arrow::{column, record_batch, record_batches, ExportColumn, PrefixBatches, PrefixColumn};
+#[cfg(feature = "parquet")]
+pub use interop::arrow::write_parquet;
 
 /// The infinity symbol constant
 pub const INFINITY: usize = usize::MAX;
@@ -73,14 +173,14 @@ mod tests {
 
     #[test]
     fn test_ones() {
-        let ones = Ones::new();
+        let ones: Ones<f64> = Ones::new();
         assert_eq!(ones.get(0), 1.0);
         assert_eq!(ones.get(100), 1.0);
     }
 
     #[test]
     fn test_range() {
-        let range = OneToInf::new();
+        let range: OneToInf<usize> = OneToInf::new();
         assert_eq!(range.get(0), 1);
         assert_eq!(range.get(1), 2);
         assert_eq!(range.get(99), 100);
@@ -0,0 +1,59 @@
+//! Adaptive linear algebra for banded infinite operators
+//!
+//! Factorizations are computed one column at a time and cached, so a
+//! factor is only ever built as far as the entries that have been asked for.
+
+use std::fmt;
+
+mod functions;
+mod lu;
+mod qr;
+mod solve;
+mod spectrum;
+
+pub use functions::{apply_expm, apply_resolvent, Resolvent, Shifted};
+pub use lu::{lu, LuFactorization};
+pub use qr::{qr, QrFactorization, RFactor};
+pub use solve::{lu_solve, solve, Solution, DEFAULT_TRUNCATION_BUDGET};
+pub use spectrum::{eigenvalues, spectrum, SpectrumEstimate};
+
+/// A factorization that reduces a banded matrix to upper-triangular form one
+/// column at a time, as used by the adaptive solvers
+///
+/// Step `k` acts on rows `k ..= k + lower` only, and the triangular factor
+/// has upper bandwidth `lower + upper`.
+pub trait Elimination<T> {
+    /// Apply the transformation of step `k` to a right-hand side with at
+    /// least `k + lower + 1` entries
+    fn eliminate(&self, k: usize, rhs: &mut [T]);
+
+    /// `(lower, upper)` bandwidths of the factored matrix
+    fn bandwidths(&self) -> (usize, usize);
+
+    /// Entry `(i, j)` of the triangular factor
+    fn triangular(&self, i: usize, j: usize) -> T;
+}
+
+/// Errors raised by the adaptive solvers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinalgError {
+    /// The residual tolerance was not met by any truncation up to `size`
+    NotConverged { size: usize },
+    /// A zero pivot was met at `index`
+    Singular { index: usize },
+}
+
+impl fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinalgError::NotConverged { size } => {
+                write!(f, "residual tolerance not met by a truncation of size {}", size)
+            }
+            LinalgError::Singular { index } => {
+                write!(f, "zero pivot at index {}", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LinalgError {}
@@ -0,0 +1,202 @@
+//! Actions of matrix functions on infinite vectors
+
+use super::solve::{solve, Solution};
+use super::{LinalgError, QrFactorization};
+use crate::arrays::{Fill, InfiniteArray, PaddedArray};
+use crate::instrument::{debug_event, debug_span};
+use crate::matrix::{Diagonal, InfiniteMatrix, MatrixSum};
+use num_traits::Float;
+
+/// Most Taylor terms summed in one substep of [`apply_expm`]
+const TAYLOR_TERM_LIMIT: usize = 200;
+
+/// Most substeps [`apply_expm`] takes before giving up
+const SUBSTEP_LIMIT: usize = 100_000;
+
+/// `A x` for a banded `A` and a finitely supported `x`, which is supported
+/// on `x.len() + lower` entries
+fn banded_matvec<T, M>(matrix: &M, (lower, upper): (usize, usize), x: &[T]) -> Vec<T>
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+{
+    if x.is_empty() {
+        return Vec::new();
+    }
+    (0..x.len() + lower)
+        .map(|i| {
+            let last = i.saturating_add(upper).min(x.len().saturating_sub(1));
+            (i.saturating_sub(lower)..=last).fold(T::zero(), |acc, j| acc + matrix.get(i, j) * x[j])
+        })
+        .collect()
+}
+
+fn norm2<T: Float>(x: &[T]) -> T {
+    x.iter().fold(T::zero(), |acc, &v| acc + v * v).sqrt()
+}
+
+/// Largest absolute row sum of `A` over the first `rows` rows
+fn row_norm<T, M>(matrix: &M, (lower, upper): (usize, usize), rows: usize) -> T
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+{
+    (0..rows).fold(T::zero(), |max, i| {
+        let sum = (i.saturating_sub(lower)..=i.saturating_add(upper)).fold(T::zero(), |acc, j| acc + matrix.get(i, j).abs());
+        max.max(sum)
+    })
+}
+
+/// The action `exp(t A) v` of the matrix exponential of a banded operator on
+/// a finitely supported vector
+///
+/// Time is split into substeps `h` with `|h| ‖A‖ <= 1`, where `‖A‖` is the
+/// largest absolute row sum over the rows the vector currently reaches, and
+/// each substep sums the Taylor series of `exp(h A)` until a term's 2-norm
+/// falls below `tol` divided by the number of substeps taken so far. Every
+/// Taylor term widens the support by the lower bandwidth, so the result is
+/// returned zero-padded beyond the reached support.
+///
+/// `tol` is a heuristic cutoff on the size of the last Taylor term kept, not
+/// a bound on the error of the result: the truncated tail and the rounding
+/// accumulated over the substeps are not estimated.
+///
+/// # Panics
+///
+/// Panics if `A` is not banded or `v` does not have finite
+/// [`support`](InfiniteArray::support).
+pub fn apply_expm<T, M, V>(matrix: &M, vector: &V, t: T, tol: T) -> Result<PaddedArray<T>, LinalgError>
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+    V: InfiniteArray<T>,
+{
+    let bands = matrix
+        .bandwidths()
+        .expect("apply_expm requires a banded matrix");
+    let support = vector
+        .support()
+        .expect("apply_expm requires a vector with finite support");
+    debug_span!("apply_expm", support);
+    let mut x: Vec<T> = (0..support).map(|i| vector.get(i)).collect();
+    if x.iter().all(|v| v.is_zero()) {
+        return Ok(PaddedArray::zero_padded(x));
+    }
+
+    let mut remaining = t;
+    let mut substeps = 0;
+    while remaining != T::zero() {
+        if substeps >= SUBSTEP_LIMIT {
+            return Err(LinalgError::NotConverged { size: x.len() });
+        }
+        let nu = row_norm(matrix, bands, x.len() + bands.0);
+        let h = if nu * remaining.abs() <= T::one() {
+            remaining
+        } else {
+            remaining.signum() / nu
+        };
+        substeps += 1;
+        let step_tol = tol / T::from(substeps).unwrap();
+
+        let mut term = x.clone();
+        let mut k = 0;
+        loop {
+            k += 1;
+            if k > TAYLOR_TERM_LIMIT {
+                return Err(LinalgError::NotConverged { size: x.len() });
+            }
+            let scale = h / T::from(k).unwrap();
+            term = banded_matvec(matrix, bands, &term).into_iter().map(|v| v * scale).collect();
+            x.resize(term.len(), T::zero());
+            for (xi, &ti) in x.iter_mut().zip(&term) {
+                *xi = *xi + ti;
+            }
+            if norm2(&term) <= step_tol {
+                break;
+            }
+        }
+        remaining = remaining - h;
+    }
+    debug_event!(substeps, support = x.len(), "converged");
+    Ok(PaddedArray::zero_padded(x))
+}
+
+/// The shifted operator `A - z I`
+pub type Shifted<T, M> = MatrixSum<M, Diagonal<Fill<T>>>;
+
+/// The vector `(A - z I)⁻¹ v` returned by [`apply_resolvent`]
+pub type Resolvent<T, M> = Solution<T, QrFactorization<T, Shifted<T, M>>>;
+
+/// The action `(A - z I)⁻¹ v` of the resolvent of a banded operator on a
+/// finitely supported vector
+///
+/// Solves the shifted system with [`solve`], so the result is a lazily
+/// extended [`Solution`] with residual at most `tol`. Only real shifts are
+/// supported.
+///
+/// # Panics
+///
+/// Panics if `A` is not banded or `v` does not have finite
+/// [`support`](InfiniteArray::support).
+pub fn apply_resolvent<T, M, V>(
+    matrix: M,
+    z: T,
+    vector: &V,
+    tol: T,
+) -> Result<Resolvent<T, M>, LinalgError>
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+    V: InfiniteArray<T>,
+{
+    let shifted = MatrixSum::new(matrix, Diagonal::new(Fill::new(-z)));
+    solve(shifted, vector, tol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+    use crate::matrix::BandedMatrix;
+
+    #[test]
+    fn test_expm_shift() {
+        // exp(t S) e_2 = e_2 + t e_1 + t²/2 e_0 for the upward shift S
+        let shift = BandedMatrix::new(0, 1, vec![Fill::new(0.0), Fill::new(1.0)]);
+        let e2 = PaddedArray::zero_padded(vec![0.0, 0.0, 1.0]);
+        let result = apply_expm(&shift, &e2, 3.0, 1e-12).unwrap();
+        assert!((result.get(0) - 4.5).abs() < 1e-12);
+        assert!((result.get(1) - 3.0).abs() < 1e-12);
+        assert!((result.get(2) - 1.0).abs() < 1e-12);
+        assert_eq!(result.get(3), 0.0);
+    }
+
+    #[test]
+    fn test_expm_of_zero_vector() {
+        let tridiagonal = BandedMatrix::new(1, 1, vec![Fill::new(1.0), Fill::new(-2.0), Fill::new(1.0)]);
+        let result = apply_expm(&tridiagonal, &PaddedArray::zero_padded(vec![]), 1.0, 1e-12).unwrap();
+        assert_eq!(result.data(), &[] as &[f64]);
+        assert_eq!(result.get(5), 0.0);
+        assert!(banded_matvec(&tridiagonal, (1, 1), &[] as &[f64]).is_empty());
+    }
+
+    #[test]
+    fn test_expm_diagonal() {
+        let decay = Diagonal::new(InfiniteArrayFromFn::new(|i| -(i as f64)));
+        let v = PaddedArray::zero_padded(vec![1.0; 6]);
+        let result = apply_expm(&decay, &v, 2.0, 1e-10).unwrap();
+        for i in 0..6 {
+            assert!((result.get(i) - (-2.0 * i as f64).exp()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_resolvent_diagonal() {
+        let a = Diagonal::new(InfiniteArrayFromFn::new(|i| (i + 1) as f64));
+        let v = PaddedArray::zero_padded(vec![1.0, 1.0, 1.0]);
+        let r = apply_resolvent(a, -1.0, &v, 1e-12).unwrap();
+        assert!((r.get(0) - 0.5).abs() < 1e-12);
+        assert!((r.get(2) - 0.25).abs() < 1e-12);
+        assert!(r.get(3).abs() < 1e-12);
+    }
+}
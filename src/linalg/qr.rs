@@ -0,0 +1,254 @@
+//! Adaptive QR factorization by Householder reflections
+
+use super::Elimination;
+use crate::matrix::InfiniteMatrix;
+use num_traits::Float;
+use std::cell::RefCell;
+
+/// Working rows and the reflectors computed so far
+struct QrState<T> {
+    /// Row `r` stores columns `r - lower ..= r + lower + upper`
+    rows: Vec<Vec<T>>,
+    /// Householder vector and scale `β = 2 / vᵀv` of each completed step
+    reflectors: Vec<(Vec<T>, T)>,
+}
+
+/// A lazily extended QR factorization `A = Q R` of a banded infinite matrix,
+/// see [`qr`]
+///
+/// `Q` is the infinite product `H_0 H_1 H_2 ⋯` of Householder reflections,
+/// where `H_k` acts on rows `k ..= k + lower`, and `R` is upper triangular
+/// with upper bandwidth `lower + upper`. Step `k` only touches rows up to
+/// `k + lower` and columns up to `k + lower + upper`, so after `n` steps the
+/// first `n` rows of `R` are final and no later step changes them.
+pub struct QrFactorization<T, M> {
+    matrix: M,
+    lower: usize,
+    upper: usize,
+    state: RefCell<QrState<T>>,
+}
+
+impl<T, M> QrFactorization<T, M>
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+{
+    /// Width of a stored working row
+    fn width(&self) -> usize {
+        2 * self.lower + self.upper + 1
+    }
+
+    /// Position of column `c` within stored row `r`
+    fn slot(&self, r: usize, c: usize) -> usize {
+        c + self.lower - r
+    }
+
+    fn load_row(&self, state: &mut QrState<T>) {
+        let r = state.rows.len();
+        let mut row = vec![T::zero(); self.width()];
+        for c in r.saturating_sub(self.lower)..=r + self.upper {
+            row[self.slot(r, c)] = self.matrix.get(r, c);
+        }
+        state.rows.push(row);
+    }
+
+    /// Carry out Householder steps until the first `n` rows of `R` are final
+    pub fn ensure(&self, n: usize) {
+        let mut state = self.state.borrow_mut();
+        while state.reflectors.len() < n {
+            let k = state.reflectors.len();
+            while state.rows.len() <= k + self.lower {
+                self.load_row(&mut state);
+            }
+
+            let mut v: Vec<T> = (0..=self.lower)
+                .map(|t| state.rows[k + t][self.slot(k + t, k)])
+                .collect();
+            let norm = v.iter().fold(T::zero(), |acc, &x| acc + x * x).sqrt();
+            let alpha = if v[0] > T::zero() { -norm } else { norm };
+            v[0] = v[0] - alpha;
+            let vv = v.iter().fold(T::zero(), |acc, &x| acc + x * x);
+            let beta = if vv > T::zero() {
+                (T::one() + T::one()) / vv
+            } else {
+                T::zero()
+            };
+
+            for c in k..=k + self.lower + self.upper {
+                let s = (0..=self.lower).fold(T::zero(), |acc, t| {
+                    acc + v[t] * state.rows[k + t][self.slot(k + t, c)]
+                }) * beta;
+                for (t, &vt) in v.iter().enumerate() {
+                    let slot = self.slot(k + t, c);
+                    state.rows[k + t][slot] = state.rows[k + t][slot] - s * vt;
+                }
+            }
+            state.reflectors.push((v, beta));
+        }
+    }
+
+    /// Number of Householder steps carried out so far
+    pub fn steps(&self) -> usize {
+        self.state.borrow().reflectors.len()
+    }
+
+    /// Entry `(i, j)` of `R`, extending the factorization if needed
+    pub fn r_entry(&self, i: usize, j: usize) -> T {
+        if j < i || j > i + self.lower + self.upper {
+            return T::zero();
+        }
+        self.ensure(i + 1);
+        self.state.borrow().rows[i][self.slot(i, j)]
+    }
+
+    /// The upper-triangular factor as an infinite matrix
+    pub fn r(&self) -> RFactor<'_, T, M> {
+        RFactor { qr: self }
+    }
+
+    /// Apply `Qₙᵀ = H_{n-1} ⋯ H_0`, the first `n` reflections, to a finite
+    /// vector (implicitly zero-padded)
+    ///
+    /// The result has length `max(b.len(), n + lower)`.
+    pub fn apply_qt(&self, b: &[T], n: usize) -> Vec<T> {
+        self.ensure(n);
+        let state = self.state.borrow();
+        let mut x = b.to_vec();
+        x.resize(b.len().max(n + self.lower), T::zero());
+        for (k, (v, beta)) in state.reflectors.iter().enumerate().take(n) {
+            reflect(&mut x[k..=k + self.lower], v, *beta);
+        }
+        x
+    }
+
+    /// Apply `Qₙ = H_0 ⋯ H_{n-1}` to a finite vector (implicitly
+    /// zero-padded)
+    ///
+    /// For a vector supported on the first `n` entries, `Qₙ x = Q x`.
+    pub fn apply_q(&self, x: &[T], n: usize) -> Vec<T> {
+        self.ensure(n);
+        let state = self.state.borrow();
+        let mut y = x.to_vec();
+        y.resize(x.len().max(n + self.lower), T::zero());
+        for (k, (v, beta)) in state.reflectors.iter().enumerate().take(n).rev() {
+            reflect(&mut y[k..=k + self.lower], v, *beta);
+        }
+        y
+    }
+}
+
+impl<T, M> Elimination<T> for QrFactorization<T, M>
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+{
+    fn eliminate(&self, k: usize, rhs: &mut [T]) {
+        self.ensure(k + 1);
+        let state = self.state.borrow();
+        let (v, beta) = &state.reflectors[k];
+        reflect(&mut rhs[k..=k + self.lower], v, *beta);
+    }
+
+    fn bandwidths(&self) -> (usize, usize) {
+        (self.lower, self.upper)
+    }
+
+    fn triangular(&self, i: usize, j: usize) -> T {
+        self.r_entry(i, j)
+    }
+}
+
+/// Apply the reflection `I - β v vᵀ` in place
+fn reflect<T: Float>(x: &mut [T], v: &[T], beta: T) {
+    let s = v.iter().zip(x.iter()).fold(T::zero(), |acc, (&vi, &xi)| acc + vi * xi) * beta;
+    for (xi, &vi) in x.iter_mut().zip(v) {
+        *xi = *xi - s * vi;
+    }
+}
+
+/// The `R` factor of a [`QrFactorization`], see [`QrFactorization::r`]
+pub struct RFactor<'a, T, M> {
+    qr: &'a QrFactorization<T, M>,
+}
+
+impl<T, M> InfiniteMatrix<T> for RFactor<'_, T, M>
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        self.qr.r_entry(i, j)
+    }
+
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        Some((0, self.qr.lower + self.qr.upper))
+    }
+}
+
+/// Start an adaptive QR factorization of a banded infinite matrix
+///
+/// Nothing is computed up front; columns are eliminated as entries of `R`
+/// or applications of `Q` ask for them.
+///
+/// # Panics
+///
+/// Panics if the matrix does not report its
+/// [`bandwidths`](InfiniteMatrix::bandwidths).
+pub fn qr<T, M>(matrix: M) -> QrFactorization<T, M>
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+{
+    let (lower, upper) = matrix
+        .bandwidths()
+        .expect("adaptive QR requires a banded matrix");
+    QrFactorization {
+        matrix,
+        lower,
+        upper,
+        state: RefCell::new(QrState {
+            rows: Vec::new(),
+            reflectors: Vec::new(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::Fill;
+    use crate::matrix::{BandedMatrix, Tridiagonal};
+
+    #[test]
+    fn test_qr_reconstructs_columns() {
+        let a = Tridiagonal::new(Fill::new(1.0), Fill::new(-3.0), Fill::new(2.0));
+        let f = qr(a);
+        let r = f.r();
+        assert_eq!(r.bandwidths(), Some((0, 2)));
+        for j in 0..6 {
+            let column: Vec<f64> = (0..=j).map(|i| r.get(i, j)).collect();
+            let qr_column = f.apply_q(&column, j + 1);
+            for (i, &x) in qr_column.iter().enumerate() {
+                assert!((x - a.get(i, j)).abs() < 1e-12);
+            }
+        }
+        assert_eq!(r.get(5, 2), 0.0);
+    }
+
+    #[test]
+    fn test_qr_is_lazy() {
+        let d = BandedMatrix::new(1, 0, vec![Fill::new(-1.0), Fill::new(2.0)]);
+        let f = qr(d);
+        assert_eq!(f.steps(), 0);
+        let _ = f.r_entry(3, 4);
+        assert_eq!(f.steps(), 4);
+
+        // Qᵀ undoes Q
+        let x = [1.0, 2.0, 3.0];
+        let back = f.apply_qt(&f.apply_q(&x, 3), 3);
+        for (i, &xi) in x.iter().enumerate() {
+            assert!((back[i] - xi).abs() < 1e-12);
+        }
+        assert!(back[3].abs() < 1e-12);
+    }
+}
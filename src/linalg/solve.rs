@@ -0,0 +1,268 @@
+//! Adaptive solution of banded infinite linear systems
+
+use super::lu::{lu, LuFactorization};
+use super::qr::{qr, QrFactorization};
+use super::{Elimination, LinalgError};
+use crate::arrays::{Indexed, InfiniteArray, InfiniteVector};
+use crate::instrument::{debug_event, debug_span, trace_event};
+use crate::matrix::InfiniteMatrix;
+use num_traits::Float;
+use std::cell::RefCell;
+
+/// Largest truncation tried before [`solve`] gives up
+pub const DEFAULT_TRUNCATION_BUDGET: usize = 100_000;
+
+/// The transformed right-hand side and the current solution prefix
+struct SolveState<T> {
+    /// Truncation size `n`
+    size: usize,
+    /// The right-hand side after the first `n` elimination steps
+    rotated: Vec<T>,
+    /// Solution of the `n × n` triangular system
+    x: Vec<T>,
+}
+
+/// The solution of `A x = b` as a lazily extended infinite array, see
+/// [`solve`] and [`lu_solve`]
+///
+/// Entries beyond the current truncation are zero until they are read: a
+/// read at index `i >= size()` grows the truncation to `i + 1` and
+/// recomputes the prefix. With the QR factorization the residual never
+/// increases as the truncation grows, so the tolerance met by [`solve`]
+/// keeps holding; with LU it usually does but is not guaranteed to.
+pub struct Solution<T, F> {
+    factorization: F,
+    state: RefCell<SolveState<T>>,
+}
+
+impl<T, F> Solution<T, F>
+where
+    T: Float,
+    F: Elimination<T>,
+{
+    fn new<B>(factorization: F, rhs: &B) -> Self
+    where
+        B: InfiniteArray<T>,
+    {
+        let support = rhs
+            .support()
+            .expect("solve requires a right-hand side with finite support");
+        Solution {
+            factorization,
+            state: RefCell::new(SolveState {
+                size: 0,
+                rotated: (0..support).map(|i| rhs.get(i)).collect(),
+                x: Vec::new(),
+            }),
+        }
+    }
+
+    /// Grow the truncation until the residual is at most `tol`, then solve
+    /// the triangular system
+    fn converge(self, tol: T) -> Result<Self, LinalgError> {
+        debug_span!("solve");
+        {
+            let mut state = self.state.borrow_mut();
+            loop {
+                let next = state.size + 1;
+                self.rotate_to(&mut state, next);
+                if Self::residual_of(&state) <= tol {
+                    break;
+                }
+                if state.size >= DEFAULT_TRUNCATION_BUDGET {
+                    debug_event!(size = state.size, "not converged");
+                    return Err(LinalgError::NotConverged { size: state.size });
+                }
+            }
+            debug_event!(size = state.size, "converged");
+            self.back_substitute(&mut state)?;
+        }
+        Ok(self)
+    }
+
+    /// Apply elimination steps until the truncation has size `n`
+    fn rotate_to(&self, state: &mut SolveState<T>, n: usize) {
+        let (lower, _) = self.factorization.bandwidths();
+        while state.size < n {
+            let k = state.size;
+            if state.rotated.len() < k + lower + 1 {
+                state.rotated.resize(k + lower + 1, T::zero());
+            }
+            self.factorization.eliminate(k, &mut state.rotated);
+            state.size += 1;
+        }
+    }
+
+    /// The residual `‖A x - b‖₂` of a truncation, which is the norm of the
+    /// part of the transformed right-hand side that the `n × n` system
+    /// cannot reach (up to the row permutation for LU)
+    fn residual_of(state: &SolveState<T>) -> T {
+        state.rotated[state.size.min(state.rotated.len())..]
+            .iter()
+            .fold(T::zero(), |acc, &c| acc + c * c)
+            .sqrt()
+    }
+
+    /// Back-substitute in the leading `n × n` triangular system
+    fn back_substitute(&self, state: &mut SolveState<T>) -> Result<(), LinalgError> {
+        let (lower, upper) = self.factorization.bandwidths();
+        let n = state.size;
+        let mut x = vec![T::zero(); n];
+        for i in (0..n).rev() {
+            let last = (i + lower + upper).min(n - 1);
+            let sum = (i + 1..=last).fold(state.rotated[i], |acc, j| acc - self.factorization.triangular(i, j) * x[j]);
+            let pivot = self.factorization.triangular(i, i);
+            if pivot == T::zero() {
+                return Err(LinalgError::Singular { index: i });
+            }
+            x[i] = sum / pivot;
+        }
+        state.x = x;
+        Ok(())
+    }
+
+    /// Current truncation size; entries at and beyond it are zero
+    pub fn size(&self) -> usize {
+        self.state.borrow().size
+    }
+
+    /// Residual `‖A x - b‖₂` of the current truncation
+    pub fn residual(&self) -> T {
+        Self::residual_of(&self.state.borrow())
+    }
+
+    /// The computed prefix of the solution
+    pub fn prefix(&self) -> Vec<T> {
+        self.state.borrow().x.clone()
+    }
+}
+
+impl<T, F> InfiniteArray<T> for Solution<T, F>
+where
+    T: Float,
+    F: Elimination<T>,
+{
+    fn get(&self, index: usize) -> T {
+        let mut state = self.state.borrow_mut();
+        if index >= state.size {
+            trace_event!(from = state.size, to = index + 1, "extending solution");
+            self.rotate_to(&mut state, index + 1);
+            self.back_substitute(&mut state)
+                .expect("solve: singular pivot while extending the solution");
+        }
+        state.x[index]
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+}
+
+impl<T, F> InfiniteVector<T> for Solution<T, F>
+where
+    T: Float,
+    F: Elimination<T>,
+{
+}
+
+/// Solve `A x = b` for a banded infinite operator `A`
+///
+/// Uses the adaptive QR factorization: the truncation size `n` grows one
+/// column at a time, and the residual `‖A x - b‖₂` of the `n × n` solution
+/// is read off from `Qₙᵀ b` without forming `A x`. The first `n` meeting
+/// `tol` is kept, and the triangular system is solved once.
+///
+/// # Panics
+///
+/// Panics if `A` is not banded or `b` does not have finite
+/// [`support`](InfiniteArray::support).
+pub fn solve<T, M, B>(matrix: M, rhs: &B, tol: T) -> Result<Solution<T, QrFactorization<T, M>>, LinalgError>
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+    B: InfiniteArray<T>,
+{
+    Solution::new(qr(matrix), rhs).converge(tol)
+}
+
+/// Solve `A x = b` for a banded infinite operator `A` with the adaptive LU
+/// factorization
+///
+/// Works like [`solve`], reading the residual off the eliminated
+/// right-hand side, but each step costs a Gauss transform instead of a
+/// Householder reflection. Prefer it for diagonally dominant operators.
+///
+/// # Panics
+///
+/// Panics if `A` is not banded or `b` does not have finite
+/// [`support`](InfiniteArray::support).
+pub fn lu_solve<T, M, B>(matrix: M, rhs: &B, tol: T) -> Result<Solution<T, LuFactorization<T, M>>, LinalgError>
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+    B: InfiniteArray<T>,
+{
+    Solution::new(lu(matrix), rhs).converge(tol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, PaddedArray};
+    use crate::matrix::{BandedMatrix, Tridiagonal};
+
+    #[test]
+    fn test_solve_tridiagonal() {
+        let a = Tridiagonal::new(Fill::new(1.0), Fill::new(4.0), Fill::new(1.0));
+        let b = PaddedArray::zero_padded(vec![1.0]);
+        let x = solve(a, &b, 1e-12).unwrap();
+        assert!(x.residual() <= 1e-12);
+
+        // The solution decays like (√3 - 2)^i
+        let ratio = 3f64.sqrt() - 2.0;
+        assert!((x.get(3) / x.get(2) - ratio).abs() < 1e-8);
+        for i in 0usize..5 {
+            let row: f64 = (i.saturating_sub(1)..=i + 1).map(|j| a.get(i, j) * x.get(j)).sum();
+            assert!((row - b.get(i)).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_solution_extends_lazily() {
+        let a = Tridiagonal::new(Fill::new(-1.0), Fill::new(3.0), Fill::new(-1.0));
+        let x = solve(a, &PaddedArray::zero_padded(vec![1.0, 1.0]), 1e-8).unwrap();
+        let size = x.size();
+        let early = x.get(0);
+        assert!(x.get(size + 10) > 0.0);
+        assert_eq!(x.size(), size + 11);
+        assert!(x.residual() <= 1e-8);
+        assert!((x.get(0) - early).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_lu_solve_matches_qr() {
+        let a = Tridiagonal::new(Fill::new(1.0), Fill::new(-4.0), Fill::new(2.0));
+        let b = PaddedArray::zero_padded(vec![1.0, -1.0, 0.5]);
+        let x_lu = lu_solve(a, &b, 1e-12).unwrap();
+        let x_qr = solve(a, &b, 1e-12).unwrap();
+        for i in 0..10 {
+            assert!((x_lu.get(i) - x_qr.get(i)).abs() < 1e-10);
+        }
+
+        // Pivoting: the subdiagonal dominates the diagonal
+        let c = Tridiagonal::new(Fill::new(3.0), Fill::new(2.0), Fill::new(-4.0));
+        let y_lu = lu_solve(c, &b, 1e-12).unwrap();
+        let y_qr = solve(c, &b, 1e-12).unwrap();
+        for i in 0..10 {
+            assert!((y_lu.get(i) - y_qr.get(i)).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_solve_singular() {
+        // Shift operator with a zero diagonal: the first column is zero
+        let s = BandedMatrix::new(0, 1, vec![Fill::new(0.0), Fill::new(1.0)]);
+        let result = solve(s, &PaddedArray::zero_padded(vec![0.0, 1.0]), 1e-12);
+        assert!(matches!(result, Err(LinalgError::Singular { .. })));
+    }
+}
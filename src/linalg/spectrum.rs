@@ -0,0 +1,261 @@
+//! Spectrum estimation for banded self-adjoint operators from growing
+//! truncations
+
+use super::LinalgError;
+use crate::matrix::InfiniteMatrix;
+use crate::instrument::{debug_event, debug_span};
+use num_traits::Float;
+
+/// Largest truncation [`spectrum`] tries before giving up
+const SPECTRUM_SECTION_LIMIT: usize = 2048;
+
+/// Truncation size [`spectrum`] starts from
+const SPECTRUM_SECTION_START: usize = 16;
+
+/// An estimate of the spectrum of a self-adjoint operator, see [`spectrum`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectrumEstimate<T> {
+    /// Smallest and largest eigenvalue of the final truncation
+    pub bounds: (T, T),
+    /// Intervals filled by accumulating eigenvalues, approximating the
+    /// essential spectrum
+    pub intervals: Vec<(T, T)>,
+    /// Eigenvalues that stayed put under refinement and are separated from
+    /// the intervals
+    pub isolated: Vec<T>,
+    /// Size of the final truncation
+    pub size: usize,
+}
+
+/// The upper band `A(i, i + d)`, `d = 0 ..= width`, of a leading section
+struct SymmetricBand<T> {
+    width: usize,
+    rows: Vec<Vec<T>>,
+}
+
+impl<T: Float> SymmetricBand<T> {
+    fn new<M: InfiniteMatrix<T>>(matrix: &M, n: usize) -> Self {
+        let (lower, upper) = matrix
+            .bandwidths()
+            .expect("spectrum estimation requires a banded matrix");
+        let width = lower.max(upper);
+        let rows = (0..n)
+            .map(|i| (0..=width).map(|d| if i + d < n { matrix.get(i, i + d) } else { T::zero() }).collect())
+            .collect();
+        Self { width, rows }
+    }
+
+    fn entry(&self, i: usize, j: usize) -> T {
+        let (i, j) = if i <= j { (i, j) } else { (j, i) };
+        if j - i > self.width {
+            T::zero()
+        } else {
+            self.rows[i][j - i]
+        }
+    }
+
+    /// Gershgorin interval containing every eigenvalue
+    fn gershgorin(&self) -> (T, T) {
+        let n = self.rows.len();
+        (0..n).fold((T::infinity(), T::neg_infinity()), |(lo, hi), i| {
+            let first = i.saturating_sub(self.width);
+            let last = (i + self.width).min(n - 1);
+            let radius = (first..=last)
+                .filter(|&j| j != i)
+                .fold(T::zero(), |acc, j| acc + self.entry(i, j).abs());
+            let centre = self.entry(i, i);
+            (lo.min(centre - radius), hi.max(centre + radius))
+        })
+    }
+
+    /// Number of eigenvalues below `x`, by Sylvester's law of inertia applied
+    /// to the banded `L D Lᵀ` factorization of `A - x I`
+    fn count_below(&self, x: T) -> usize {
+        let n = self.rows.len();
+        let w = self.width;
+        // l[i][d] holds L(i + d, i), d = 1 ..= w
+        let mut l = vec![vec![T::zero(); w + 1]; n];
+        let mut pivots = vec![T::zero(); n];
+        let tiny = T::epsilon() * T::epsilon();
+        let mut negative = 0;
+        for i in 0..n {
+            let first = i.saturating_sub(w);
+            let mut d = self.entry(i, i) - x;
+            for k in first..i {
+                let lik = l[k][i - k];
+                d = d - lik * lik * pivots[k];
+            }
+            if d == T::zero() {
+                d = tiny;
+            }
+            if d < T::zero() {
+                negative += 1;
+            }
+            pivots[i] = d;
+            for j in i + 1..=(i + w).min(n - 1) {
+                let mut value = self.entry(j, i);
+                for k in j.saturating_sub(w).max(first)..i {
+                    value = value - l[k][j - k] * l[k][i - k] * pivots[k];
+                }
+                l[i][j - i] = value / d;
+            }
+        }
+        negative
+    }
+
+    /// All eigenvalues in increasing order, each found by bisection to a
+    /// relative accuracy near machine precision
+    fn eigenvalues(&self) -> Vec<T> {
+        let (lo, hi) = self.gershgorin();
+        let two = T::one() + T::one();
+        let scale = lo.abs().max(hi.abs()).max(T::min_positive_value());
+        let eps = scale * T::epsilon() * two;
+        (0..self.rows.len())
+            .map(|k| {
+                // Smallest x with more than k eigenvalues below it
+                let (mut a, mut b) = (lo - eps, hi + eps);
+                while b - a > eps {
+                    let mid = (a + b) / two;
+                    if mid <= a || mid >= b {
+                        break;
+                    }
+                    if self.count_below(mid) > k {
+                        b = mid;
+                    } else {
+                        a = mid;
+                    }
+                }
+                (a + b) / two
+            })
+            .collect()
+    }
+}
+
+/// Eigenvalues of the leading `n × n` section of a banded self-adjoint
+/// matrix, in increasing order
+///
+/// Only the entries on and above the diagonal are read.
+///
+/// # Panics
+///
+/// Panics if the matrix does not report its
+/// [`bandwidths`](InfiniteMatrix::bandwidths).
+pub fn eigenvalues<T, M>(matrix: &M, n: usize) -> Vec<T>
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+{
+    if n == 0 {
+        return Vec::new();
+    }
+    SymmetricBand::new(matrix, n).eigenvalues()
+}
+
+/// Estimate the spectrum of a banded self-adjoint operator
+///
+/// Truncations of size `n` and `2n` are compared, doubling `n` until the
+/// extreme eigenvalues agree to within `tol`. In the final pair, an
+/// eigenvalue of the smaller truncation counts as isolated when the larger
+/// truncation has a single eigenvalue within `max(tol, width / √(2n))` of
+/// it (and that eigenvalue is within `tol` of it); the remaining
+/// eigenvalues of the larger truncation are grouped into intervals wherever
+/// consecutive gaps are below that window.
+///
+/// This is a heuristic in the spirit of the truncation method: extreme
+/// eigenvalues of truncations converge to the ends of the spectrum, but
+/// eigenvalues of truncations may also appear in gaps of the spectrum
+/// (spectral pollution), so isolated values should be confirmed, e.g. by
+/// solving with [`solve`](super::solve).
+///
+/// # Panics
+///
+/// Panics if the matrix does not report its
+/// [`bandwidths`](InfiniteMatrix::bandwidths).
+pub fn spectrum<T, M>(matrix: &M, tol: T) -> Result<SpectrumEstimate<T>, LinalgError>
+where
+    T: Float,
+    M: InfiniteMatrix<T>,
+{
+    debug_span!("spectrum");
+    let mut n = SPECTRUM_SECTION_START;
+    let mut coarse = eigenvalues(matrix, n);
+    loop {
+        let fine = eigenvalues(matrix, 2 * n);
+        let (lo, hi) = (fine[0], fine[fine.len() - 1]);
+        let settled = (coarse[0] - lo).abs() <= tol && (coarse[coarse.len() - 1] - hi).abs() <= tol;
+        if settled {
+            debug_event!(size = 2 * n, "converged");
+            return Ok(classify(&coarse, &fine, tol));
+        }
+        if 2 * n >= SPECTRUM_SECTION_LIMIT {
+            debug_event!(size = 2 * n, "not converged");
+            return Err(LinalgError::NotConverged { size: 2 * n });
+        }
+        n *= 2;
+        coarse = fine;
+    }
+}
+
+/// Split the eigenvalues of the larger truncation into isolated values and
+/// accumulation intervals
+fn classify<T: Float>(coarse: &[T], fine: &[T], tol: T) -> SpectrumEstimate<T> {
+    let (lo, hi) = (fine[0], fine[fine.len() - 1]);
+    let size = T::from(fine.len()).unwrap();
+    let window = tol.max((hi - lo) / size.sqrt());
+
+    let near = |x: T, radius: T| fine.iter().filter(|&&y| (y - x).abs() <= radius).count();
+    let isolated: Vec<T> = coarse
+        .iter()
+        .filter(|&&x| near(x, window) == 1 && near(x, tol) == 1)
+        .map(|&x| fine.iter().fold(fine[0], |best, &y| if (y - x).abs() < (best - x).abs() { y } else { best }))
+        .collect();
+
+    let mut intervals: Vec<(T, T)> = Vec::new();
+    for &y in fine.iter().filter(|y| !isolated.contains(y)) {
+        match intervals.last_mut() {
+            Some((_, end)) if y - *end <= window => *end = y,
+            _ => intervals.push((y, y)),
+        }
+    }
+    // A lone leftover eigenvalue is not an accumulation interval
+    intervals.retain(|&(a, b)| b > a);
+
+    SpectrumEstimate {
+        bounds: (lo, hi),
+        intervals,
+        isolated,
+        size: fine.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, PaddedArray};
+    use crate::matrix::Tridiagonal;
+
+    #[test]
+    fn test_truncated_eigenvalues() {
+        // Eigenvalues of the n × n free Jacobi matrix are 2 cos(kπ/(n + 1))
+        let laplacian = Tridiagonal::new(Fill::new(1.0), Fill::new(0.0), Fill::new(1.0));
+        let n = 10;
+        let computed = eigenvalues(&laplacian, n);
+        for (k, &lambda) in computed.iter().enumerate() {
+            let exact = 2.0 * ((n - k) as f64 * std::f64::consts::PI / (n + 1) as f64).cos();
+            assert!((lambda - exact).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_spectrum_with_bound_state() {
+        // A potential of height 3 at the origin adds the eigenvalue 3 + 1/3
+        // outside the essential spectrum [-2, 2]
+        let jacobi = Tridiagonal::new(Fill::new(1.0), PaddedArray::new(vec![3.0], 0.0), Fill::new(1.0));
+        let estimate = spectrum(&jacobi, 1e-2).unwrap();
+        assert_eq!(estimate.isolated.len(), 1);
+        assert!((estimate.isolated[0] - 10.0 / 3.0).abs() < 1e-6);
+        assert_eq!(estimate.intervals.len(), 1);
+        let (a, b) = estimate.intervals[0];
+        assert!((a + 2.0).abs() < 2e-2 && (b - 2.0).abs() < 2e-2);
+    }
+}
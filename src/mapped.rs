@@ -0,0 +1,159 @@
+//! Arrays whose prefix is read from a memory-mapped file
+//!
+//! The file holds raw values in the little-endian encoding of
+//! [`Persist`], one after another with no header, so tables can be written
+//! once with [`write_prefix`] and mapped by any later process.
+
+use crate::arrays::{Indexed, InfiniteArray, InfiniteVector};
+use crate::cache::Persist;
+use memmap2::Mmap;
+use num_traits::Zero;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// Write the first `n` elements of `arr` as raw values to `path`
+pub fn write_prefix<T, A, P>(path: P, arr: &A, n: usize) -> io::Result<()>
+where
+    T: Persist,
+    A: InfiniteArray<T>,
+    P: AsRef<Path>,
+{
+    let mut writer = BufWriter::new(File::create(path)?);
+    for i in 0..n {
+        arr.get(i).write_le(&mut writer)?;
+    }
+    writer.flush()
+}
+
+/// An infinite array whose first `N` elements are read from a memory-mapped
+/// file and whose remaining elements come from a base array
+///
+/// The base is read at the same index, so `get(i)` is `base.get(i)` for
+/// `i >= N`; use a [`Fill`](crate::arrays::Fill) base for a constant tail.
+pub struct MappedArray<T, B> {
+    map: Mmap,
+    len: usize,
+    base: B,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, B> MappedArray<T, B>
+where
+    T: Persist,
+    B: InfiniteArray<T>,
+{
+    /// Map the raw values in `path` as the prefix of an array continued by
+    /// `base`
+    ///
+    /// Fails if the file size is not a multiple of the element size.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the array exists,
+    /// as with [`Mmap::map`].
+    pub unsafe fn open<P: AsRef<Path>>(path: P, base: B) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let map = Mmap::map(&file)?;
+        if map.len() % T::SIZE != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "file size is not a multiple of the element size",
+            ));
+        }
+        Ok(Self {
+            len: map.len() / T::SIZE,
+            map,
+            base,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Number of elements read from the file
+    pub fn mapped_len(&self) -> usize {
+        self.len
+    }
+
+    /// The array the elements beyond the mapped prefix come from
+    pub fn base(&self) -> &B {
+        &self.base
+    }
+}
+
+impl<T, B> InfiniteArray<T> for MappedArray<T, B>
+where
+    T: Persist,
+    B: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        if index < self.len {
+            let mut bytes = &self.map[index * T::SIZE..(index + 1) * T::SIZE];
+            T::read_le(&mut bytes).expect("mapped slice holds a whole element")
+        } else {
+            self.base.get(index)
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.base.len().map(|n| n.max(self.len))
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        self.base.support().map(|n| n.max(self.len))
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        self.base
+            .periodicity()
+            .map(|(start, period)| (start.max(self.len), period))
+    }
+}
+
+impl<T, B> InfiniteVector<T> for MappedArray<T, B>
+where
+    T: Persist,
+    B: InfiniteArray<T>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArrayFromFn};
+
+    #[test]
+    fn test_mapped_prefix_with_tail() {
+        let path = std::env::temp_dir().join(format!("infinite-arrays-squares-{}.bin", std::process::id()));
+        let squares = InfiniteArrayFromFn::new(|i| (i * i) as u64);
+        write_prefix(&path, &squares, 1000).unwrap();
+
+        // Safety: the file is private to this test and not modified
+        let mapped = unsafe { MappedArray::open(&path, squares) }.unwrap();
+        assert_eq!(mapped.mapped_len(), 1000);
+        assert_eq!(mapped.get(999), 998_001);
+        assert_eq!(mapped.get(5000), 25_000_000);
+
+        let constant = unsafe { MappedArray::open(&path, Fill::new(0u64)) }.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(constant.get(1000), 0);
+        assert_eq!(constant.periodicity(), Some((1000, 1)));
+        assert_eq!(constant.support(), Some(1000));
+    }
+
+    #[test]
+    fn test_mapped_rejects_partial_elements() {
+        let path = std::env::temp_dir().join(format!("infinite-arrays-partial-{}.bin", std::process::id()));
+        std::fs::write(&path, [0u8; 5]).unwrap();
+        let result = unsafe { MappedArray::<u32, _>::open(&path, Fill::new(0)) };
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+}
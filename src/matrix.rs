@@ -0,0 +1,502 @@
+//! Infinite matrices and their row and column views
+
+use crate::arrays::{marker_eq_hash, Indexed, InfiniteArray, InfiniteArrayFromFn, InfiniteVector};
+use crate::operations::mul_scalar;
+use crate::reductions::dot;
+use crate::series::SeriesError;
+use num_traits::{Float, One, Zero};
+use std::ops::{Add, Mul};
+
+mod banded;
+mod block;
+mod toeplitz;
+
+pub use banded::{BandedMatrix, ProductBand, Tridiagonal};
+pub use block::BlockDiagonal;
+pub use toeplitz::{Hankel, Toeplitz};
+
+/// Trait for two-dimensional arrays whose row and column counts may be infinite
+pub trait InfiniteMatrix<T> {
+    /// Get the value at row `i`, column `j`
+    fn get(&self, i: usize, j: usize) -> T;
+
+    /// Number of rows, or `None` if infinite
+    fn rows(&self) -> Option<usize> {
+        None
+    }
+
+    /// Number of columns, or `None` if infinite
+    fn cols(&self) -> Option<usize> {
+        None
+    }
+
+    /// `(lower, upper)` bandwidths if the matrix is known to be banded
+    ///
+    /// Entry `(i, j)` is zero whenever `i > j + lower` or `j > i + upper`.
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// View row `i` as a one-dimensional array
+    fn row(&self, i: usize) -> Row<'_, Self>
+    where
+        Self: Sized,
+    {
+        Row { matrix: self, index: i }
+    }
+
+    /// View column `j` as a one-dimensional array
+    fn col(&self, j: usize) -> Col<'_, Self>
+    where
+        Self: Sized,
+    {
+        Col { matrix: self, index: j }
+    }
+}
+
+/// A row of an infinite matrix, see [`InfiniteMatrix::row`]
+///
+/// Rows are cheap to copy, so they can be passed to the combinators in
+/// [`operations`](crate::operations) that clone their input. For a banded
+/// matrix the row reports its finite [`support`](InfiniteArray::support),
+/// which lets reductions such as [`dot`](crate::reductions::dot) sum it
+/// exactly.
+pub struct Row<'a, M> {
+    matrix: &'a M,
+    index: usize,
+}
+
+impl<M> Clone for Row<'_, M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for Row<'_, M> {}
+
+impl<T, M> InfiniteArray<T> for Row<'_, M>
+where
+    M: InfiniteMatrix<T>,
+{
+    fn get(&self, index: usize) -> T {
+        self.matrix.get(self.index, index)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |j| self.matrix.get(self.index, j)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.matrix.cols()
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        let (_, upper) = self.matrix.bandwidths()?;
+        Some(self.index.saturating_add(upper).saturating_add(1))
+    }
+}
+
+impl<T, M> InfiniteVector<T> for Row<'_, M> where M: InfiniteMatrix<T> {}
+
+/// A column of an infinite matrix, see [`InfiniteMatrix::col`]
+///
+/// Like [`Row`], columns are `Copy` and have finite support when the matrix
+/// is banded.
+pub struct Col<'a, M> {
+    matrix: &'a M,
+    index: usize,
+}
+
+impl<M> Clone for Col<'_, M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for Col<'_, M> {}
+
+impl<T, M> InfiniteArray<T> for Col<'_, M>
+where
+    M: InfiniteMatrix<T>,
+{
+    fn get(&self, index: usize) -> T {
+        self.matrix.get(index, self.index)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |i| self.matrix.get(i, self.index)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.matrix.rows()
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        let (lower, _) = self.matrix.bandwidths()?;
+        Some(self.index.saturating_add(lower).saturating_add(1))
+    }
+}
+
+impl<T, M> InfiniteVector<T> for Col<'_, M> where M: InfiniteMatrix<T> {}
+
+/// An infinite matrix from a function of the row and column index
+#[derive(Clone)]
+pub struct InfiniteMatrixFromFn<F, T> {
+    f: F,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<F, T> InfiniteMatrixFromFn<F, T>
+where
+    F: Fn(usize, usize) -> T,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            f,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<F, T> InfiniteMatrix<T> for InfiniteMatrixFromFn<F, T>
+where
+    F: Fn(usize, usize) -> T,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        (self.f)(i, j)
+    }
+}
+
+/// A dense finite matrix given by its rows
+///
+/// Indexing outside the matrix panics, as for a slice.
+impl<T> InfiniteMatrix<T> for Vec<Vec<T>>
+where
+    T: Clone,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        self[i][j].clone()
+    }
+
+    fn rows(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn cols(&self) -> Option<usize> {
+        Some(self.first().map_or(0, Vec::len))
+    }
+}
+
+/// The lazy sum of two infinite matrices
+///
+/// If both operands are banded, so is the sum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MatrixSum<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> MatrixSum<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<T, A, B> InfiniteMatrix<T> for MatrixSum<A, B>
+where
+    T: Add<Output = T>,
+    A: InfiniteMatrix<T>,
+    B: InfiniteMatrix<T>,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        self.a.get(i, j) + self.b.get(i, j)
+    }
+
+    fn rows(&self) -> Option<usize> {
+        match (self.a.rows(), self.b.rows()) {
+            (Some(m), Some(n)) => Some(m.min(n)),
+            (m, n) => m.or(n),
+        }
+    }
+
+    fn cols(&self) -> Option<usize> {
+        match (self.a.cols(), self.b.cols()) {
+            (Some(m), Some(n)) => Some(m.min(n)),
+            (m, n) => m.or(n),
+        }
+    }
+
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        let (la, ua) = self.a.bandwidths()?;
+        let (lb, ub) = self.b.bandwidths()?;
+        Some((la.max(lb), ua.max(ub)))
+    }
+}
+
+/// The infinite identity matrix, with `get(i, j) = δᵢⱼ`
+#[derive(Debug, Clone, Copy)]
+pub struct Eye<T> {
+    _phantom: std::marker::PhantomData<T>,
+}
+
+marker_eq_hash!(Eye);
+
+impl<T> Eye<T> {
+    pub fn new() -> Self {
+        Self {
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// The product `I v`, which is `v` itself
+    pub fn apply<V>(&self, vector: V) -> V
+    where
+        V: InfiniteArray<T>,
+    {
+        vector
+    }
+}
+
+impl<T> Default for Eye<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> InfiniteMatrix<T> for Eye<T>
+where
+    T: Zero + One,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        if i == j {
+            T::one()
+        } else {
+            T::zero()
+        }
+    }
+
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        Some((0, 0))
+    }
+}
+
+impl<T, M> Add<M> for Eye<T>
+where
+    T: Zero + One + Add<Output = T>,
+    M: InfiniteMatrix<T>,
+{
+    type Output = MatrixSum<Eye<T>, M>;
+
+    /// `I + M`, which keeps the bandwidths of `M` (at least diagonal)
+    fn add(self, other: M) -> Self::Output {
+        MatrixSum::new(self, other)
+    }
+}
+
+impl<T, M> Mul<M> for Eye<T>
+where
+    M: InfiniteMatrix<T>,
+{
+    type Output = M;
+
+    /// `I M = M`
+    fn mul(self, other: M) -> M {
+        other
+    }
+}
+
+/// A diagonal matrix whose diagonal is an infinite array
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Diagonal<A> {
+    diagonal: A,
+}
+
+impl<A> Diagonal<A> {
+    pub fn new(diagonal: A) -> Self {
+        Self { diagonal }
+    }
+
+    /// The diagonal entries
+    pub fn diagonal(&self) -> &A {
+        &self.diagonal
+    }
+}
+
+impl<T, A> InfiniteMatrix<T> for Diagonal<A>
+where
+    T: Zero,
+    A: InfiniteArray<T>,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        if i == j {
+            self.diagonal.get(i)
+        } else {
+            T::zero()
+        }
+    }
+
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        Some((0, 0))
+    }
+}
+
+/// The rank-one matrix `a bᵀ`, see [`outer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outer<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Outer<A, B> {
+    /// The column factor `a`
+    pub fn left(&self) -> &A {
+        &self.a
+    }
+
+    /// The row factor `b`
+    pub fn right(&self) -> &B {
+        &self.b
+    }
+
+    /// The product `(a bᵀ) v = ⟨b, v⟩ a`
+    ///
+    /// Only the single inner product `⟨b, v⟩` is summed, with [`dot`] and
+    /// the tolerance `tol`; the result is `a` scaled by it.
+    pub fn apply<T, V>(&self, v: &V, tol: T) -> Result<InfiniteArrayFromFn<impl Fn(usize) -> T, T>, SeriesError>
+    where
+        T: Float,
+        A: InfiniteArray<T> + Clone,
+        B: InfiniteArray<T>,
+        V: InfiniteArray<T>,
+    {
+        let scale = dot(&self.b, v, tol)?.value;
+        Ok(mul_scalar(self.a.clone(), scale))
+    }
+}
+
+impl<T, A, B> InfiniteMatrix<T> for Outer<A, B>
+where
+    T: Zero + PartialEq + Mul<Output = T>,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        self.a.get(i) * self.b.get(j)
+    }
+
+    fn rows(&self) -> Option<usize> {
+        self.a.len()
+    }
+
+    fn cols(&self) -> Option<usize> {
+        self.b.len()
+    }
+
+    /// Finite supports of both factors confine the matrix to a finite block
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        let rows = self.a.support()?;
+        let cols = self.b.support()?;
+        Some((rows.saturating_sub(1), cols.saturating_sub(1)))
+    }
+}
+
+/// The outer product `a bᵀ`, with `get(i, j) = a[i] * b[j]`
+pub fn outer<A, B>(a: A, b: B) -> Outer<A, B> {
+    Outer { a, b }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{InfiniteArrayFromFn, Ones, PaddedArray};
+    use crate::operations::cumsum;
+    use crate::reductions::dot;
+
+    #[test]
+    fn test_matrix_from_fn() {
+        let hilbert = InfiniteMatrixFromFn::new(|i, j| 1.0 / (i + j + 1) as f64);
+        assert_eq!(hilbert.get(0, 0), 1.0);
+        assert_eq!(hilbert.get(1, 2), 0.25);
+        assert_eq!(hilbert.rows(), None);
+    }
+
+    #[test]
+    fn test_row_and_col() {
+        let m = InfiniteMatrixFromFn::new(|i, j| 10 * i + j);
+        let row = m.row(3);
+        assert_eq!(row.get(0), 30);
+        assert_eq!(row.get(4), 34);
+        let col = m.col(2);
+        let prefix: Vec<usize> = col.iter().take(3).collect();
+        assert_eq!(prefix, vec![2, 12, 22]);
+    }
+
+    #[test]
+    fn test_views_in_operations() {
+        let m = InfiniteMatrixFromFn::new(|i, j| (i + j) as f64);
+        let partial = cumsum(m.row(2));
+        assert_eq!(partial.get(3), 2.0 + 3.0 + 4.0 + 5.0);
+
+        // A tridiagonal row has finite support, so the dot product is exact
+        let t = BandedMatrix::new(1, 1, vec![Ones::new(), Ones::new(), Ones::new()]);
+        assert_eq!(t.row(4).support(), Some(6));
+        assert_eq!(t.col(0).support(), Some(2));
+        let report = dot(&t.row(4), &t.col(5), 1e-12).unwrap();
+        assert_eq!(report.value, 2.0);
+        assert!(report.is_rigorous());
+    }
+
+    #[test]
+    fn test_outer() {
+        let a = InfiniteArrayFromFn::new(|i| 0.5f64.powi(i as i32));
+        let b = PaddedArray::zero_padded(vec![1.0, 2.0]);
+        let m = outer(a, b);
+        assert_eq!(m.get(1, 1), 1.0);
+        assert_eq!(m.get(3, 5), 0.0);
+        assert_eq!(m.bandwidths(), None);
+
+        let v = Ones::new();
+        let mv = m.apply(&v, 1e-12).unwrap();
+        assert_eq!(mv.get(0), 3.0);
+        assert_eq!(mv.get(2), 0.75);
+    }
+
+    #[test]
+    fn test_eye() {
+        let eye: Eye<f64> = Eye::new();
+        assert_eq!(eye.get(3, 3), 1.0);
+        assert_eq!(eye.get(3, 4), 0.0);
+        assert_eq!(eye.bandwidths(), Some((0, 0)));
+        assert_eq!(eye.row(3).support(), Some(4));
+        assert_eq!((eye.row(usize::MAX).support(), eye.col(usize::MAX).support()), (Some(usize::MAX), Some(usize::MAX)));
+
+        let v = InfiniteArrayFromFn::new(|i| i as f64);
+        assert_eq!(eye.apply(v).get(7), 7.0);
+    }
+
+    #[test]
+    fn test_eye_fast_paths() {
+        let eye: Eye<f64> = Eye::new();
+        let d = Diagonal::new(InfiniteArrayFromFn::new(|i| i as f64));
+        let sum = eye + d.clone();
+        assert_eq!(sum.get(2, 2), 3.0);
+        assert_eq!(sum.get(2, 3), 0.0);
+        assert_eq!(sum.bandwidths(), Some((0, 0)));
+
+        let hilbert = InfiniteMatrixFromFn::new(|i, j| 1.0 / (i + j + 1) as f64);
+        assert_eq!((eye + hilbert).bandwidths(), None);
+        assert_eq!((eye * d).get(4, 4), 4.0);
+
+        let block = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]];
+        let sum = eye + block.clone();
+        assert_eq!((sum.rows(), sum.cols(), sum.get(1, 1)), (Some(2), Some(3), 6.0));
+        let sum = MatrixSum::new(block, eye);
+        assert_eq!((sum.rows(), sum.cols()), (Some(2), Some(3)));
+    }
+}
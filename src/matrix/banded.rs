@@ -0,0 +1,328 @@
+//! Banded matrices whose diagonals are infinite arrays
+
+use super::InfiniteMatrix;
+use crate::arrays::{Indexed, InfiniteArray, InfiniteArrayFromFn};
+use num_traits::Zero;
+use std::ops::{Add, Mul};
+use std::rc::Rc;
+
+/// An infinite matrix that is zero outside a fixed band around the diagonal
+///
+/// Diagonal `k` (with `k = j - i` running from `-lower` to `upper`) is stored
+/// as an infinite array indexed by position along the diagonal, i.e. by
+/// `min(i, j)`. Bands of different kinds can be mixed by storing them as
+/// `Box<dyn InfiniteArray<T>>` or `Rc<dyn InfiniteArray<T>>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BandedMatrix<A> {
+    lower: usize,
+    upper: usize,
+    bands: Vec<A>,
+}
+
+impl<A> BandedMatrix<A> {
+    /// Build a banded matrix from its diagonals, ordered from the lowest
+    /// subdiagonal `-lower` up to the highest superdiagonal `upper`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bands.len() != lower + upper + 1`.
+    pub fn new(lower: usize, upper: usize, bands: Vec<A>) -> Self {
+        assert_eq!(
+            bands.len(),
+            lower + upper + 1,
+            "a banded matrix with bandwidths ({}, {}) needs {} bands",
+            lower,
+            upper,
+            lower + upper + 1
+        );
+        Self { lower, upper, bands }
+    }
+
+    /// Number of subdiagonals
+    pub fn lower(&self) -> usize {
+        self.lower
+    }
+
+    /// Number of superdiagonals
+    pub fn upper(&self) -> usize {
+        self.upper
+    }
+
+    /// Diagonal `k = j - i`, or `None` outside the band
+    pub fn band(&self, k: isize) -> Option<&A> {
+        let offset = k + self.lower as isize;
+        if offset < 0 {
+            return None;
+        }
+        self.bands.get(offset as usize)
+    }
+
+    /// All diagonals, from the lowest subdiagonal up
+    pub fn bands(&self) -> &[A] {
+        &self.bands
+    }
+
+    /// Columns with possibly nonzero entries in row `i`
+    pub(crate) fn row_range(&self, i: usize) -> std::ops::RangeInclusive<usize> {
+        i.saturating_sub(self.lower)..=i + self.upper
+    }
+
+    /// The lazy product `A v`
+    ///
+    /// Each entry sums only the `lower + upper + 1` entries of its row that
+    /// lie inside the band.
+    pub fn apply<T, V>(&self, v: V) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
+    where
+        T: Zero + Add<Output = T> + Mul<Output = T>,
+        A: InfiniteArray<T> + Clone,
+        V: InfiniteArray<T>,
+    {
+        let matrix = self.clone();
+        InfiniteArrayFromFn::new(move |i| {
+            matrix
+                .row_range(i)
+                .fold(T::zero(), |acc, j| acc + matrix.get(i, j) * v.get(j))
+        })
+    }
+}
+
+impl<T, A> InfiniteMatrix<T> for BandedMatrix<A>
+where
+    T: Zero,
+    A: InfiniteArray<T>,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        if i > j + self.lower || j > i + self.upper {
+            return T::zero();
+        }
+        self.bands[j + self.lower - i].get(i.min(j))
+    }
+
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        Some((self.lower, self.upper))
+    }
+}
+
+impl<A, B> Mul<BandedMatrix<B>> for BandedMatrix<A> {
+    type Output = BandedMatrix<ProductBand<A, B>>;
+
+    /// The product `A B`, banded with bandwidths
+    /// `(l_A + l_B, u_A + u_B)`
+    ///
+    /// Each band of the result is a lazy [`ProductBand`] sharing both
+    /// factors, so products can be chained (e.g. `D * D * D`) without
+    /// computing any entries up front.
+    fn mul(self, other: BandedMatrix<B>) -> Self::Output {
+        let (lower, upper) = (self.lower + other.lower, self.upper + other.upper);
+        let (a, b) = (Rc::new(self), Rc::new(other));
+        let bands = (0..=lower + upper)
+            .map(|offset| ProductBand {
+                a: Rc::clone(&a),
+                b: Rc::clone(&b),
+                k: offset as isize - lower as isize,
+            })
+            .collect();
+        BandedMatrix::new(lower, upper, bands)
+    }
+}
+
+/// Diagonal `k` of the product of two banded matrices, see
+/// [`BandedMatrix`]'s `Mul` implementation
+#[derive(Debug)]
+pub struct ProductBand<A, B> {
+    a: Rc<BandedMatrix<A>>,
+    b: Rc<BandedMatrix<B>>,
+    k: isize,
+}
+
+impl<A, B> Clone for ProductBand<A, B> {
+    fn clone(&self) -> Self {
+        Self {
+            a: Rc::clone(&self.a),
+            b: Rc::clone(&self.b),
+            k: self.k,
+        }
+    }
+}
+
+impl<T, A, B> InfiniteArray<T> for ProductBand<A, B>
+where
+    T: Zero + Add<Output = T> + Mul<Output = T>,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        let (i, j) = if self.k >= 0 {
+            (index, index + self.k as usize)
+        } else {
+            (index + self.k.unsigned_abs(), index)
+        };
+        // Inner indices inside the bands of both factors
+        let first = i.saturating_sub(self.a.lower).max(j.saturating_sub(self.b.upper));
+        let last = (i + self.a.upper).min(j + self.b.lower);
+        (first..=last).fold(T::zero(), |acc, m| acc + self.a.get(i, m) * self.b.get(m, j))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+}
+
+/// An infinite tridiagonal matrix with separate sub, main and super diagonals
+///
+/// Like [`BandedMatrix`], each diagonal is indexed by `min(i, j)`: entry
+/// `(i + 1, i)` is `sub[i]` and entry `(i, i + 1)` is `sup[i]`. Unlike
+/// `BandedMatrix`, the three diagonals may have different types.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tridiagonal<L, D, U> {
+    sub: L,
+    main: D,
+    sup: U,
+}
+
+impl<L, D, U> Tridiagonal<L, D, U> {
+    pub fn new(sub: L, main: D, sup: U) -> Self {
+        Self { sub, main, sup }
+    }
+
+    /// The subdiagonal, with `sub[i]` at `(i + 1, i)`
+    pub fn sub(&self) -> &L {
+        &self.sub
+    }
+
+    /// The main diagonal
+    pub fn main(&self) -> &D {
+        &self.main
+    }
+
+    /// The superdiagonal, with `sup[i]` at `(i, i + 1)`
+    pub fn sup(&self) -> &U {
+        &self.sup
+    }
+
+    /// The lazy product `T v`, with entry `i` equal to
+    /// `sub[i-1] v[i-1] + main[i] v[i] + sup[i] v[i+1]`
+    pub fn apply<T, V>(&self, v: V) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
+    where
+        T: Add<Output = T> + Mul<Output = T>,
+        L: InfiniteArray<T> + Clone,
+        D: InfiniteArray<T> + Clone,
+        U: InfiniteArray<T> + Clone,
+        V: InfiniteArray<T>,
+    {
+        let (sub, main, sup) = (self.sub.clone(), self.main.clone(), self.sup.clone());
+        InfiniteArrayFromFn::new(move |i| {
+            let value = main.get(i) * v.get(i) + sup.get(i) * v.get(i + 1);
+            if i == 0 {
+                value
+            } else {
+                sub.get(i - 1) * v.get(i - 1) + value
+            }
+        })
+    }
+}
+
+impl<T, L, D, U> InfiniteMatrix<T> for Tridiagonal<L, D, U>
+where
+    T: Zero,
+    L: InfiniteArray<T>,
+    D: InfiniteArray<T>,
+    U: InfiniteArray<T>,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        if i == j {
+            self.main.get(i)
+        } else if i == j + 1 {
+            self.sub.get(j)
+        } else if j == i + 1 {
+            self.sup.get(i)
+        } else {
+            T::zero()
+        }
+    }
+
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        Some((1, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArrayFromFn, PaddedArray};
+
+    #[test]
+    fn test_banded_get() {
+        let m = BandedMatrix::new(1, 2, vec![Fill::new(-1.0), Fill::new(2.0), Fill::new(3.0), Fill::new(4.0)]);
+        assert_eq!(m.get(5, 4), -1.0);
+        assert_eq!(m.get(5, 5), 2.0);
+        assert_eq!(m.get(5, 7), 4.0);
+        assert_eq!(m.get(5, 3), 0.0);
+        assert_eq!(m.get(5, 8), 0.0);
+        assert_eq!(m.bandwidths(), Some((1, 2)));
+        assert_eq!(m.band(-1), Some(&Fill::new(-1.0)));
+        assert_eq!(m.band(3), None);
+    }
+
+    #[test]
+    fn test_banded_mixed_bands() {
+        // Diagonal i + 1 with a constant superdiagonal
+        let bands: Vec<Box<dyn InfiniteArray<f64>>> = vec![
+            Box::new(InfiniteArrayFromFn::new(|i| (i + 1) as f64)),
+            Box::new(Fill::new(0.5)),
+        ];
+        let m = BandedMatrix::new(0, 1, bands);
+        assert_eq!(m.get(3, 3), 4.0);
+        assert_eq!(m.get(3, 4), 0.5);
+    }
+
+    #[test]
+    fn test_banded_apply() {
+        // Second difference operator
+        let m = BandedMatrix::new(1, 1, vec![Fill::new(1.0), Fill::new(-2.0), Fill::new(1.0)]);
+        let squares = InfiniteArrayFromFn::new(|i| (i * i) as f64);
+        let result = m.apply(squares);
+        assert_eq!(result.get(0), 1.0);
+        assert_eq!(result.get(10), 2.0);
+    }
+
+    #[test]
+    fn test_banded_product() {
+        // Forward difference D with (D v)_i = v_{i+1} - v_i, so D² is the
+        // second forward difference
+        let d = BandedMatrix::new(0, 1, vec![Fill::new(-1.0), Fill::new(1.0)]);
+        let d2 = d.clone() * d.clone();
+        assert_eq!(d2.bandwidths(), Some((0, 2)));
+        assert_eq!(d2.get(4, 4), 1.0);
+        assert_eq!(d2.get(4, 5), -2.0);
+        assert_eq!(d2.get(4, 6), 1.0);
+
+        let cubes = InfiniteArrayFromFn::new(|i| (i * i * i) as f64);
+        assert_eq!(d2.apply(cubes).get(1), 6.0 * 2.0);
+
+        // Against a dense computation for a non-symmetric band structure
+        let a = BandedMatrix::new(1, 0, vec![PaddedArray::new(vec![1.0, 2.0, 3.0], 4.0), PaddedArray::new(vec![2.0, 1.0], -1.0)]);
+        let product = a.clone() * d.clone();
+        for i in 0..6 {
+            for j in 0..6 {
+                let expected: f64 = (0..8).map(|m| a.get(i, m) * d.get(m, j)).sum();
+                assert_eq!(product.get(i, j), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tridiagonal() {
+        // Jacobi operator of the Chebyshev polynomials of the second kind
+        let t = Tridiagonal::new(Fill::new(0.5), Fill::new(0.0), Fill::new(0.5));
+        assert_eq!(t.get(3, 2), 0.5);
+        assert_eq!(t.get(3, 3), 0.0);
+        assert_eq!(t.get(3, 5), 0.0);
+        assert_eq!(t.bandwidths(), Some((1, 1)));
+
+        let v = InfiniteArrayFromFn::new(|i| i as f64);
+        let tv = t.apply(v);
+        assert_eq!(tv.get(0), 0.5);
+        assert_eq!(tv.get(4), 4.0);
+    }
+}
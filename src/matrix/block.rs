@@ -0,0 +1,160 @@
+//! Block-diagonal matrices built from an infinite sequence of finite blocks
+
+use super::InfiniteMatrix;
+use crate::arrays::{InfiniteArray, InfiniteArrayFromFn};
+use num_traits::Zero;
+use std::cell::RefCell;
+use std::ops::{Add, Mul};
+
+/// Generated blocks and the global index at which each one starts
+struct BlockLayout<T> {
+    starts: Vec<usize>,
+    blocks: Vec<Vec<Vec<T>>>,
+}
+
+/// An infinite block-diagonal matrix whose `k`-th diagonal block is a finite
+/// square matrix produced by a function of `k`
+///
+/// Blocks are given as rows (`block[a][b]` is local entry `(a, b)`) and may
+/// have different sizes. They are generated on first use and cached
+/// together with their offsets, so locating the block of an index costs a
+/// binary search once the blocks up to it have been built.
+pub struct BlockDiagonal<T, F> {
+    generator: F,
+    layout: RefCell<BlockLayout<T>>,
+}
+
+impl<T, F> BlockDiagonal<T, F>
+where
+    T: Clone,
+    F: Fn(usize) -> Vec<Vec<T>>,
+{
+    pub fn new(generator: F) -> Self {
+        Self {
+            generator,
+            layout: RefCell::new(BlockLayout {
+                starts: vec![0],
+                blocks: Vec::new(),
+            }),
+        }
+    }
+
+    /// Generate blocks until block `k` exists
+    ///
+    /// # Panics
+    ///
+    /// Panics if a generated block is empty or not square.
+    fn ensure_block(&self, k: usize) {
+        let mut layout = self.layout.borrow_mut();
+        while layout.blocks.len() <= k {
+            let index = layout.blocks.len();
+            let block = (self.generator)(index);
+            let size = block.len();
+            assert!(size > 0, "block {} is empty", index);
+            assert!(
+                block.iter().all(|row| row.len() == size),
+                "block {} is not square",
+                index
+            );
+            let start = layout.starts[index];
+            layout.starts.push(start + size);
+            layout.blocks.push(block);
+        }
+    }
+
+    /// The block containing global index `i` and the local index within it
+    fn locate(&self, i: usize) -> (usize, usize) {
+        loop {
+            {
+                let layout = self.layout.borrow();
+                let end = *layout.starts.last().unwrap();
+                if i < end {
+                    let k = layout.starts.partition_point(|&s| s <= i) - 1;
+                    return (k, i - layout.starts[k]);
+                }
+            }
+            let built = self.layout.borrow().blocks.len();
+            self.ensure_block(built);
+        }
+    }
+
+    /// The `k`-th diagonal block
+    pub fn block(&self, k: usize) -> Vec<Vec<T>> {
+        self.ensure_block(k);
+        self.layout.borrow().blocks[k].clone()
+    }
+
+    /// Global index of the first row and column of block `k`
+    pub fn block_start(&self, k: usize) -> usize {
+        self.ensure_block(k);
+        self.layout.borrow().starts[k]
+    }
+
+    /// The lazy product `B v`
+    ///
+    /// Entry `i` only involves the row of `i` within its own block, so it
+    /// costs one block-sized dot product.
+    pub fn apply<'a, V>(&'a self, v: V) -> InfiniteArrayFromFn<impl Fn(usize) -> T + 'a, T>
+    where
+        T: Zero + Add<Output = T> + Mul<Output = T>,
+        V: InfiniteArray<T> + 'a,
+    {
+        InfiniteArrayFromFn::new(move |i| {
+            let (k, a) = self.locate(i);
+            let layout = self.layout.borrow();
+            let start = layout.starts[k];
+            layout.blocks[k][a]
+                .iter()
+                .enumerate()
+                .fold(T::zero(), |acc, (b, x)| acc + x.clone() * v.get(start + b))
+        })
+    }
+}
+
+impl<T, F> InfiniteMatrix<T> for BlockDiagonal<T, F>
+where
+    T: Zero + Clone,
+    F: Fn(usize) -> Vec<Vec<T>>,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        let (k, a) = self.locate(i);
+        let (l, b) = self.locate(j);
+        if k != l {
+            return T::zero();
+        }
+        self.layout.borrow().blocks[k][a][b].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+
+    #[test]
+    fn test_block_diagonal_repeated() {
+        // Rotation by 90° in each coordinate plane
+        let b = BlockDiagonal::new(|_| vec![vec![0.0, -1.0], vec![1.0, 0.0]]);
+        assert_eq!(b.get(4, 5), -1.0);
+        assert_eq!(b.get(5, 4), 1.0);
+        assert_eq!(b.get(5, 6), 0.0);
+        assert_eq!(b.block_start(3), 6);
+
+        let v = InfiniteArrayFromFn::new(|i| i as f64);
+        let bv = b.apply(v);
+        assert_eq!(bv.get(0), -1.0);
+        assert_eq!(bv.get(7), 6.0);
+    }
+
+    #[test]
+    fn test_block_diagonal_growing() {
+        // Block k is the (k + 1) × (k + 1) all-k matrix
+        let b = BlockDiagonal::new(|k| vec![vec![k as f64; k + 1]; k + 1]);
+        assert_eq!(b.block_start(4), 10);
+        assert_eq!(b.get(10, 14), 4.0);
+        assert_eq!(b.get(9, 10), 0.0);
+
+        let bv = b.apply(InfiniteArrayFromFn::new(|_| 1.0));
+        assert_eq!(bv.get(12), 20.0);
+    }
+}
@@ -0,0 +1,269 @@
+//! Toeplitz and Hankel matrices, constant along diagonals or anti-diagonals
+
+use super::InfiniteMatrix;
+use crate::arrays::{InfiniteArray, InfiniteArrayFromFn, PaddedArray};
+use num_traits::Zero;
+use std::ops::{Add, Mul};
+
+/// An infinite Toeplitz matrix with `get(i, j) = col[i - j]` on and below
+/// the diagonal and `row[j - i]` above it
+///
+/// `row[0]` is never read; the diagonal comes from `col[0]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Toeplitz<C, R> {
+    col: C,
+    row: R,
+}
+
+impl<C, R> Toeplitz<C, R> {
+    pub fn new(col: C, row: R) -> Self {
+        Self { col, row }
+    }
+
+    /// The first column, giving the diagonal and the subdiagonals
+    pub fn first_col(&self) -> &C {
+        &self.col
+    }
+
+    /// The first row, giving the superdiagonals
+    pub fn first_row(&self) -> &R {
+        &self.row
+    }
+
+    /// The lazy product `T v`
+    ///
+    /// Entry `i` is the finite convolution `Σ_{j<=i} col[i-j] v[j]`, limited
+    /// to the support of `col` when known, plus the contribution of the
+    /// superdiagonals, which is summed only as far as the support of `row` or
+    /// of `v` reaches.
+    ///
+    /// # Panics
+    ///
+    /// Panics if neither the first row nor `v` has finite
+    /// [`support`](InfiniteArray::support), since the entries would then be
+    /// infinite sums.
+    pub fn apply<T, V>(&self, v: V) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
+    where
+        T: Zero + PartialEq + Add<Output = T> + Mul<Output = T> + Copy,
+        C: InfiniteArray<T> + Clone,
+        R: InfiniteArray<T> + Clone,
+        V: InfiniteArray<T>,
+    {
+        let col_support = self.col.support();
+        let row_support = self.row.support();
+        let vector_support = v.support();
+        assert!(
+            row_support.is_some() || vector_support.is_some(),
+            "Toeplitz matvec requires a first row or vector with finite support"
+        );
+        let (col, row) = (self.col.clone(), self.row.clone());
+        InfiniteArrayFromFn::new(move |i| {
+            let first = col_support.map_or(0, |s| (i + 1).saturating_sub(s));
+            let lower = (first..=i).fold(T::zero(), |acc, j| acc + col.get(i - j) * v.get(j));
+            let reach = match (row_support, vector_support) {
+                (Some(s), Some(m)) => s.min(m.saturating_sub(i)),
+                (Some(s), None) => s,
+                (None, Some(m)) => m.saturating_sub(i),
+                (None, None) => unreachable!(),
+            };
+            (1..reach).fold(lower, |acc, k| acc + row.get(k) * v.get(i + k))
+        })
+    }
+}
+
+impl<T, C, R> InfiniteMatrix<T> for Toeplitz<C, R>
+where
+    T: Zero + PartialEq,
+    C: InfiniteArray<T>,
+    R: InfiniteArray<T>,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        if i >= j {
+            self.col.get(i - j)
+        } else {
+            self.row.get(j - i)
+        }
+    }
+
+    fn bandwidths(&self) -> Option<(usize, usize)> {
+        let lower = self.col.support()?;
+        let upper = self.row.support()?;
+        Some((lower.saturating_sub(1), upper.saturating_sub(1)))
+    }
+}
+
+impl<C, R> Toeplitz<C, R> {
+    /// The Hankel matrix whose leading `n × n` section is this matrix's
+    /// leading section with its columns reversed
+    ///
+    /// An infinite matrix has no last column to flip around, so the
+    /// correspondence `H_n = T_n J_n` (with `J_n` the exchange matrix) holds
+    /// for the chosen section size `n` only.
+    pub fn to_hankel<T>(&self, n: usize) -> Hankel<InfiniteArrayFromFn<impl Fn(usize) -> T + Clone, T>>
+    where
+        C: InfiniteArray<T> + Clone,
+        R: InfiniteArray<T> + Clone,
+    {
+        assert!(n > 0, "section size must be positive");
+        let (col, row) = (self.col.clone(), self.row.clone());
+        Hankel::new(InfiniteArrayFromFn::new(move |m| {
+            if m + 1 >= n {
+                col.get(m + 1 - n)
+            } else {
+                row.get(n - 1 - m)
+            }
+        }))
+    }
+}
+
+/// An infinite Hankel matrix with `get(i, j) = a[i + j]`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hankel<A> {
+    generator: A,
+}
+
+impl<A> Hankel<A> {
+    pub fn new(generator: A) -> Self {
+        Self { generator }
+    }
+
+    /// The generating array `a`
+    pub fn generator(&self) -> &A {
+        &self.generator
+    }
+
+    /// The lazy product `H v`
+    ///
+    /// Entry `i` is `Σ_j a[i + j] v[j]`, summed over the support of `v` or,
+    /// for rows that reach it, the support of `a`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if neither `a` nor `v` has finite
+    /// [`support`](InfiniteArray::support).
+    pub fn apply<T, V>(&self, v: V) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
+    where
+        T: Zero + PartialEq + Add<Output = T> + Mul<Output = T> + Copy,
+        A: InfiniteArray<T> + Clone,
+        V: InfiniteArray<T>,
+    {
+        let generator_support = self.generator.support();
+        let vector_support = v.support();
+        assert!(
+            generator_support.is_some() || vector_support.is_some(),
+            "Hankel matvec requires a generator or vector with finite support"
+        );
+        let a = self.generator.clone();
+        InfiniteArrayFromFn::new(move |i| {
+            let reach = match (generator_support, vector_support) {
+                (Some(s), Some(m)) => s.saturating_sub(i).min(m),
+                (Some(s), None) => s.saturating_sub(i),
+                (None, Some(m)) => m,
+                (None, None) => unreachable!(),
+            };
+            (0..reach).fold(T::zero(), |acc, j| acc + a.get(i + j) * v.get(j))
+        })
+    }
+
+    /// The Toeplitz matrix whose leading `n × n` section is this matrix's
+    /// leading section with its columns reversed
+    ///
+    /// As with [`Toeplitz::to_hankel`], the flip is relative to the section
+    /// size `n`; superdiagonals beyond the section are zero.
+    pub fn to_toeplitz<T>(
+        &self,
+        n: usize,
+    ) -> Toeplitz<InfiniteArrayFromFn<impl Fn(usize) -> T + Clone, T>, PaddedArray<T>>
+    where
+        T: Zero + Copy,
+        A: InfiniteArray<T> + Clone,
+    {
+        assert!(n > 0, "section size must be positive");
+        let a = self.generator.clone();
+        let row = (0..n).map(|k| self.generator.get(n - 1 - k)).collect();
+        Toeplitz::new(
+            InfiniteArrayFromFn::new(move |k| a.get(n - 1 + k)),
+            PaddedArray::zero_padded(row),
+        )
+    }
+}
+
+impl<T, A> InfiniteMatrix<T> for Hankel<A>
+where
+    A: InfiniteArray<T>,
+{
+    fn get(&self, i: usize, j: usize) -> T {
+        self.generator.get(i + j)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{InfiniteArrayFromFn, Ones, PaddedArray};
+
+    #[test]
+    fn test_toeplitz_get() {
+        let t = Toeplitz::new(
+            InfiniteArrayFromFn::new(|i| i as f64),
+            InfiniteArrayFromFn::new(|i| -(i as f64)),
+        );
+        assert_eq!(t.get(5, 2), 3.0);
+        assert_eq!(t.get(2, 5), -3.0);
+        assert_eq!(t.get(4, 4), 0.0);
+        assert_eq!(t.bandwidths(), None);
+    }
+
+    #[test]
+    fn test_toeplitz_apply() {
+        // Lower-bidiagonal difference operator: (T v)_i = v_i - v_{i-1}
+        let t = Toeplitz::new(
+            PaddedArray::zero_padded(vec![1.0, -1.0]),
+            PaddedArray::zero_padded(vec![0.0]),
+        );
+        assert_eq!(t.bandwidths(), Some((1, 0)));
+        let squares = InfiniteArrayFromFn::new(|i| (i * i) as f64);
+        let diff = t.apply(squares);
+        assert_eq!(diff.get(0), 0.0);
+        assert_eq!(diff.get(5), 9.0);
+
+        // Upper part summed over the vector's support
+        let upper = Toeplitz::new(PaddedArray::zero_padded(vec![1.0]), Ones::new());
+        let v = PaddedArray::zero_padded(vec![1.0, 2.0, 3.0]);
+        let result = upper.apply(v);
+        assert_eq!(result.get(0), 6.0);
+        assert_eq!(result.get(1), 5.0);
+        assert_eq!(result.get(3), 0.0);
+    }
+
+    #[test]
+    fn test_hankel() {
+        let h = Hankel::new(InfiniteArrayFromFn::new(|k| k as f64));
+        assert_eq!(h.get(2, 3), 5.0);
+        assert_eq!(h.get(3, 2), 5.0);
+
+        let v = PaddedArray::zero_padded(vec![1.0, 1.0]);
+        let hv = h.apply(v);
+        assert_eq!(hv.get(0), 1.0);
+        assert_eq!(hv.get(10), 21.0);
+    }
+
+    #[test]
+    fn test_hankel_toeplitz_flips() {
+        let n = 4;
+        let h = Hankel::new(InfiniteArrayFromFn::new(|k| (k * k) as f64));
+        let t = h.to_toeplitz(n);
+        for i in 0..n {
+            for j in 0..n {
+                assert_eq!(t.get(i, j), h.get(i, n - 1 - j));
+            }
+        }
+
+        let back = t.to_hankel(n);
+        for i in 0..n {
+            for j in 0..n {
+                assert_eq!(back.get(i, j), h.get(i, j));
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Containing NaNs in float arrays
+//!
+//! One NaN early in a lazily composed pipeline turns every cumulative sum,
+//! product or reduction after it into NaN as well. [`nan_to`] replaces
+//! NaNs before they spread, [`is_nan_mask`] marks where they are, and the
+//! `nan*_prefix` reductions skip them.
+
+use crate::arrays::{Indexed, InfiniteArray, InfiniteVector};
+use crate::describe::Description;
+use num_traits::{Float, Zero};
+use std::marker::PhantomData;
+
+/// `arr` with every NaN replaced by a value, see [`nan_to`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NanTo<A, T> {
+    arr: A,
+    value: T,
+}
+
+/// `arr` with every NaN replaced by `value`
+pub fn nan_to<T, A>(arr: A, value: T) -> NanTo<A, T>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    NanTo { arr, value }
+}
+
+impl<T, A> InfiniteArray<T> for NanTo<A, T>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        let x = self.arr.get(index);
+        if x.is_nan() {
+            self.value
+        } else {
+            x
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.arr.len()
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        self.arr.support()
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        self.arr.periodicity()
+    }
+
+    fn get_range(&self, start: usize, out: &mut [T]) {
+        self.arr.get_range(start, out);
+        for x in out.iter_mut().filter(|x| x.is_nan()) {
+            *x = self.value;
+        }
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("nan_to", [self.arr.describe()])
+    }
+}
+
+impl<T: Float, A: InfiniteArray<T>> InfiniteVector<T> for NanTo<A, T> {}
+
+/// Whether each element of an array is NaN, see [`is_nan_mask`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NanMask<T, A> {
+    arr: A,
+    _phantom: PhantomData<T>,
+}
+
+/// `true` where `arr` is NaN, e.g. to pass to [`compress`](crate::compress)
+pub fn is_nan_mask<T, A>(arr: A) -> NanMask<T, A>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    NanMask {
+        arr,
+        _phantom: PhantomData,
+    }
+}
+
+impl<T, A> InfiniteArray<bool> for NanMask<T, A>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> bool {
+        self.arr.get(index).is_nan()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = bool> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.arr.len()
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        self.arr.periodicity()
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("is_nan", [self.arr.describe()])
+    }
+}
+
+impl<T: Float, A: InfiniteArray<T>> InfiniteVector<bool> for NanMask<T, A> {}
+
+/// Sum of the first `n` elements, skipping NaNs
+pub fn nansum_prefix<T, A>(arr: &A, n: usize) -> T
+where
+    T: Float,
+    A: InfiniteArray<T> + ?Sized,
+{
+    arr.iter_range(0..n).filter(|x| !x.is_nan()).fold(T::zero(), |sum, x| sum + x)
+}
+
+/// Least of the first `n` elements, skipping NaNs; `None` if all are NaN
+pub fn nanmin_prefix<T, A>(arr: &A, n: usize) -> Option<T>
+where
+    T: Float,
+    A: InfiniteArray<T> + ?Sized,
+{
+    arr.iter_range(0..n).filter(|x| !x.is_nan()).reduce(T::min)
+}
+
+/// Greatest of the first `n` elements, skipping NaNs; `None` if all are NaN
+pub fn nanmax_prefix<T, A>(arr: &A, n: usize) -> Option<T>
+where
+    T: Float,
+    A: InfiniteArray<T> + ?Sized,
+{
+    arr.iter_range(0..n).filter(|x| !x.is_nan()).reduce(T::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{EventuallyPeriodic, InfiniteArrayFromFn};
+    use crate::operations::cumsum;
+
+    #[test]
+    fn test_nan_to_stops_poisoning_cumsum() {
+        let roots = InfiniteArrayFromFn::new(|i| (i as f64 - 2.0).sqrt());
+        assert!(cumsum(&roots).get(5).is_nan());
+        let cleaned = nan_to(&roots, 0.0);
+        assert_eq!(cleaned.iter().take(4).collect::<Vec<_>>(), vec![0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(cumsum(&cleaned).get(6), 1.0 + 2f64.sqrt() + 3f64.sqrt() + 2.0);
+        assert_eq!(format!("{:?}", cleaned.describe()), "nan_to(from_fn)");
+
+        let periodic = EventuallyPeriodic::new(vec![f64::NAN], vec![1.0, f64::NAN]);
+        let mask = is_nan_mask(&periodic);
+        assert_eq!(mask.iter().take(4).collect::<Vec<_>>(), vec![true, false, true, false]);
+        assert_eq!(mask.periodicity(), Some((1, 2)));
+        let mut out = [0.0; 3];
+        nan_to(&periodic, -1.0).get_range(0, &mut out);
+        assert_eq!(out, [-1.0, 1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_nan_skipping_reductions() {
+        let x = EventuallyPeriodic::new(vec![f64::NAN, 3.0, -2.0], vec![f64::NAN]);
+        assert_eq!(nansum_prefix(&x, 10), 1.0);
+        assert_eq!(nanmin_prefix(&x, 10), Some(-2.0));
+        assert_eq!(nanmax_prefix(&x, 2), Some(3.0));
+        assert_eq!(nanmax_prefix(&x, 1), None);
+    }
+}
@@ -1,21 +1,28 @@
 //! Operations on infinite arrays
 
-use crate::arrays::{InfiniteArray, InfiniteArrayFromFn};
+use crate::arrays::{Indexed, InfiniteArray, InfiniteArrayFromFn, InfiniteVector};
+use crate::describe::Description;
+use crate::instrument::trace_event;
+use crate::matrix::InfiniteMatrix;
 use num_traits::Zero;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ops::{Add, Sub, Mul, Div};
 
 /// Cumulative sum of an infinite array
 pub fn cumsum<T, A>(arr: A) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
 where
-    T: Zero + Add<Output = T> + Copy,
+    T: Zero + Add<Output = T> + Clone,
     A: InfiniteArray<T> + Clone,
 {
+    let description = Description::new("cumsum", [arr.describe()]);
     InfiniteArrayFromFn::new(move |i| {
         // Clone the array for each cumulative sum calculation
         // This is necessary because we need to access previous elements
         let arr_clone = arr.clone();
         (0..=i).fold(T::zero(), |acc, idx| acc + arr_clone.get(idx))
     })
+    .with_description(description)
 }
 
 /// Broadcast a function over an infinite array
@@ -26,100 +33,216 @@ where
     TIn: Clone,
     TOut: Clone,
 {
+    let description = Description::new("broadcast", [arr.describe()]);
     InfiniteArrayFromFn::new(move |i| {
         let arr_clone = arr.clone();
         f(arr_clone.get(i))
     })
+    .with_description(description)
 }
 
 /// Element-wise addition of two infinite arrays
 pub fn add_arrays<T, A, B>(a: A, b: B) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
 where
-    T: Add<Output = T> + Copy,
+    T: Add<Output = T> + Clone,
     A: InfiniteArray<T> + Clone,
     B: InfiniteArray<T> + Clone,
 {
+    let description = Description::new("add", [a.describe(), b.describe()]);
     InfiniteArrayFromFn::new(move |i| {
         let a_clone = a.clone();
         let b_clone = b.clone();
         a_clone.get(i) + b_clone.get(i)
     })
+    .with_description(description)
 }
 
 /// Element-wise subtraction of two infinite arrays
 pub fn sub_arrays<T, A, B>(a: A, b: B) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
 where
-    T: Sub<Output = T> + Copy,
+    T: Sub<Output = T> + Clone,
     A: InfiniteArray<T> + Clone,
     B: InfiniteArray<T> + Clone,
 {
+    let description = Description::new("sub", [a.describe(), b.describe()]);
     InfiniteArrayFromFn::new(move |i| {
         let a_clone = a.clone();
         let b_clone = b.clone();
         a_clone.get(i) - b_clone.get(i)
     })
+    .with_description(description)
 }
 
 /// Element-wise multiplication of two infinite arrays
 pub fn mul_arrays<T, A, B>(a: A, b: B) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
 where
-    T: Mul<Output = T> + Copy,
+    T: Mul<Output = T> + Clone,
     A: InfiniteArray<T> + Clone,
     B: InfiniteArray<T> + Clone,
 {
+    let description = Description::new("mul", [a.describe(), b.describe()]);
     InfiniteArrayFromFn::new(move |i| {
         let a_clone = a.clone();
         let b_clone = b.clone();
         a_clone.get(i) * b_clone.get(i)
     })
+    .with_description(description)
 }
 
 /// Element-wise division of two infinite arrays
 pub fn div_arrays<T, A, B>(a: A, b: B) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
 where
-    T: Div<Output = T> + Copy,
+    T: Div<Output = T> + Clone,
     A: InfiniteArray<T> + Clone,
     B: InfiniteArray<T> + Clone,
 {
+    let description = Description::new("div", [a.describe(), b.describe()]);
     InfiniteArrayFromFn::new(move |i| {
         let a_clone = a.clone();
         let b_clone = b.clone();
         a_clone.get(i) / b_clone.get(i)
     })
+    .with_description(description)
 }
 
 /// Scalar addition
 pub fn add_scalar<T, A>(arr: A, scalar: T) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
 where
-    T: Add<Output = T> + Copy,
+    T: Add<Output = T> + Clone,
     A: InfiniteArray<T> + Clone,
 {
+    let description = Description::new("add_scalar", [arr.describe()]);
     InfiniteArrayFromFn::new(move |i| {
         let arr_clone = arr.clone();
-        arr_clone.get(i) + scalar
+        arr_clone.get(i) + scalar.clone()
     })
+    .with_description(description)
 }
 
 /// Scalar multiplication
 pub fn mul_scalar<T, A>(arr: A, scalar: T) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
 where
-    T: Mul<Output = T> + Copy,
+    T: Mul<Output = T> + Clone,
     A: InfiniteArray<T> + Clone,
 {
+    let description = Description::new("mul_scalar", [arr.describe()]);
     InfiniteArrayFromFn::new(move |i| {
         let arr_clone = arr.clone();
-        arr_clone.get(i) * scalar
+        arr_clone.get(i) * scalar.clone()
     })
+    .with_description(description)
+}
+
+/// The lazy product of an infinite matrix and an infinite vector, see [`matvec`]
+pub struct MatVec<T, M, V> {
+    matrix: M,
+    vector: V,
+    bandwidths: Option<(usize, usize)>,
+    support: Option<usize>,
+    cache: RefCell<HashMap<usize, T>>,
+}
+
+impl<T, M, V> MatVec<T, M, V> {
+    /// Number of entries computed and cached so far
+    pub fn cached(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+    /// Columns that can contribute to entry `i`
+    fn columns(&self, i: usize) -> std::ops::Range<usize> {
+        let (first, end) = match self.bandwidths {
+            Some((lower, upper)) => (i.saturating_sub(lower), i.saturating_add(upper).saturating_add(1)),
+            None => (0, usize::MAX),
+        };
+        let end = self.support.map_or(end, |m| end.min(m));
+        first..end.max(first)
+    }
+}
+
+impl<T, M, V> InfiniteArray<T> for MatVec<T, M, V>
+where
+    T: Zero + Add<Output = T> + Mul<Output = T> + Clone,
+    M: InfiniteMatrix<T>,
+    V: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        if let Some(value) = self.cache.borrow().get(&index) {
+            return value.clone();
+        }
+        trace_event!(index, "cache miss");
+        let value = self
+            .columns(index)
+            .fold(T::zero(), |acc, j| acc + self.matrix.get(index, j) * self.vector.get(j));
+        self.cache.borrow_mut().insert(index, value.clone());
+        value
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.matrix.rows()
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("matvec", [Description::of_type::<M>(), self.vector.describe()])
+    }
+}
+
+impl<T, M, V> InfiniteVector<T> for MatVec<T, M, V>
+where
+    T: Zero + Add<Output = T> + Mul<Output = T> + Clone,
+    M: InfiniteMatrix<T>,
+    V: InfiniteArray<T>,
+{
+}
+
+/// Lazy matrix–vector product `A v`
+///
+/// Entry `i` sums only over the columns inside the matrix's
+/// [`bandwidths`](InfiniteMatrix::bandwidths), further limited by the
+/// vector's [`support`](InfiniteArray::support) and the matrix's column
+/// count when those are finite. Computed entries are cached, so repeated
+/// access does not recompute the sum.
+///
+/// # Panics
+///
+/// Panics if the matrix is not banded and neither the vector's support nor
+/// the column count is finite, since the entries would be infinite sums.
+pub fn matvec<T, M, V>(matrix: M, vector: V) -> MatVec<T, M, V>
+where
+    T: Zero + PartialEq,
+    M: InfiniteMatrix<T>,
+    V: InfiniteArray<T>,
+{
+    let bandwidths = matrix.bandwidths();
+    let support = match (vector.support(), matrix.cols()) {
+        (Some(m), Some(n)) => Some(m.min(n)),
+        (m, n) => m.or(n),
+    };
+    assert!(
+        bandwidths.is_some() || support.is_some(),
+        "matvec requires a banded matrix or a vector with finite support"
+    );
+    MatVec {
+        matrix,
+        vector,
+        bandwidths,
+        support,
+        cache: RefCell::new(HashMap::new()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::arrays::Ones;
+    use crate::arrays::{Ones, PaddedArray};
+    use crate::matrix::{BandedMatrix, InfiniteMatrixFromFn};
 
     #[test]
     fn test_cumsum() {
-        let ones = Ones::new();
+        let ones: Ones<f64> = Ones::new();
         let cumsum_result = cumsum(ones);
         
         assert_eq!(cumsum_result.get(0), 1.0);
@@ -130,7 +253,7 @@ mod tests {
 
     #[test]
     fn test_broadcast() {
-        let ones = Ones::new();
+        let ones: Ones<f64> = Ones::new();
         let doubled = broadcast(ones, |x| x * 2.0);
         
         assert_eq!(doubled.get(0), 2.0);
@@ -139,11 +262,47 @@ mod tests {
 
     #[test]
     fn test_add_scalar() {
-        let ones = Ones::new();
+        let ones: Ones<f64> = Ones::new();
         let result = add_scalar(ones, 2.0);
         
         assert_eq!(result.get(0), 3.0);
         assert_eq!(result.get(100), 3.0);
     }
-}
 
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_elements_without_copy() {
+        use crate::arrays::{EventuallyPeriodic, Fill};
+        use crate::fusion::fuse;
+        use num_bigint::BigInt;
+
+        let huge = BigInt::from(u64::MAX) * BigInt::from(u64::MAX);
+        let sums = cumsum(Fill::new(huge.clone()));
+        assert_eq!(sums.get(9), &huge * 10);
+        let signs = EventuallyPeriodic::cyclic(vec![BigInt::from(1), BigInt::from(-1)]);
+        let scaled = mul_scalar(&signs, huge.clone());
+        assert_eq!(add_arrays(&scaled, PaddedArray::zero_padded(vec![huge.clone(), huge.clone()])).get(1), BigInt::from(0));
+        assert_eq!((fuse(&signs) * huge.clone() + BigInt::from(1)).get(3), 1 - huge);
+    }
+
+    #[test]
+    fn test_matvec_banded() {
+        let neighbours = BandedMatrix::new(1, 1, vec![Ones::new(), Ones::new(), Ones::new()]);
+        let v = InfiniteArrayFromFn::new(|i| i as f64);
+        let result = matvec(neighbours, v);
+        assert_eq!(result.get(0), 1.0);
+        assert_eq!(result.get(10), 30.0);
+        assert_eq!(result.get(10), 30.0);
+        assert_eq!(result.cached(), 2);
+        assert_eq!(result.columns(usize::MAX), usize::MAX - 1..usize::MAX);
+    }
+
+    #[test]
+    fn test_matvec_finite_support() {
+        let hilbert = InfiniteMatrixFromFn::new(|i, j| 1.0 / (i + j + 1) as f64);
+        let v = PaddedArray::zero_padded(vec![1.0, 1.0]);
+        let result = matvec(hilbert, v);
+        assert_eq!(result.get(0), 1.5);
+        assert_eq!(result.get(1), 0.5 + 1.0 / 3.0);
+    }
+}
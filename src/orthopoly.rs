@@ -0,0 +1,138 @@
+//! Classical orthogonal polynomial families as infinite operators
+//!
+//! Each family is described by its three-term recurrence. The coefficients
+//! are infinite arrays, and the Jacobi matrix of the orthonormal
+//! polynomials is a symmetric [`Tridiagonal`] operator whose leading
+//! sections have the Gauss quadrature nodes as eigenvalues.
+
+use crate::arrays::{InfiniteArray, InfiniteArrayFromFn};
+use crate::matrix::Tridiagonal;
+use num_traits::Float;
+
+/// The `n`-th coefficient of a recurrence as a function of `n`
+pub type Term<T> = fn(usize) -> T;
+
+/// Recurrence or Jacobi coefficients as an infinite array
+pub type Coefficients<T> = InfiniteArrayFromFn<Term<T>, T>;
+
+/// The Jacobi operator of an orthonormal polynomial family
+pub type JacobiOperator<T> = Tridiagonal<Coefficients<T>, Coefficients<T>, Coefficients<T>>;
+
+/// A classical family of orthogonal polynomials
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PolynomialFamily {
+    /// Chebyshev polynomials of the first kind `Tₙ`, weight `1/√(1 - x²)` on `[-1, 1]`
+    Chebyshev,
+    /// Legendre polynomials `Pₙ`, weight `1` on `[-1, 1]`
+    Legendre,
+    /// Physicists' Hermite polynomials `Hₙ`, weight `e^{-x²}` on the real line
+    Hermite,
+    /// Laguerre polynomials `Lₙ`, weight `e^{-x}` on `[0, ∞)`
+    Laguerre,
+}
+
+/// The three-term recurrence `p_{n+1}(x) = (aₙ x + bₙ) pₙ(x) - cₙ p_{n-1}(x)`
+/// with `p_0 = 1`, in a family's standard normalization
+#[derive(Clone)]
+pub struct Recurrence<T> {
+    pub a: Coefficients<T>,
+    pub b: Coefficients<T>,
+    pub c: Coefficients<T>,
+}
+
+impl<T: Float> Recurrence<T> {
+    /// Evaluate `pₙ(x)` by running the recurrence forward
+    pub fn evaluate(&self, n: usize, x: T) -> T {
+        let (mut previous, mut current) = (T::zero(), T::one());
+        for k in 0..n {
+            let next = (self.a.get(k) * x + self.b.get(k)) * current - self.c.get(k) * previous;
+            previous = current;
+            current = next;
+        }
+        current
+    }
+}
+
+fn ratio<T: Float>(numerator: usize, denominator: usize) -> T {
+    T::from(numerator).unwrap() / T::from(denominator).unwrap()
+}
+
+fn constant<T: Float>(value: f64) -> T {
+    T::from(value).unwrap()
+}
+
+impl PolynomialFamily {
+    /// The three-term recurrence in the family's standard normalization
+    pub fn recurrence<T: Float>(self) -> Recurrence<T> {
+        let (a, b, c): (Term<T>, Term<T>, Term<T>) = match self {
+            PolynomialFamily::Chebyshev => (
+                |n| if n == 0 { T::one() } else { constant(2.0) },
+                |_| T::zero(),
+                |n| if n == 0 { T::zero() } else { T::one() },
+            ),
+            PolynomialFamily::Legendre => (|n| ratio(2 * n + 1, n + 1), |_| T::zero(), |n| ratio(n, n + 1)),
+            PolynomialFamily::Hermite => (|_| constant(2.0), |_| T::zero(), |n| ratio(2 * n, 1)),
+            PolynomialFamily::Laguerre => (|n| -ratio::<T>(1, n + 1), |n| ratio(2 * n + 1, n + 1), |n| ratio(n, n + 1)),
+        };
+        Recurrence {
+            a: InfiniteArrayFromFn::new(a),
+            b: InfiniteArrayFromFn::new(b),
+            c: InfiniteArrayFromFn::new(c),
+        }
+    }
+
+    /// The symmetric tridiagonal Jacobi operator of the orthonormal
+    /// polynomials, with `x qₙ = β_{n-1} q_{n-1} + αₙ qₙ + βₙ q_{n+1}`
+    ///
+    /// The eigenvalues of its `n × n` leading section are the nodes of the
+    /// `n`-point Gauss quadrature rule for the family's weight.
+    pub fn jacobi<T: Float>(self) -> JacobiOperator<T> {
+        let (alpha, beta): (Term<T>, Term<T>) = match self {
+            PolynomialFamily::Chebyshev => (
+                |_| T::zero(),
+                |n| if n == 0 { constant::<T>(0.5).sqrt() } else { constant(0.5) },
+            ),
+            PolynomialFamily::Legendre => (|_| T::zero(), |n| ratio::<T>(n + 1, 1) / ratio::<T>((2 * n + 1) * (2 * n + 3), 1).sqrt()),
+            PolynomialFamily::Hermite => (|_| T::zero(), |n| ratio::<T>(n + 1, 2).sqrt()),
+            PolynomialFamily::Laguerre => (|n| ratio(2 * n + 1, 1), |n| ratio(n + 1, 1)),
+        };
+        Tridiagonal::new(
+            InfiniteArrayFromFn::new(beta),
+            InfiniteArrayFromFn::new(alpha),
+            InfiniteArrayFromFn::new(beta),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::eigenvalues;
+    use crate::matrix::InfiniteMatrix;
+
+    #[test]
+    fn test_recurrences() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+        assert!(close(PolynomialFamily::Chebyshev.recurrence().evaluate(3, 0.5), -1.0));
+        assert!(close(PolynomialFamily::Legendre.recurrence().evaluate(2, 0.5), -0.125));
+        assert!(close(PolynomialFamily::Hermite.recurrence().evaluate(3, 1.0), -4.0));
+        assert!(close(PolynomialFamily::Laguerre.recurrence().evaluate(2, 1.0), -0.5));
+    }
+
+    #[test]
+    fn test_jacobi_operators() {
+        let legendre = PolynomialFamily::Legendre.jacobi::<f64>();
+        assert_eq!(legendre.bandwidths(), Some((1, 1)));
+        assert_eq!(legendre.get(1, 0), legendre.get(0, 1));
+        let nodes = eigenvalues(&legendre, 2);
+        assert!((nodes[1] - 1.0 / 3f64.sqrt()).abs() < 1e-12);
+
+        // Two-point Gauss–Hermite nodes are ±1/√2
+        let hermite = PolynomialFamily::Hermite.jacobi::<f64>();
+        assert!((eigenvalues(&hermite, 2)[1] - 0.5f64.sqrt()).abs() < 1e-12);
+
+        // Two-point Gauss–Laguerre nodes are 2 ± √2
+        let laguerre = PolynomialFamily::Laguerre.jacobi::<f64>();
+        assert!((eigenvalues(&laguerre, 2)[0] - (2.0 - 2f64.sqrt())).abs() < 1e-12);
+    }
+}
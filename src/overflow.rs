@@ -0,0 +1,197 @@
+//! Integer arithmetic that does not overflow silently
+//!
+//! The plain operations overflow like the `+`, `-` and `*` of the element
+//! type: they panic in debug builds and wrap in release builds, deep inside
+//! a lazy closure and only once a large enough index is read. Each
+//! operation here chooses instead: the `checked_*` ones fail with
+//! [`Error::Overflow`] through `try_get` (and panic with it in `get`), the
+//! `saturating_*` ones clamp to the bounds of the type, and the
+//! `wrapping_*` ones wrap around in every build.
+
+use crate::arrays::{Indexed, InfiniteArray, InfiniteVector};
+use crate::describe::Description;
+use crate::error::Error;
+use crate::fallible::{Cumsum, SumMode, ZipWith};
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub, SaturatingAdd, SaturatingMul, SaturatingSub, WrappingAdd, WrappingMul, WrappingSub, Zero};
+
+/// `f(a[i], b[i])` for an `f` that returns `None` on overflow, see
+/// [`checked_add_arrays`] and friends
+#[derive(Debug, Clone)]
+pub struct CheckedZip<T, A, B> {
+    a: A,
+    b: B,
+    f: fn(&T, &T) -> Option<T>,
+    op: &'static str,
+}
+
+impl<T, A, B> InfiniteArray<T> for CheckedZip<T, A, B>
+where
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        self.try_get(index).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        match (self.a.len(), self.b.len()) {
+            (Some(m), Some(n)) => Some(m.min(n)),
+            (m, n) => m.or(n),
+        }
+    }
+
+    fn try_get(&self, index: usize) -> Result<T, Error> {
+        let (x, y) = (self.a.try_get(index)?, self.b.try_get(index)?);
+        (self.f)(&x, &y).ok_or(Error::Overflow { index })
+    }
+
+    fn describe(&self) -> Description {
+        Description::new(self.op, [self.a.describe(), self.b.describe()])
+    }
+}
+
+impl<T, A, B> InfiniteVector<T> for CheckedZip<T, A, B>
+where
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+}
+
+macro_rules! checked_binary {
+    ($($(#[$doc:meta])* $name:ident, $bound:ident, $method:ident, $op:literal;)*) => {$(
+        $(#[$doc])*
+        pub fn $name<T, A, B>(a: A, b: B) -> CheckedZip<T, A, B>
+        where
+            T: $bound,
+            A: InfiniteArray<T>,
+            B: InfiniteArray<T>,
+        {
+            CheckedZip { a, b, f: |x, y| x.$method(y), op: $op }
+        }
+    )*};
+}
+
+checked_binary! {
+    /// Element-wise `a + b`, failing with [`Error::Overflow`] where it overflows
+    checked_add_arrays, CheckedAdd, checked_add, "checked_add";
+    /// Element-wise `a - b`, failing with [`Error::Overflow`] where it overflows
+    checked_sub_arrays, CheckedSub, checked_sub, "checked_sub";
+    /// Element-wise `a * b`, failing with [`Error::Overflow`] where it overflows
+    checked_mul_arrays, CheckedMul, checked_mul, "checked_mul";
+}
+
+macro_rules! total_binary {
+    ($($(#[$doc:meta])* $name:ident, $bound:ident, $method:ident, $op:literal;)*) => {$(
+        $(#[$doc])*
+        pub fn $name<T, A, B>(a: A, b: B) -> ZipWith<A, B, fn(T, T) -> T>
+        where
+            T: $bound,
+            A: InfiniteArray<T>,
+            B: InfiniteArray<T>,
+        {
+            ZipWith::new(a, b, |x, y| x.$method(&y), $op)
+        }
+    )*};
+}
+
+total_binary! {
+    /// Element-wise `a + b`, clamped to the bounds of the type
+    saturating_add_arrays, SaturatingAdd, saturating_add, "saturating_add";
+    /// Element-wise `a - b`, clamped to the bounds of the type
+    saturating_sub_arrays, SaturatingSub, saturating_sub, "saturating_sub";
+    /// Element-wise `a * b`, clamped to the bounds of the type
+    saturating_mul_arrays, SaturatingMul, saturating_mul, "saturating_mul";
+    /// Element-wise `a + b`, wrapping around at the bounds of the type
+    wrapping_add_arrays, WrappingAdd, wrapping_add, "wrapping_add";
+    /// Element-wise `a - b`, wrapping around at the bounds of the type
+    wrapping_sub_arrays, WrappingSub, wrapping_sub, "wrapping_sub";
+    /// Element-wise `a * b`, wrapping around at the bounds of the type
+    wrapping_mul_arrays, WrappingMul, wrapping_mul, "wrapping_mul";
+}
+
+/// Cumulative sums, failing with [`Error::Overflow`] at the first that
+/// overflows; the same as [`try_cumsum`](crate::fallible::try_cumsum)
+pub fn checked_cumsum<T, A>(arr: A) -> Cumsum<A>
+where
+    T: Zero + CheckedAdd,
+    A: InfiniteArray<T>,
+{
+    crate::fallible::try_cumsum(arr)
+}
+
+/// Addition clamped to the bounds of the type, see [`saturating_cumsum`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Saturating;
+
+impl<T: SaturatingAdd> SumMode<T> for Saturating {
+    fn add(&self, sum: &T, x: &T) -> Option<T> {
+        Some(sum.saturating_add(x))
+    }
+}
+
+/// Addition wrapping around at the bounds of the type, see
+/// [`wrapping_cumsum`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Wrapping;
+
+impl<T: WrappingAdd> SumMode<T> for Wrapping {
+    fn add(&self, sum: &T, x: &T) -> Option<T> {
+        Some(sum.wrapping_add(x))
+    }
+}
+
+/// Cumulative sums with each addition clamped to the bounds of the type; a
+/// sum that hit a bound moves away from it again if later terms have the
+/// opposite sign
+pub fn saturating_cumsum<T, A>(arr: A) -> Cumsum<A, Saturating>
+where
+    T: Zero + SaturatingAdd,
+    A: InfiniteArray<T>,
+{
+    Cumsum::with_mode(arr, Saturating)
+}
+
+/// Cumulative sums wrapping around at the bounds of the type
+pub fn wrapping_cumsum<T, A>(arr: A) -> Cumsum<A, Wrapping>
+where
+    T: Zero + WrappingAdd,
+    A: InfiniteArray<T>,
+{
+    Cumsum::with_mode(arr, Wrapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArrayFromFn, PaddedArray};
+
+    #[test]
+    fn test_cumsum_modes_at_large_indices() {
+        let naturals = InfiniteArrayFromFn::new(|i| i as u16 + 1);
+        assert!(matches!(checked_cumsum(&naturals).try_get(400), Err(Error::Overflow { index: 361 })));
+        assert_eq!(checked_cumsum(&naturals).get(360), 65341);
+        assert_eq!(saturating_cumsum(&naturals).get(400), u16::MAX);
+        let swings = PaddedArray::new(vec![100i8, 100, -50], 0);
+        assert_eq!(saturating_cumsum(&swings).iter().take(3).collect::<Vec<_>>(), vec![100, 127, 77]);
+        assert!(format!("{:?}", saturating_cumsum(&swings)).ends_with("mode: Saturating }"));
+        assert_eq!(wrapping_cumsum(&naturals).get(361), (362 * 363 / 2 - 65536) as u16);
+    }
+
+    #[test]
+    fn test_elementwise_modes() {
+        let big = Fill::new(i8::MAX - 1);
+        let steps = InfiniteArrayFromFn::new(|i| i as i8);
+        let sums = checked_add_arrays(&big, &steps);
+        assert_eq!(sums.try_get(1).unwrap(), i8::MAX);
+        assert!(sums.try_get(2).is_err());
+        assert_eq!(saturating_add_arrays(&big, &steps).get(5), i8::MAX);
+        assert_eq!(wrapping_add_arrays(&big, &steps).get(2), i8::MIN);
+        assert_eq!(saturating_sub_arrays(Fill::new(i8::MIN), &steps).get(3), i8::MIN);
+        assert_eq!(wrapping_mul_arrays(Fill::new(16u8), Fill::new(16u8)).get(0), 0);
+        assert_eq!(format!("{:?}", checked_mul_arrays(&big, &steps).describe()), "checked_mul(Fill, from_fn)");
+    }
+}
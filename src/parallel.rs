@@ -0,0 +1,164 @@
+//! Evaluating prefixes across threads with rayon
+//!
+//! Elements of an array are independent, so a prefix can be split between
+//! threads freely. This pays off when each element is expensive to compute.
+
+use crate::arrays::InfiniteArray;
+use crate::instrument::debug_span;
+use num_traits::Zero;
+use rayon::prelude::*;
+use std::ops::Add;
+
+/// The first `n` elements of `arr`, computed in parallel
+pub fn materialize_par<T, A>(arr: &A, n: usize) -> Vec<T>
+where
+    T: Send,
+    A: InfiniteArray<T> + Sync + ?Sized,
+{
+    arr.par_iter_prefix(n).collect()
+}
+
+/// Parallel iteration over a prefix of an array
+pub trait ParallelPrefix<T>: InfiniteArray<T> + Sync {
+    /// A parallel iterator over the first `n` elements, in index order
+    fn par_iter_prefix(&self, n: usize) -> impl IndexedParallelIterator<Item = T> + '_
+    where
+        T: Send,
+    {
+        let n = self.len().map_or(n, |len| len.min(n));
+        (0..n).into_par_iter().map(move |i| self.get(i))
+    }
+}
+
+impl<T, A: InfiniteArray<T> + Sync + ?Sized> ParallelPrefix<T> for A {}
+
+/// The first `n` cumulative sums of `arr`, computed in parallel
+///
+/// The prefix is cut into a few blocks per thread. Each block is scanned on
+/// its own, the block totals are scanned in order, and every block is then
+/// shifted by the total before it, so each element is added about twice.
+pub fn scan_par<T, A>(arr: &A, n: usize) -> Vec<T>
+where
+    T: Zero + Add<Output = T> + Copy + Send + Sync,
+    A: InfiniteArray<T> + Sync + ?Sized,
+{
+    let mut sums = materialize_par(arr, n);
+    let block = sums.len().div_ceil(4 * rayon::current_num_threads()).max(1);
+    sums.par_chunks_mut(block).for_each(|chunk| {
+        for i in 1..chunk.len() {
+            chunk[i] = chunk[i - 1] + chunk[i];
+        }
+    });
+    let offsets: Vec<T> = sums
+        .chunks(block)
+        .scan(T::zero(), |total, chunk| {
+            let offset = *total;
+            *total = offset + chunk[chunk.len() - 1];
+            Some(offset)
+        })
+        .collect();
+    sums.par_chunks_mut(block).zip(offsets).skip(1).for_each(|(chunk, offset)| {
+        for x in chunk {
+            *x = offset + *x;
+        }
+    });
+    sums
+}
+
+/// Cumulative sums of an array with the first sums computed in parallel by
+/// [`scan_par`] and kept as a checkpoint
+///
+/// Sums past the checkpoint continue from its last value, so only the
+/// elements between the checkpoint and the index are added.
+pub struct CumsumCheckpoint<T, A> {
+    base: A,
+    sums: Vec<T>,
+}
+
+/// Cumulative sums of `arr` with the first `n` computed in parallel
+pub fn cumsum_par<T, A>(arr: A, n: usize) -> CumsumCheckpoint<T, A>
+where
+    T: Zero + Add<Output = T> + Copy + Send + Sync,
+    A: InfiniteArray<T> + Sync,
+{
+    debug_span!("cumsum_par", n);
+    let sums = scan_par(&arr, n);
+    CumsumCheckpoint { base: arr, sums }
+}
+
+impl<T, A> CumsumCheckpoint<T, A> {
+    /// The precomputed sums
+    pub fn checkpoint(&self) -> &[T] {
+        &self.sums
+    }
+}
+
+impl<T, A> InfiniteArray<T> for CumsumCheckpoint<T, A>
+where
+    T: Zero + Add<Output = T> + Copy,
+    A: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        match self.sums.get(index) {
+            Some(&sum) => sum,
+            None => {
+                let start = self.sums.last().copied().unwrap_or_else(T::zero);
+                (self.sums.len()..=index).fold(start, |sum, i| sum + self.base.get(i))
+            }
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        let rest = (self.sums.len()..).scan(self.sums.last().copied().unwrap_or_else(T::zero), |sum, i| {
+            *sum = *sum + self.base.get(i);
+            Some(*sum)
+        });
+        Box::new(self.sums.iter().copied().chain(rest))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.base.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+
+    fn is_prime(n: u64) -> bool {
+        n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+    }
+
+    #[test]
+    fn test_materialize_par() {
+        let primality = InfiniteArrayFromFn::new(|i| is_prime(i as u64));
+        let parallel = materialize_par(&primality, 10_000);
+        let sequential: Vec<bool> = primality.iter().take(10_000).collect();
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_par_iter_prefix() {
+        let squares = InfiniteArrayFromFn::new(|i| (i * i) as u64);
+        assert_eq!(squares.par_iter_prefix(1000).sum::<u64>(), 332_833_500);
+        assert_eq!(squares.par_iter_prefix(100).filter(|x| x % 2 == 1).count(), 50);
+    }
+
+    #[test]
+    fn test_scan_par_matches_cumsum() {
+        let terms = InfiniteArrayFromFn::new(|i| (i % 7) as i64 - 3);
+        let sequential: Vec<i64> = crate::operations::cumsum(&terms).iter().take(1001).collect();
+        assert_eq!(scan_par(&terms, 1001), sequential);
+        assert!(scan_par(&terms, 0).is_empty());
+    }
+
+    #[test]
+    fn test_cumsum_checkpoint() {
+        let odds = cumsum_par(InfiniteArrayFromFn::new(|i| 2 * i as u64 + 1), 100_000);
+        assert_eq!(odds.checkpoint().len(), 100_000);
+        assert_eq!(odds.get(99_999), 100_000 * 100_000);
+        assert_eq!(odds.get(100_009), 100_010 * 100_010);
+        assert_eq!(odds.iter().skip(99_998).take(3).collect::<Vec<_>>(), vec![99_999 * 99_999, 100_000 * 100_000, 100_001 * 100_001]);
+    }
+}
@@ -0,0 +1,339 @@
+//! Finite summaries of infinite arrays and their prefixes
+
+use crate::arrays::InfiniteArray;
+use num_traits::Float;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// Number of leading elements that must be inspected to answer a question
+/// about the first `n` elements
+fn inspected<T, A: InfiniteArray<T>>(arr: &A, n: usize) -> usize {
+    arr.exhaustive_prefix().map_or(n, |k| k.min(n))
+}
+
+/// Whether `pred` holds for each of the first `n` elements
+///
+/// Arrays with a known [`exhaustive_prefix`](InfiniteArray::exhaustive_prefix),
+/// such as [`Fill`](crate::arrays::Fill) or
+/// [`EventuallyPeriodic`](crate::arrays::EventuallyPeriodic), only have that
+/// prefix inspected, so the cost is independent of `n`.
+pub fn all_prefix<T, A, P>(arr: &A, pred: P, n: usize) -> bool
+where
+    A: InfiniteArray<T>,
+    P: Fn(&T) -> bool,
+{
+    (0..inspected(arr, n)).all(|i| pred(&arr.get(i)))
+}
+
+/// Whether `pred` holds for any of the first `n` elements
+///
+/// Uses the same structural shortcut as [`all_prefix`].
+pub fn any_prefix<T, A, P>(arr: &A, pred: P, n: usize) -> bool
+where
+    A: InfiniteArray<T>,
+    P: Fn(&T) -> bool,
+{
+    (0..inspected(arr, n)).any(|i| pred(&arr.get(i)))
+}
+
+/// Whether `pred` holds for every element of the array, where decidable
+///
+/// Returns `None` unless the array has a known
+/// [`exhaustive_prefix`](InfiniteArray::exhaustive_prefix).
+pub fn forall<T, A, P>(arr: &A, pred: P) -> Option<bool>
+where
+    A: InfiniteArray<T>,
+    P: Fn(&T) -> bool,
+{
+    arr.exhaustive_prefix()
+        .map(|k| (0..k).all(|i| pred(&arr.get(i))))
+}
+
+/// Whether `pred` holds for some element of the array, where decidable
+///
+/// Like [`forall`], returns `None` unless the array has a known
+/// [`exhaustive_prefix`](InfiniteArray::exhaustive_prefix).
+pub fn exists<T, A, P>(arr: &A, pred: P) -> Option<bool>
+where
+    A: InfiniteArray<T>,
+    P: Fn(&T) -> bool,
+{
+    arr.exhaustive_prefix()
+        .map(|k| (0..k).any(|i| pred(&arr.get(i))))
+}
+
+/// How many of the first `n` elements satisfy `pred`
+///
+/// For arrays with known [`periodicity`](InfiniteArray::periodicity) only the
+/// preperiod and one period are evaluated, so the cost is independent of `n`.
+pub fn count_prefix<T, A, P>(arr: &A, pred: P, n: usize) -> usize
+where
+    A: InfiniteArray<T>,
+    P: Fn(&T) -> bool,
+{
+    match arr.periodicity() {
+        Some((start, period)) if n > start + period => {
+            let head = (0..start).filter(|&i| pred(&arr.get(i))).count();
+            let matches: Vec<bool> = (start..start + period).map(|i| pred(&arr.get(i))).collect();
+            let per_period = matches.iter().filter(|&&m| m).count();
+            let (full, partial) = ((n - start) / period, (n - start) % period);
+            head + full * per_period + matches[..partial].iter().filter(|&&m| m).count()
+        }
+        _ => (0..n).filter(|&i| pred(&arr.get(i))).count(),
+    }
+}
+
+/// How values are assigned to histogram bins
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bins<T> {
+    /// `count` equal-width bins spanning `[lo, hi)`
+    Uniform { lo: T, hi: T, count: usize },
+    /// Bins `[edges[k], edges[k + 1])` between consecutive sorted edges
+    Edges(Vec<T>),
+    /// `count` equal-width bins spanning the observed minimum and maximum
+    Auto(usize),
+}
+
+/// A histogram of finitely many values, see [`histogram`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram<T> {
+    /// Bin edges; bin `k` is `[edges[k], edges[k + 1])`
+    pub edges: Vec<T>,
+    /// Number of values in each bin
+    pub counts: Vec<usize>,
+    /// Values below the first edge
+    pub below: usize,
+    /// Values at or above the last edge
+    pub above: usize,
+    /// NaN values, which belong to no bin
+    pub nan: usize,
+}
+
+impl<T> Histogram<T> {
+    /// Total number of values counted, including those outside the bins
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum::<usize>() + self.below + self.above + self.nan
+    }
+}
+
+fn uniform_edges<T: Float>(lo: T, hi: T, count: usize) -> Vec<T> {
+    let width = (hi - lo) / T::from(count).unwrap();
+    (0..=count)
+        .map(|k| if k == count { hi } else { lo + width * T::from(k).unwrap() })
+        .collect()
+}
+
+/// Histogram of the first `n` elements
+///
+/// With [`Bins::Auto`] the last bin is closed so that the maximum is counted.
+///
+/// # Panics
+///
+/// Panics if the bins are empty or the edges are not sorted.
+pub fn histogram<T, A>(arr: &A, n: usize, bins: Bins<T>) -> Histogram<T>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    let values: Vec<T> = (0..n).map(|i| arr.get(i)).collect();
+    let (edges, close_last) = match bins {
+        Bins::Uniform { lo, hi, count } => (uniform_edges(lo, hi, count), false),
+        Bins::Edges(edges) => (edges, false),
+        Bins::Auto(count) => {
+            let finite = values.iter().filter(|v| !v.is_nan());
+            let lo = finite.clone().fold(T::infinity(), |a, &b| a.min(b));
+            let hi = finite.fold(T::neg_infinity(), |a, &b| a.max(b));
+            if lo > hi {
+                (uniform_edges(T::zero(), T::one(), count), true)
+            } else {
+                (uniform_edges(lo, hi, count), true)
+            }
+        }
+    };
+    assert!(edges.len() >= 2, "histogram requires at least one bin");
+    assert!(edges.windows(2).all(|w| w[0] <= w[1]), "histogram edges must be sorted");
+
+    let mut result = Histogram {
+        counts: vec![0; edges.len() - 1],
+        edges,
+        below: 0,
+        above: 0,
+        nan: 0,
+    };
+    let last = *result.edges.last().unwrap();
+    for value in values {
+        if value.is_nan() {
+            result.nan += 1;
+        } else if value < result.edges[0] {
+            result.below += 1;
+        } else if value >= last {
+            if close_last && value == last {
+                *result.counts.last_mut().unwrap() += 1;
+            } else {
+                result.above += 1;
+            }
+        } else {
+            // First edge strictly greater than value, minus one
+            let bin = result.edges.partition_point(|&e| e <= value) - 1;
+            result.counts[bin] += 1;
+        }
+    }
+    result
+}
+
+/// Order a partially ordered value, treating incomparable values as equal
+fn order<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+/// Quantiles of the first `n` elements
+///
+/// Each `q` in `[0, 1]` is located with quickselect and interpolated
+/// linearly between the neighbouring order statistics, matching the default
+/// definition of NumPy and R.
+///
+/// # Panics
+///
+/// Panics if `n` is zero or any `q` lies outside `[0, 1]`.
+pub fn quantiles<T, A>(arr: &A, n: usize, qs: &[T]) -> Vec<T>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    assert!(n > 0, "quantiles of an empty prefix");
+    let mut values: Vec<T> = (0..n).map(|i| arr.get(i)).collect();
+    qs.iter()
+        .map(|&q| {
+            assert!(q >= T::zero() && q <= T::one(), "quantile must lie in [0, 1]");
+            let h = q * T::from(n - 1).unwrap();
+            let k = h.floor().to_usize().unwrap();
+            let (_, &mut lower, upper) = values.select_nth_unstable_by(k, order);
+            match upper.iter().min_by(order) {
+                Some(&next) => lower + (h - h.floor()) * (next - lower),
+                None => lower,
+            }
+        })
+        .collect()
+}
+
+/// Entry in the `top_k` heap, ordered by value
+struct Ranked<T>(T);
+
+impl<T: PartialOrd> PartialEq for Ranked<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: PartialOrd> Eq for Ranked<T> {}
+
+impl<T: PartialOrd> PartialOrd for Ranked<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PartialOrd> Ord for Ranked<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        order(&self.0, &other.0)
+    }
+}
+
+/// The `k` largest of the first `n` elements, in descending order
+///
+/// Streams the prefix through a min-heap of size `k`, using `O(k)` memory.
+pub fn top_k<T, A>(arr: &A, n: usize, k: usize) -> Vec<T>
+where
+    T: PartialOrd,
+    A: InfiniteArray<T>,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for i in 0..n {
+        heap.push(Reverse(Ranked(arr.get(i))));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+    let mut result: Vec<T> = heap.into_iter().map(|Reverse(Ranked(v))| v).collect();
+    result.sort_by(|a, b| order(b, a));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{EventuallyPeriodic, Fill, InfiniteArrayFromFn, PaddedArray};
+
+    #[test]
+    fn test_all_any_prefix() {
+        let squares = InfiniteArrayFromFn::new(|i| i * i);
+        assert!(all_prefix(&squares, |&x| x < 100, 10));
+        assert!(!all_prefix(&squares, |&x| x < 100, 11));
+        assert!(any_prefix(&squares, |&x| x == 81, 10));
+        assert!(!any_prefix(&squares, |&x| x == 81, 9));
+    }
+
+    #[test]
+    fn test_structural_fast_paths() {
+        let fill = Fill::new(3);
+        assert!(all_prefix(&fill, |&x| x == 3, usize::MAX));
+        assert_eq!(forall(&fill, |&x| x == 3), Some(true));
+
+        let periodic = EventuallyPeriodic::new(vec![0], vec![1, 2, 3]);
+        assert_eq!(forall(&periodic, |&x| x < 4), Some(true));
+        assert_eq!(exists(&periodic, |&x| x == 2), Some(true));
+        assert_eq!(exists(&periodic, |&x| x == 5), Some(false));
+
+        let squares = InfiniteArrayFromFn::new(|i| i * i);
+        assert_eq!(forall(&squares, |&x| x < 100), None);
+    }
+
+    #[test]
+    fn test_count_prefix() {
+        let squares = InfiniteArrayFromFn::new(|i| i * i);
+        assert_eq!(count_prefix(&squares, |&x| x % 2 == 0, 10), 5);
+
+        let periodic = EventuallyPeriodic::new(vec![7, 7], vec![1, 2, 3]);
+        assert_eq!(count_prefix(&periodic, |&x| x == 2, 3), 0);
+        assert_eq!(count_prefix(&periodic, |&x| x == 2, 4), 1);
+        assert_eq!(count_prefix(&periodic, |&x| x > 1, 1_000_000_000_002), 666_666_666_668);
+
+        let padded = PaddedArray::new(vec![1, 0, 1], 1);
+        assert_eq!(count_prefix(&padded, |&x| x == 1, 10), 9);
+        assert_eq!(count_prefix(&Fill::new(true), |&b| b, usize::MAX), usize::MAX);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let arr = InfiniteArrayFromFn::new(|i| i as f64);
+        let hist = histogram(&arr, 10, Bins::Uniform { lo: 0.0, hi: 8.0, count: 4 });
+        assert_eq!(hist.counts, vec![2, 2, 2, 2]);
+        assert_eq!(hist.above, 2);
+        assert_eq!(hist.total(), 10);
+
+        let hist = histogram(&arr, 10, Bins::Edges(vec![1.0, 2.0, 5.0]));
+        assert_eq!((hist.below, hist.counts.clone(), hist.above), (1, vec![1, 3], 5));
+
+        let hist = histogram(&arr, 10, Bins::Auto(3));
+        assert_eq!(hist.counts, vec![3, 3, 4]);
+        assert_eq!(hist.above, 0);
+    }
+
+    #[test]
+    fn test_quantiles() {
+        // 0, 7, 4, 1, 8, 5, 2, 9, 6, 3
+        let arr = InfiniteArrayFromFn::new(|i| ((i * 7) % 10) as f64);
+        assert_eq!(quantiles(&arr, 10, &[0.0, 0.5, 1.0]), vec![0.0, 4.5, 9.0]);
+        assert_eq!(quantiles(&arr, 10, &[0.25]), vec![2.25]);
+    }
+
+    #[test]
+    fn test_top_k() {
+        let arr = InfiniteArrayFromFn::new(|i| (i * 7) % 10);
+        assert_eq!(top_k(&arr, 10, 3), vec![9, 8, 7]);
+        assert_eq!(top_k(&arr, 3, 5), vec![7, 4, 0]);
+    }
+}
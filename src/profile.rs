@@ -0,0 +1,159 @@
+//! Counting evaluations of an array
+//!
+//! Composed arrays recompute their operands on every access, so a pipeline
+//! can end up evaluating the same element many times, e.g. the naive
+//! [`cumsum`](crate::cumsum) reads `n(n + 1)/2` elements for the first `n`
+//! sums. Wrapping an operand in [`Profiled`] records each read.
+
+use crate::arrays::{Indexed, InfiniteArray};
+use crate::describe::Description;
+use crate::error::Error;
+use num_traits::Zero;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+
+/// An array that counts how often each of its elements is read
+#[derive(Debug)]
+pub struct Profiled<A> {
+    inner: A,
+    total: Cell<usize>,
+    counts: RefCell<BTreeMap<usize, usize>>,
+}
+
+impl<A> Profiled<A> {
+    pub fn new(inner: A) -> Self {
+        Self {
+            inner,
+            total: Cell::new(0),
+            counts: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+
+    /// Number of elements read so far
+    pub fn total(&self) -> usize {
+        self.total.get()
+    }
+
+    /// Number of times element `index` was read
+    pub fn count(&self, index: usize) -> usize {
+        self.counts.borrow().get(&index).copied().unwrap_or(0)
+    }
+
+    /// Read counts of every element read at least once, by index
+    pub fn histogram(&self) -> BTreeMap<usize, usize> {
+        self.counts.borrow().clone()
+    }
+
+    /// The most often read element and its count, preferring the lowest index
+    pub fn hottest(&self) -> Option<(usize, usize)> {
+        self.counts
+            .borrow()
+            .iter()
+            .fold(None, |best, (&index, &count)| match best {
+                Some((_, most)) if most >= count => best,
+                _ => Some((index, count)),
+            })
+    }
+
+    /// Elements read more than once
+    pub fn recomputed(&self) -> usize {
+        self.counts.borrow().values().filter(|&&count| count > 1).count()
+    }
+
+    /// Forget all counts
+    pub fn reset(&self) {
+        self.total.set(0);
+        self.counts.borrow_mut().clear();
+    }
+
+    fn record(&self, start: usize, n: usize) {
+        self.total.set(self.total.get() + n);
+        let mut counts = self.counts.borrow_mut();
+        for index in start..start + n {
+            *counts.entry(index).or_insert(0) += 1;
+        }
+    }
+}
+
+impl<T, A> InfiniteArray<T> for Profiled<A>
+where
+    A: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        self.record(index, 1);
+        self.inner.get(index)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.inner.len()
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        self.inner.support()
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        self.inner.periodicity()
+    }
+
+    fn get_range(&self, start: usize, out: &mut [T]) {
+        self.record(start, out.len());
+        self.inner.get_range(start, out);
+    }
+
+    fn try_get(&self, index: usize) -> Result<T, Error> {
+        self.record(index, 1);
+        self.inner.try_get(index)
+    }
+
+    fn describe(&self) -> Description {
+        Description::new("profiled", [self.inner.describe()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArrayFromFn};
+    use crate::operations::cumsum;
+
+    #[test]
+    fn test_profiled_detects_quadratic_cumsum() {
+        let x = Profiled::new(InfiniteArrayFromFn::new(|i| i as u64));
+        let sums = cumsum(&x);
+        let prefix: Vec<u64> = sums.iter().take(10).collect();
+        assert_eq!(prefix[9], 45);
+        assert_eq!(x.total(), 55);
+        assert_eq!(x.count(0), 10);
+        assert_eq!(x.count(9), 1);
+        assert_eq!(x.hottest(), Some((0, 10)));
+        assert_eq!(x.recomputed(), 9);
+        assert_eq!(format!("{:?}", sums.describe()), "cumsum(profiled(from_fn))");
+    }
+
+    #[test]
+    fn test_profiled_iteration_and_ranges() {
+        let x = Profiled::new(Fill::new(1));
+        assert_eq!(x.iter().take(3).sum::<i32>(), 3);
+        let mut out = [0; 4];
+        x.get_range(2, &mut out);
+        assert_eq!(x.histogram().into_iter().collect::<Vec<_>>(), vec![(0, 1), (1, 1), (2, 2), (3, 1), (4, 1), (5, 1)]);
+        x.reset();
+        assert_eq!((x.total(), x.hottest()), (0, None));
+    }
+}
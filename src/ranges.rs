@@ -1,13 +1,66 @@
 //! Infinite range types for indexing infinite arrays
 
-use num_traits::One;
+use crate::arrays::marker_eq_hash;
+use num_traits::{Float, One};
+use std::iter::FusedIterator;
+
+/// Element types that an index converts to, for the ranges
+///
+/// Integers convert exactly and fail when the index does not fit, as
+/// `u8` does above 255; floats convert to the nearest representable value.
+pub trait FromIndex: Sized {
+    /// The index as `Self`, or `None` if it does not fit
+    fn try_from_index(index: usize) -> Option<Self>;
+
+    /// The index as `Self`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index does not fit.
+    fn from_index(index: usize) -> Self {
+        Self::try_from_index(index)
+            .unwrap_or_else(|| panic!("index {} does not fit in {}", index, std::any::type_name::<Self>()))
+    }
+}
+
+macro_rules! from_index_exact {
+    ($($t:ty),*) => {$(
+        impl FromIndex for $t {
+            fn try_from_index(index: usize) -> Option<Self> {
+                <$t>::try_from(index).ok()
+            }
+        }
+    )*};
+}
+
+from_index_exact!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+#[cfg(feature = "bigint")]
+from_index_exact!(num_bigint::BigInt, num_bigint::BigUint);
+#[cfg(feature = "decimal")]
+from_index_exact!(rust_decimal::Decimal);
+
+macro_rules! from_index_lossy {
+    ($($t:ty),*) => {$(
+        impl FromIndex for $t {
+            fn try_from_index(index: usize) -> Option<Self> {
+                Some(index as $t)
+            }
+        }
+    )*};
+}
+
+from_index_lossy!(f32, f64);
 
 /// An infinite range starting from 1: 1, 2, 3, ...
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OneToInf<T> {
+    #[cfg_attr(feature = "serde", serde(skip))]
     _phantom: std::marker::PhantomData<T>,
 }
 
+marker_eq_hash!(OneToInf);
+
 impl<T> OneToInf<T> {
     pub fn new() -> Self {
         Self {
@@ -25,10 +78,10 @@ impl<T> Default for OneToInf<T> {
 
 impl<T> OneToInf<T>
 where
-    T: From<usize> + One + Copy,
+    T: FromIndex + One + Clone,
 {
     pub fn get(&self, index: usize) -> T {
-        T::from(index + 1)
+        T::from_index(index + 1)
     }
 
     pub fn iter(&self) -> OneToInfIter<T> {
@@ -47,19 +100,33 @@ pub struct OneToInfIter<T> {
 
 impl<T> Iterator for OneToInfIter<T>
 where
-    T: std::ops::Add<Output = T> + One + Copy,
+    T: FromIndex + std::ops::Add<Output = T> + One + Clone,
 {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let result = self.current;
-        self.current = self.current + T::one();
-        Some(result)
+        let next = self.current.clone() + T::one();
+        Some(std::mem::replace(&mut self.current, next))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.current = self.current.clone() + T::from_index(n);
+        self.next()
     }
 }
 
+impl<T> FusedIterator for OneToInfIter<T> where T: FromIndex + std::ops::Add<Output = T> + One + Clone {}
+
 /// An infinite unit range starting from a given value
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Element `i` is computed as `start + i`, so the index `i` itself has to
+/// fit in `T` (see [`FromIndex`]), not just the element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InfUnitRange<T> {
     start: T,
 }
@@ -68,23 +135,33 @@ impl<T> InfUnitRange<T> {
     pub fn new(start: T) -> Self {
         Self { start }
     }
+
+    /// The first element
+    pub fn start(&self) -> &T {
+        &self.start
+    }
+
+    /// The first element, taken out of the range
+    pub fn into_start(self) -> T {
+        self.start
+    }
 }
 
 impl<T> InfUnitRange<T>
 where
-    T: From<usize> + std::ops::Add<Output = T> + Copy,
+    T: FromIndex + std::ops::Add<Output = T> + Clone,
 {
     pub fn get(&self, index: usize) -> T {
         if index == 0 {
-            self.start
+            self.start.clone()
         } else {
-            self.start + T::from(index)
+            self.start.clone() + T::from_index(index)
         }
     }
 
     pub fn iter(&self) -> InfUnitRangeIter<T> {
         InfUnitRangeIter {
-            current: self.start,
+            current: self.start.clone(),
         }
     }
 }
@@ -96,19 +173,36 @@ pub struct InfUnitRangeIter<T> {
 
 impl<T> Iterator for InfUnitRangeIter<T>
 where
-    T: std::ops::Add<Output = T> + One + Copy,
+    T: FromIndex + std::ops::Add<Output = T> + One + Clone,
 {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let result = self.current;
-        self.current = self.current + T::one();
-        Some(result)
+        let next = self.current.clone() + T::one();
+        Some(std::mem::replace(&mut self.current, next))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.current = self.current.clone() + T::from_index(n);
+        self.next()
     }
 }
 
+impl<T> FusedIterator for InfUnitRangeIter<T> where T: FromIndex + std::ops::Add<Output = T> + One + Clone {}
+
 /// An infinite step range: start, start+step, start+2*step, ...
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// A negative step of a signed or float type gives a decreasing range such
+/// as `100, 98, 96, ...`. Element `i` is computed as `start + step * i`, so
+/// both the index `i` (see [`FromIndex`]) and `step * i` have to fit in `T`,
+/// not just the element: `InfStepRange::new(100i8, -1).get(150)` would be
+/// `-50` but panics because 150 does not fit in `i8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InfStepRange<T> {
     start: T,
     step: T,
@@ -118,20 +212,35 @@ impl<T> InfStepRange<T> {
     pub fn new(start: T, step: T) -> Self {
         Self { start, step }
     }
+
+    /// The first element
+    pub fn start(&self) -> &T {
+        &self.start
+    }
+
+    /// The difference between consecutive elements
+    pub fn step(&self) -> &T {
+        &self.step
+    }
+
+    /// `(start, step)`, taken out of the range
+    pub fn into_parts(self) -> (T, T) {
+        (self.start, self.step)
+    }
 }
 
 impl<T> InfStepRange<T>
 where
-    T: From<usize> + std::ops::Add<Output = T> + std::ops::Mul<Output = T> + Copy,
+    T: FromIndex + std::ops::Add<Output = T> + std::ops::Mul<Output = T> + Clone,
 {
     pub fn get(&self, index: usize) -> T {
-        self.start + self.step * T::from(index)
+        self.start.clone() + self.step.clone() * T::from_index(index)
     }
 
     pub fn iter(&self) -> InfStepRangeIter<T> {
         InfStepRangeIter {
-            current: self.start,
-            step: self.step,
+            current: self.start.clone(),
+            step: self.step.clone(),
         }
     }
 }
@@ -144,14 +253,178 @@ pub struct InfStepRangeIter<T> {
 
 impl<T> Iterator for InfStepRangeIter<T>
 where
-    T: std::ops::Add<Output = T> + Copy,
+    T: FromIndex + std::ops::Add<Output = T> + std::ops::Mul<Output = T> + Clone,
 {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let result = self.current;
-        self.current = self.current + self.step;
-        Some(result)
+        let next = self.current.clone() + self.step.clone();
+        Some(std::mem::replace(&mut self.current, next))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.current = self.current.clone() + self.step.clone() * T::from_index(n);
+        self.next()
+    }
+}
+
+impl<T> FusedIterator for InfStepRangeIter<T> where
+    T: FromIndex + std::ops::Add<Output = T> + std::ops::Mul<Output = T> + Clone
+{
+}
+
+/// An infinite geometric range: start, start*ratio, start*ratio^2, ...
+///
+/// Element `i` is computed directly as `start * ratio^i`, so it does not
+/// accumulate the rounding error of repeated multiplication.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InfGeometricRange<T> {
+    start: T,
+    ratio: T,
+}
+
+impl<T> InfGeometricRange<T> {
+    pub fn new(start: T, ratio: T) -> Self {
+        Self { start, ratio }
+    }
+
+    /// The first element
+    pub fn start(&self) -> &T {
+        &self.start
+    }
+
+    /// The quotient of consecutive elements
+    pub fn ratio(&self) -> &T {
+        &self.ratio
+    }
+
+    /// `(start, ratio)`, taken out of the range
+    pub fn into_parts(self) -> (T, T) {
+        (self.start, self.ratio)
+    }
+}
+
+impl<T> InfGeometricRange<T>
+where
+    T: Float + FromIndex,
+{
+    pub fn get(&self, index: usize) -> T {
+        let power = match i32::try_from(index) {
+            Ok(n) => self.ratio.powi(n),
+            Err(_) => self.ratio.powf(T::from_index(index)),
+        };
+        self.start * power
+    }
+
+    pub fn iter(&self) -> InfGeometricRangeIter<T> {
+        InfGeometricRangeIter {
+            range: *self,
+            index: 0,
+        }
+    }
+
+    /// Sum of the first `n` elements, `start * (1 - ratio^n) / (1 - ratio)`
+    pub fn partial_sum(&self, n: usize) -> T {
+        if self.ratio == T::one() {
+            self.start * T::from_index(n)
+        } else {
+            (self.start - self.get(n)) / (T::one() - self.ratio)
+        }
+    }
+
+    /// Sum of all elements, `start / (1 - ratio)`; `None` unless
+    /// `|ratio| < 1` or `start` is zero
+    pub fn sum(&self) -> Option<T> {
+        if self.start.is_zero() {
+            Some(self.start)
+        } else if self.ratio.abs() < T::one() {
+            Some(self.start / (T::one() - self.ratio))
+        } else {
+            None
+        }
+    }
+}
+
+/// Iterator over InfGeometricRange
+pub struct InfGeometricRangeIter<T> {
+    range: InfGeometricRange<T>,
+    index: usize,
+}
+
+impl<T> Iterator for InfGeometricRangeIter<T>
+where
+    T: Float + FromIndex,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.range.get(self.index);
+        self.index = self.index.saturating_add(1);
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.index = self.index.saturating_add(n);
+        self.next()
+    }
+}
+
+impl<T> FusedIterator for InfGeometricRangeIter<T> where T: Float + FromIndex {}
+
+impl<T> IntoIterator for &OneToInf<T>
+where
+    T: FromIndex + std::ops::Add<Output = T> + One + Clone,
+{
+    type Item = T;
+    type IntoIter = OneToInfIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for &InfUnitRange<T>
+where
+    T: FromIndex + std::ops::Add<Output = T> + One + Clone,
+{
+    type Item = T;
+    type IntoIter = InfUnitRangeIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for &InfStepRange<T>
+where
+    T: FromIndex + std::ops::Add<Output = T> + std::ops::Mul<Output = T> + Clone,
+{
+    type Item = T;
+    type IntoIter = InfStepRangeIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for &InfGeometricRange<T>
+where
+    T: Float + FromIndex,
+{
+    type Item = T;
+    type IntoIter = InfGeometricRangeIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
@@ -161,7 +434,7 @@ mod tests {
 
     #[test]
     fn test_one_to_inf() {
-        let range = OneToInf::new();
+        let range: OneToInf<usize> = OneToInf::new();
         assert_eq!(range.get(0), 1);
         assert_eq!(range.get(1), 2);
         assert_eq!(range.get(99), 100);
@@ -169,13 +442,104 @@ mod tests {
 
     #[test]
     fn test_one_to_inf_iter() {
-        let range = OneToInf::new();
+        let range: OneToInf<usize> = OneToInf::new();
         let mut iter = range.iter();
         assert_eq!(iter.next(), Some(1));
         assert_eq!(iter.next(), Some(2));
         assert_eq!(iter.next(), Some(3));
     }
 
+    #[test]
+    fn test_ranges_into_iterator() {
+        let odds = InfStepRange::new(1usize, 2);
+        let sum: usize = (&odds).into_iter().take(10).sum();
+        assert_eq!(sum, 100);
+        let pairs: Vec<_> = (&OneToInf::<usize>::new()).into_iter().zip(&InfUnitRange::new(10usize)).take(2).collect();
+        assert_eq!(pairs, vec![(1, 10), (2, 11)]);
+    }
+
+    #[test]
+    fn test_ranges_of_other_element_types() {
+        assert_eq!(OneToInf::<i32>::new().get(9), 10);
+        assert_eq!(InfStepRange::new(1.0f32, 0.5).iter().nth(4), Some(3.0));
+        assert_eq!(InfUnitRange::new(-3i64).iter().take(4).collect::<Vec<_>>(), vec![-3, -2, -1, 0]);
+        assert_eq!(u8::try_from_index(256), None);
+        assert_eq!(f64::from_index(1 << 60), 2f64.powi(60));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_factorials_do_not_overflow() {
+        use num_bigint::BigUint;
+
+        let naturals = OneToInf::<BigUint>::new();
+        let factorials: Vec<BigUint> = naturals
+            .iter()
+            .scan(BigUint::one(), |product, n| {
+                *product *= n;
+                Some(product.clone())
+            })
+            .take(30)
+            .collect();
+        assert_eq!(factorials[29].to_string(), "265252859812191058636308480000000");
+        assert_eq!(InfStepRange::new(BigUint::from(7u8), BigUint::from(3u8)).get(1 << 40), BigUint::from(3u64 << 40) + 7u8);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 300 does not fit in u8")]
+    fn test_from_index_panics_when_too_large() {
+        InfStepRange::new(0u8, 0).get(300);
+    }
+
+    #[test]
+    fn test_decreasing_ranges() {
+        let down = InfStepRange::new(100i32, -2);
+        assert_eq!(down.iter().take(4).collect::<Vec<_>>(), vec![100, 98, 96, 94]);
+        assert_eq!(down.get(75), -50);
+        assert_eq!(down.iter().nth(50), Some(0));
+        assert_eq!(down.iter().skip(10).step_by(10).take(2).collect::<Vec<_>>(), vec![80, 60]);
+        let cooling = InfStepRange::new(1.0f64, -0.25);
+        assert_eq!((&cooling).into_iter().nth(6), Some(-0.5));
+        assert_eq!(InfStepRange::new(0i8, -2).get(64), i8::MIN);
+        assert_eq!(InfStepRange::new(100i8, -1).get(127), -27);
+        assert_eq!(InfUnitRange::new(-100i8).get(127), 27);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 150 does not fit in i8")]
+    fn test_decreasing_range_index_must_fit() {
+        InfStepRange::new(100i8, -1).get(150);
+    }
+
+    #[test]
+    fn test_geometric_range() {
+        let halves = InfGeometricRange::new(1.0f64, 0.5);
+        assert_eq!(halves.iter().take(4).collect::<Vec<_>>(), vec![1.0, 0.5, 0.25, 0.125]);
+        assert_eq!(halves.get(10), 1.0 / 1024.0);
+        assert_eq!((&halves).into_iter().nth(3), Some(0.125));
+        assert_eq!(halves.partial_sum(4), 1.875);
+        assert_eq!(halves.sum(), Some(2.0));
+        let alternating = InfGeometricRange::new(3.0f64, -1.0);
+        assert_eq!(alternating.get(5), -3.0);
+        assert_eq!(alternating.partial_sum(5), 3.0);
+        assert_eq!(alternating.sum(), None);
+        assert_eq!(InfGeometricRange::new(2.0f32, 1.0).partial_sum(7), 14.0);
+        assert_eq!(InfGeometricRange::new(1.0f64, 1.0 - 1e-12).get(1 << 40), (1.0f64 - 1e-12).powf(2f64.powi(40)));
+        let mut far = halves.iter();
+        far.next();
+        assert_eq!((far.nth(usize::MAX), far.next()), (Some(0.0), Some(0.0)));
+    }
+
+    #[test]
+    fn test_range_iterators_jump() {
+        let mut evens = InfStepRange::new(0usize, 2).iter();
+        assert_eq!(evens.nth(1_000_000), Some(2_000_000));
+        assert_eq!(evens.next(), Some(2_000_002));
+        assert_eq!(InfUnitRange::new(5usize).iter().step_by(10).take(3).collect::<Vec<_>>(), vec![5, 15, 25]);
+        assert_eq!(OneToInf::<usize>::new().iter().skip(4).take(2).collect::<Vec<_>>(), vec![5, 6]);
+        assert_eq!(OneToInf::<usize>::new().iter().size_hint(), (usize::MAX, None));
+    }
+
     #[test]
     fn test_inf_unit_range() {
         let range = InfUnitRange::new(5);
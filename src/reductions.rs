@@ -0,0 +1,228 @@
+//! Reductions of infinite arrays to scalars
+
+use crate::arrays::InfiniteArray;
+use crate::series::{estimate_tail, Convergence, ConvergenceReport, SeriesError, DEFAULT_TERM_BUDGET};
+use num_traits::Float;
+
+/// Sum the first `n` terms exactly
+pub(crate) fn exact_sum<T, F>(term: F, n: usize) -> ConvergenceReport<T>
+where
+    T: Float,
+    F: Fn(usize) -> T,
+{
+    let mut sum = T::zero();
+    let mut last = T::zero();
+    for i in 0..n {
+        last = term(i);
+        sum = sum + last;
+    }
+    ConvergenceReport {
+        value: sum,
+        terms: n,
+        last_increment: last,
+        remainder: T::zero(),
+        convergence: Convergence::Exact,
+    }
+}
+
+/// Sum `term(0) + term(1) + ...` until the error `error(sum, tail)` caused
+/// by dropping the estimated tail is at most `tol`
+///
+/// The tail estimate from [`tail_estimate`](crate::series::tail_estimate) is
+/// added to the returned value.
+pub(crate) fn adaptive_sum<T, F, E>(term: F, tol: T, error: E) -> Result<ConvergenceReport<T>, SeriesError>
+where
+    T: Float,
+    F: Fn(usize) -> T,
+    E: Fn(T, T) -> T,
+{
+    let mut sum = T::zero();
+    let mut window = [T::zero(); 3];
+    for index in 0..DEFAULT_TERM_BUDGET {
+        let t = term(index);
+        sum = sum + t;
+        window = [window[1], window[2], t];
+        if index >= 2 {
+            if let Some(tail) = estimate_tail(window, index + 1) {
+                let remainder = error(sum, tail).abs();
+                if remainder <= tol {
+                    return Ok(ConvergenceReport {
+                        value: sum + tail,
+                        terms: index + 1,
+                        last_increment: t,
+                        remainder,
+                        convergence: Convergence::Estimated,
+                    });
+                }
+            }
+        }
+    }
+
+    Err(SeriesError::BudgetExhausted {
+        terms: DEFAULT_TERM_BUDGET,
+    })
+}
+
+/// The smallest finite support among the given supports, if any is finite
+fn min_support(supports: &[Option<usize>]) -> Option<usize> {
+    supports.iter().flatten().copied().min()
+}
+
+/// The ℓp norm `(Σ |a[i]|^p)^(1/p)` of a summable infinite array
+///
+/// Terms are accumulated until the estimated contribution of the remaining
+/// tail changes the norm by at most `tol`; that contribution is included in
+/// the result. `p` must be finite and at least 1.
+/// The report's `terms` and `last_increment` refer to the sum of `|a[i]|^p`.
+pub fn norm<T, A>(arr: &A, p: T, tol: T) -> Result<ConvergenceReport<T>, SeriesError>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    assert!(p >= T::one() && p.is_finite(), "norm requires 1 <= p < ∞");
+
+    let term = |i| arr.get(i).abs().powf(p);
+    let mut report = match arr.support() {
+        Some(n) => exact_sum(term, n),
+        None => adaptive_sum(term, tol, |sum, tail| {
+            (sum + tail).powf(p.recip()) - sum.powf(p.recip())
+        })?,
+    };
+    report.value = report.value.powf(p.recip());
+    Ok(report)
+}
+
+/// The dot product `Σ a[i] b[i]` of two infinite arrays
+///
+/// When either operand has finite [`support`](InfiniteArray::support), e.g.
+/// a zero-padded [`PaddedArray`](crate::arrays::PaddedArray), the sum is
+/// computed exactly over that support. Otherwise terms are accumulated until
+/// the estimated tail falls below `tol`.
+pub fn dot<T, A, B>(a: &A, b: &B, tol: T) -> Result<ConvergenceReport<T>, SeriesError>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    let term = |i| a.get(i) * b.get(i);
+    match min_support(&[a.support(), b.support()]) {
+        Some(n) => Ok(exact_sum(term, n)),
+        None => adaptive_sum(term, tol, |_, tail| tail),
+    }
+}
+
+/// The weighted inner product `Σ w[i] a[i] b[i]`
+///
+/// Uses the same finite-support fast path and adaptive truncation as [`dot`].
+pub fn inner_product<T, A, B, W>(
+    a: &A,
+    b: &B,
+    weights: &W,
+    tol: T,
+) -> Result<ConvergenceReport<T>, SeriesError>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+    W: InfiniteArray<T>,
+{
+    let term = |i| weights.get(i) * a.get(i) * b.get(i);
+    match min_support(&[a.support(), b.support(), weights.support()]) {
+        Some(n) => Ok(exact_sum(term, n)),
+        None => adaptive_sum(term, tol, |_, tail| tail),
+    }
+}
+
+/// Whether `a` and `b` are orthogonal under `weights` up to a relative `tol`
+///
+/// Checks `|⟨a, b⟩_w| <= tol · ‖a‖_w ‖b‖_w`, with every inner product
+/// computed to absolute tolerance `tol`.
+pub fn is_orthogonal<T, A, B, W>(a: &A, b: &B, weights: &W, tol: T) -> Result<bool, SeriesError>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+    W: InfiniteArray<T>,
+{
+    let ab = inner_product(a, b, weights, tol)?.value;
+    let aa = inner_product(a, a, weights, tol)?.value;
+    let bb = inner_product(b, b, weights, tol)?.value;
+    Ok(ab.abs() <= tol * (aa * bb).abs().sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{InfiniteArrayFromFn, Ones, PaddedArray, Zeros};
+
+    #[test]
+    fn test_norm_geometric() {
+        // Σ (2^-i)^2 = 4/3
+        let arr = InfiniteArrayFromFn::new(|i| 0.5f64.powi(i as i32));
+        let result = norm(&arr, 2.0, 1e-10).unwrap().value;
+        assert!((result - (4.0f64 / 3.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_norm_power_law() {
+        // Σ 1/k² = π²/6
+        let arr = InfiniteArrayFromFn::new(|i| 1.0 / (i + 1) as f64);
+        let report = norm(&arr, 2.0, 1e-6).unwrap();
+        let exact = std::f64::consts::PI / 6f64.sqrt();
+        assert!((report.value - exact).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_norm_zeros() {
+        let zeros: Zeros<f64> = Zeros::new();
+        let report = norm(&zeros, 1.0, 1e-10).unwrap();
+        assert_eq!(report.value, 0.0);
+        assert_eq!(report.convergence, Convergence::Exact);
+    }
+
+    #[test]
+    fn test_norm_divergent() {
+        let ones: Ones<f64> = Ones::new();
+        assert!(matches!(
+            norm(&ones, 1.0, 1e-10),
+            Err(SeriesError::BudgetExhausted { .. })
+        ));
+    }
+
+    #[test]
+    fn test_dot_finite_support() {
+        let padded = PaddedArray::zero_padded(vec![1.0, 2.0, 3.0]);
+        let ones: Ones<f64> = Ones::new();
+        assert_eq!(dot(&padded, &ones, 0.0).unwrap().value, 6.0);
+        assert_eq!(dot(&ones, &padded, 0.0).unwrap().value, 6.0);
+    }
+
+    #[test]
+    fn test_dot_adaptive() {
+        // Σ 2^-i · 3^-i = 1 / (1 - 1/6)
+        let a = InfiniteArrayFromFn::new(|i| 0.5f64.powi(i as i32));
+        let b = InfiniteArrayFromFn::new(|i| (1.0f64 / 3.0).powi(i as i32));
+        let report = dot(&a, &b, 1e-12).unwrap();
+        assert!((report.value - 1.2).abs() < 1e-10);
+        assert_eq!(report.convergence, Convergence::Estimated);
+        assert!(report.remainder <= 1e-12);
+    }
+
+    #[test]
+    fn test_inner_product_weighted() {
+        let a = PaddedArray::zero_padded(vec![1.0, 2.0]);
+        let b: Ones<f64> = Ones::new();
+        let w = InfiniteArrayFromFn::new(|i| (i + 1) as f64);
+        assert_eq!(inner_product(&a, &b, &w, 0.0).unwrap().value, 5.0);
+    }
+
+    #[test]
+    fn test_is_orthogonal() {
+        let a = PaddedArray::zero_padded(vec![1.0, 1.0]);
+        let b = PaddedArray::zero_padded(vec![2.0, -1.0]);
+        let w = PaddedArray::zero_padded(vec![1.0, 2.0]);
+        let ones: Ones<f64> = Ones::new();
+        assert_eq!(is_orthogonal(&a, &b, &w, 1e-12), Ok(true));
+        assert_eq!(is_orthogonal(&a, &b, &ones, 1e-12), Ok(false));
+    }
+}
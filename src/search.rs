@@ -0,0 +1,199 @@
+//! Bounded searches over infinite arrays
+
+use crate::arrays::{InfiniteArray, InfiniteVector};
+use crate::describe::Description;
+use num_traits::Zero;
+
+/// An infinite array declared to be nondecreasing
+///
+/// Wrapping an array in `Monotone` is a promise by the caller that
+/// `arr[i] <= arr[i + 1]` for every `i`; it enables logarithmic searches.
+/// The promise is not checked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Monotone<A> {
+    inner: A,
+}
+
+impl<A> Monotone<A> {
+    pub fn new(inner: A) -> Self {
+        Self { inner }
+    }
+
+    /// The wrapped array
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    /// Unwrap the array, dropping the monotonicity declaration
+    pub fn into_inner(self) -> A {
+        self.inner
+    }
+}
+
+impl<T, A> InfiniteArray<T> for Monotone<A>
+where
+    A: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        self.inner.get(index)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        self.inner.iter()
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.inner.len()
+    }
+
+    fn support(&self) -> Option<usize>
+    where
+        T: Zero + PartialEq,
+    {
+        self.inner.support()
+    }
+
+    fn periodicity(&self) -> Option<(usize, usize)> {
+        self.inner.periodicity()
+    }
+
+    fn describe(&self) -> Description {
+        self.inner.describe()
+    }
+}
+
+impl<T, A> InfiniteVector<T> for Monotone<A> where A: InfiniteArray<T> {}
+
+/// Index of the first of the first `limit` elements satisfying `pred`
+pub fn find<T, A, P>(arr: &A, pred: P, limit: usize) -> Option<usize>
+where
+    A: InfiniteArray<T>,
+    P: Fn(&T) -> bool,
+{
+    let limit = arr.len().map_or(limit, |len| len.min(limit));
+    (0..limit).find(|&i| pred(&arr.get(i)))
+}
+
+/// Index of the first of the first `limit` elements equal to `value`
+pub fn position_of<T, A>(arr: &A, value: &T, limit: usize) -> Option<usize>
+where
+    T: PartialEq,
+    A: InfiniteArray<T>,
+{
+    find(arr, |x| x == value, limit)
+}
+
+/// The first index whose element is not less than `target`
+///
+/// Doubles a probe index until it reaches an element `>= target`, then
+/// binary searches the last bracket, so the cost is `O(log i)` evaluations
+/// for a result at index `i`. A finite array of length `len` with every
+/// element below `target` gives `Some(len)`, while an infinite one gives
+/// `None` if every representable index holds an element below `target`,
+/// which happens for bounded sequences.
+pub fn search_sorted<T, A>(arr: &Monotone<A>, target: &T) -> Option<usize>
+where
+    T: PartialOrd,
+    A: InfiniteArray<T>,
+{
+    let mut lo = 0;
+    let mut hi = 1;
+    loop {
+        if let Some(len) = arr.len().filter(|&len| hi >= len) {
+            if len == 0 || arr.get(len - 1) < *target {
+                return Some(len);
+            }
+            hi = len;
+            break;
+        }
+        if arr.get(hi - 1) >= *target {
+            break;
+        }
+        lo = hi;
+        hi = hi.checked_mul(2)?;
+    }
+
+    // The answer lies in lo..hi: arr[lo - 1] < target <= arr[hi - 1]
+    let mut hi = hi - 1;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if arr.get(mid) < *target {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    Some(lo)
+}
+
+/// Whether `value` occurs in a nondecreasing array
+///
+/// Built on [`search_sorted`], so evaluation stops as soon as an element
+/// `>= value` is found and never scans unboundedly.
+pub fn contains<T, A>(arr: &Monotone<A>, value: &T) -> bool
+where
+    T: PartialOrd,
+    A: InfiniteArray<T>,
+{
+    search_sorted(arr, value).is_some_and(|i| arr.len().is_none_or(|len| i < len) && arr.get(i) == *value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{InfiniteArrayFromFn, Ones};
+    use crate::streamed::IteratorArray;
+
+    #[test]
+    fn test_find() {
+        let squares = InfiniteArrayFromFn::new(|i| i * i);
+        assert_eq!(find(&squares, |&x| x > 50, 100), Some(8));
+        assert_eq!(find(&squares, |&x| x > 50, 5), None);
+    }
+
+    #[test]
+    fn test_search_sorted() {
+        let squares = Monotone::new(InfiniteArrayFromFn::new(|i| i * i));
+        assert_eq!(search_sorted(&squares, &0), Some(0));
+        assert_eq!(search_sorted(&squares, &49), Some(7));
+        assert_eq!(search_sorted(&squares, &50), Some(8));
+        assert_eq!(search_sorted(&squares, &1_000_000_000_000), Some(1_000_000));
+        assert_eq!(format!("{:?}", Monotone::new(Ones::<f64>::new()).describe()), "Ones");
+    }
+
+    #[test]
+    fn test_search_sorted_bounded() {
+        let bounded = Monotone::new(InfiniteArrayFromFn::new(|i| 1.0 - 1.0 / (i + 1) as f64));
+        assert_eq!(search_sorted(&bounded, &0.75), Some(3));
+        assert_eq!(search_sorted(&bounded, &2.0), None);
+
+        let finite = Monotone::new(IteratorArray::new(vec![1, 3, 3, 8]));
+        assert_eq!(finite.len(), Some(4));
+        assert_eq!(search_sorted(&finite, &3), Some(1));
+        assert_eq!(search_sorted(&finite, &8), Some(3));
+        assert_eq!(search_sorted(&finite, &9), Some(4));
+        assert!(contains(&finite, &8) && !contains(&finite, &9));
+        assert_eq!(search_sorted(&Monotone::new(IteratorArray::new(Vec::<i32>::new())), &0), Some(0));
+        assert_eq!(find(&finite.into_inner(), |&x| x > 8, 100), None);
+    }
+
+    #[test]
+    fn test_contains() {
+        let squares = Monotone::new(InfiniteArrayFromFn::new(|i| i * i));
+        assert!(contains(&squares, &1_018_081));
+        assert!(!contains(&squares, &1_018_082));
+
+        let bounded = Monotone::new(InfiniteArrayFromFn::new(|i| 1.0 - 1.0 / (i + 1) as f64));
+        assert!(!contains(&bounded, &2.0));
+    }
+
+    #[test]
+    fn test_position_of() {
+        let squares = InfiniteArrayFromFn::new(|i| i * i);
+        assert_eq!(position_of(&squares, &49, 100), Some(7));
+        assert_eq!(position_of(&squares, &50, 100), None);
+
+        let ones: Ones<f64> = Ones::new();
+        assert_eq!(position_of(&ones, &0.0, 1000), None);
+    }
+}
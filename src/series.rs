@@ -0,0 +1,233 @@
+//! Summation of infinite series with error control
+
+use crate::arrays::InfiniteArray;
+use num_traits::Float;
+use std::fmt;
+
+pub mod accelerate;
+
+/// Maximum number of terms evaluated before a summation gives up
+pub const DEFAULT_TERM_BUDGET: usize = 1_000_000;
+
+/// Errors raised when a series cannot be summed to the requested tolerance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesError {
+    /// The term at `index` has the same sign as its predecessor
+    NotAlternating { index: usize },
+    /// The term at `index` is larger in magnitude than its predecessor
+    NotDecreasing { index: usize },
+    /// The tolerance was not reached within `terms` evaluations
+    BudgetExhausted { terms: usize },
+}
+
+impl fmt::Display for SeriesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeriesError::NotAlternating { index } => {
+                write!(f, "series is not alternating at index {}", index)
+            }
+            SeriesError::NotDecreasing { index } => {
+                write!(f, "series terms increase in magnitude at index {}", index)
+            }
+            SeriesError::BudgetExhausted { terms } => {
+                write!(f, "series did not converge within {} terms", terms)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SeriesError {}
+
+/// How much confidence a [`ConvergenceReport`] carries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Convergence {
+    /// The value was computed exactly from finitely many nonzero terms
+    Exact,
+    /// The remainder is a guaranteed bound on the error
+    Bounded,
+    /// The remainder is a heuristic estimate of the error
+    Estimated,
+}
+
+/// The result of a series or limit computation with diagnostics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConvergenceReport<T> {
+    /// Computed value
+    pub value: T,
+    /// Number of terms evaluated
+    pub terms: usize,
+    /// The last term added to the running sum
+    pub last_increment: T,
+    /// Bound or estimate of `|value - exact|`, depending on `convergence`
+    pub remainder: T,
+    /// Whether the value is exact, rigorously bounded, or estimated
+    pub convergence: Convergence,
+}
+
+impl<T> ConvergenceReport<T> {
+    /// Whether `remainder` is a guaranteed bound rather than an estimate
+    pub fn is_rigorous(&self) -> bool {
+        self.convergence != Convergence::Estimated
+    }
+}
+
+/// Sum an alternating series whose terms decrease in magnitude
+///
+/// By the alternating series test the exact sum lies between any two
+/// consecutive partial sums, so stopping at the first term `a[n]` with
+/// `|a[n]| / 2 <= tol` and returning the midpoint of `S[n]` and `S[n+1]`
+/// gives an error of at most `tol`. Terms are checked as they are read and
+/// an error is returned as soon as the alternating or decreasing property
+/// fails.
+pub fn sum_alternating<T, A>(arr: &A, tol: T) -> Result<ConvergenceReport<T>, SeriesError>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    let two = T::one() + T::one();
+    let mut sum = T::zero();
+    let mut prev: Option<T> = None;
+
+    for index in 0..DEFAULT_TERM_BUDGET {
+        let term = arr.get(index);
+        if let Some(p) = prev {
+            if term != T::zero() && term.signum() == p.signum() {
+                return Err(SeriesError::NotAlternating { index });
+            }
+            if term.abs() > p.abs() {
+                return Err(SeriesError::NotDecreasing { index });
+            }
+        }
+
+        let half = term.abs() / two;
+        if half <= tol {
+            return Ok(ConvergenceReport {
+                value: sum + term / two,
+                terms: index + 1,
+                last_increment: term,
+                remainder: half,
+                convergence: Convergence::Bounded,
+            });
+        }
+
+        sum = sum + term;
+        prev = Some(term);
+    }
+
+    Err(SeriesError::BudgetExhausted {
+        terms: DEFAULT_TERM_BUDGET,
+    })
+}
+
+/// Estimate the sum of the tail `a[n] + a[n+1] + ...` from `a[n-3..n]`
+///
+/// Both a geometric model `a[k] ≈ C r^k` and a power-law model
+/// `a[k] ≈ C (k+1)^-s` are fitted to `a[n-3]` and `a[n-2]`; the one that
+/// better predicts `a[n-1]` is then summed in closed form (the power law via
+/// its integral). Returns `None` if `n < 3` or the fitted model does not
+/// decay fast enough to be summable.
+pub fn tail_estimate<T, A>(arr: &A, n: usize) -> Option<T>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    if n < 3 {
+        return None;
+    }
+    estimate_tail([arr.get(n - 3), arr.get(n - 2), arr.get(n - 1)], n)
+}
+
+/// [`tail_estimate`] from the three terms preceding index `n`
+pub(crate) fn estimate_tail<T: Float>(terms: [T; 3], n: usize) -> Option<T> {
+    let [a0, a1, a2] = terms;
+    if a1 == T::zero() && a2 == T::zero() {
+        return Some(T::zero());
+    }
+    if a0 == T::zero() || a1 == T::zero() {
+        return None;
+    }
+
+    let one = T::one();
+    let ratio = a1 / a0;
+    let geometric_error = (a1 * ratio - a2).abs();
+
+    // Power law in 1-based positions k = n-2, n-1, n
+    let k1 = T::from(n - 1)?;
+    let k2 = T::from(n)?;
+    let exponent = (a0.abs() / a1.abs()).ln() / (k1 / (k1 - one)).ln();
+    let power_error = (a1 * (k1 / k2).powf(exponent) - a2).abs();
+
+    if geometric_error <= power_error {
+        if ratio.abs() < one {
+            Some(a2 * ratio / (one - ratio))
+        } else {
+            None
+        }
+    } else if exponent > one {
+        // C k^-s summed over k > n, approximated by ∫_{n+1/2}^∞
+        let half = one / (one + one);
+        let scale = a2 * k2.powf(exponent);
+        Some(scale * (k2 + half).powf(one - exponent) / (exponent - one))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{InfiniteArrayFromFn, Ones};
+
+    #[test]
+    fn test_sum_alternating_harmonic() {
+        // 1 - 1/2 + 1/3 - ... = ln 2
+        let arr = InfiniteArrayFromFn::new(|i| {
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            sign / (i + 1) as f64
+        });
+        let result = sum_alternating(&arr, 1e-6).unwrap();
+        assert!((result.value - 2f64.ln()).abs() <= result.remainder);
+        assert!(result.remainder <= 1e-6);
+        assert!(result.is_rigorous());
+    }
+
+    #[test]
+    fn test_tail_estimate_geometric() {
+        // Σ_{k>=10} 2^-k = 2^-9
+        let arr = InfiniteArrayFromFn::new(|i| 0.5f64.powi(i as i32));
+        let tail = tail_estimate(&arr, 10).unwrap();
+        assert!((tail - 0.5f64.powi(9)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_tail_estimate_power_law() {
+        // Σ_{k>100} 1/k² ≈ 1/100.5
+        let arr = InfiniteArrayFromFn::new(|i| 1.0 / ((i + 1) * (i + 1)) as f64);
+        let tail = tail_estimate(&arr, 100).unwrap();
+        let exact: f64 = (101..1_000_000).map(|k| 1.0 / (k as f64 * k as f64)).sum();
+        assert!((tail - exact).abs() / exact < 1e-2);
+        let ones: Ones<f64> = Ones::new();
+        assert_eq!(tail_estimate(&ones, 100), None);
+    }
+
+    #[test]
+    fn test_sum_alternating_rejects_non_alternating() {
+        let ones: Ones<f64> = Ones::new();
+        assert_eq!(
+            sum_alternating(&ones, 1e-6),
+            Err(SeriesError::NotAlternating { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_sum_alternating_rejects_increasing() {
+        let arr = InfiniteArrayFromFn::new(|i| {
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            sign * (i + 1) as f64
+        });
+        assert_eq!(
+            sum_alternating(&arr, 1e-6),
+            Err(SeriesError::NotDecreasing { index: 1 })
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Convergence acceleration of partial-sum sequences
+//!
+//! Each transform takes an infinite array of partial sums `S[0], S[1], ...`
+//! and returns a new infinite array whose entries converge to the same
+//! limit, usually much faster.
+
+use crate::arrays::{InfiniteArray, InfiniteArrayFromFn};
+use num_traits::Float;
+
+/// Aitken's Δ² process
+///
+/// `A[n] = S[n] - (S[n+1] - S[n])² / (S[n+2] - 2 S[n+1] + S[n])`, falling back
+/// to `S[n+2]` when the second difference vanishes.
+pub fn aitken<T, A>(partial_sums: A) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    InfiniteArrayFromFn::new(move |n| {
+        let s0 = partial_sums.get(n);
+        let s1 = partial_sums.get(n + 1);
+        let s2 = partial_sums.get(n + 2);
+        let denom = s2 - (s1 + s1) + s0;
+        if denom == T::zero() {
+            s2
+        } else {
+            let d = s1 - s0;
+            s0 - d * d / denom
+        }
+    })
+}
+
+/// Richardson extrapolation of the given order
+///
+/// Assumes the error of `S[n]` has an asymptotic expansion in powers of
+/// `1/(n+1)` and eliminates its first `order` terms using
+/// `S[n], ..., S[n+order]`.
+pub fn richardson<T, A>(partial_sums: A, order: usize) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    InfiniteArrayFromFn::new(move |n| {
+        let mut acc = T::zero();
+        for k in 0..=order {
+            let m = T::from(n + k + 1).unwrap();
+            let sign = if (k + order).is_multiple_of(2) { T::one() } else { -T::one() };
+            let weight = m.powi(order as i32) / (factorial::<T>(k) * factorial::<T>(order - k));
+            acc = acc + sign * weight * partial_sums.get(n + k);
+        }
+        acc
+    })
+}
+
+/// Euler transform of a sequence of partial sums
+///
+/// `E[n] = 2⁻ⁿ Σ_{j=0}^{n} C(n, j) S[j]`, computed by repeated averaging of
+/// neighbouring partial sums. Particularly effective for alternating series.
+pub fn euler<T, A>(partial_sums: A) -> InfiniteArrayFromFn<impl Fn(usize) -> T, T>
+where
+    T: Float,
+    A: InfiniteArray<T>,
+{
+    InfiniteArrayFromFn::new(move |n| {
+        let two = T::one() + T::one();
+        let mut row: Vec<T> = (0..=n).map(|j| partial_sums.get(j)).collect();
+        for len in (1..=n).rev() {
+            for j in 0..len {
+                row[j] = (row[j] + row[j + 1]) / two;
+            }
+        }
+        row[0]
+    })
+}
+
+fn factorial<T: Float>(n: usize) -> T {
+    (1..=n).fold(T::one(), |acc, k| acc * T::from(k).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::cumsum;
+
+    fn alternating_harmonic() -> InfiniteArrayFromFn<impl Fn(usize) -> f64 + Clone, f64> {
+        InfiniteArrayFromFn::new(|i| {
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            sign / (i + 1) as f64
+        })
+    }
+
+    #[test]
+    fn test_aitken() {
+        let sums = cumsum(alternating_harmonic());
+        let accelerated = aitken(sums);
+        assert!((accelerated.get(10) - 2f64.ln()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_richardson() {
+        // Partial sums of 1/k² converge to π²/6 with error ~ 1/n
+        let sums = cumsum(InfiniteArrayFromFn::new(|i| 1.0 / ((i + 1) * (i + 1)) as f64));
+        let accelerated = richardson(sums, 4);
+        let exact = std::f64::consts::PI.powi(2) / 6.0;
+        assert!((accelerated.get(10) - exact).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_euler() {
+        let sums = cumsum(alternating_harmonic());
+        let accelerated = euler(sums);
+        assert!((accelerated.get(20) - 2f64.ln()).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,174 @@
+//! Batch evaluation of element-wise arithmetic, several lanes at a time
+//!
+//! The combinators here implement [`InfiniteArray::get_range`] by reading
+//! their operands in blocks of [`LANES`] elements into fixed-size buffers
+//! and combining the buffers lane by lane. For `f32` and `f64` the compiler
+//! turns those loops into vector instructions, so materializing a prefix
+//! evaluates 4–8 elements per instruction instead of one.
+
+use crate::arrays::{Indexed, InfiniteArray};
+use crate::describe::Description;
+use num_traits::Zero;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Sub};
+
+/// Number of elements evaluated together
+pub const LANES: usize = 8;
+
+/// A binary operation applied lane by lane
+pub trait LaneOp<T> {
+    /// Name used by [`InfiniteArray::describe`]
+    const NAME: &'static str;
+
+    fn apply(a: T, b: T) -> T;
+}
+
+macro_rules! lane_ops {
+    ($($(#[$doc:meta])* $name:ident: $bound:ident, $op:tt, $label:literal;)*) => {$(
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name;
+
+        impl<T: $bound<Output = T>> LaneOp<T> for $name {
+            const NAME: &'static str = $label;
+
+            #[inline(always)]
+            fn apply(a: T, b: T) -> T {
+                a $op b
+            }
+        }
+    )*};
+}
+
+lane_ops! {
+    /// Lane-wise `a + b`
+    AddOp: Add, +, "add_simd";
+    /// Lane-wise `a - b`
+    SubOp: Sub, -, "sub_simd";
+    /// Lane-wise `a * b`
+    MulOp: Mul, *, "mul_simd";
+    /// Lane-wise `a / b`
+    DivOp: Div, /, "div_simd";
+}
+
+/// Element-wise combination of two arrays with a vectorized `get_range`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Elementwise<A, B, Op> {
+    a: A,
+    b: B,
+    _op: PhantomData<Op>,
+}
+
+impl<A, B, Op> Elementwise<A, B, Op> {
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b, _op: PhantomData }
+    }
+}
+
+/// `a + b`, evaluated in batches
+pub fn add_simd<A, B>(a: A, b: B) -> Elementwise<A, B, AddOp> {
+    Elementwise::new(a, b)
+}
+
+/// `a - b`, evaluated in batches
+pub fn sub_simd<A, B>(a: A, b: B) -> Elementwise<A, B, SubOp> {
+    Elementwise::new(a, b)
+}
+
+/// `a * b`, evaluated in batches
+pub fn mul_simd<A, B>(a: A, b: B) -> Elementwise<A, B, MulOp> {
+    Elementwise::new(a, b)
+}
+
+/// `a / b`, evaluated in batches
+pub fn div_simd<A, B>(a: A, b: B) -> Elementwise<A, B, DivOp> {
+    Elementwise::new(a, b)
+}
+
+impl<T, A, B, Op> InfiniteArray<T> for Elementwise<A, B, Op>
+where
+    T: Zero + Copy,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+    Op: LaneOp<T>,
+{
+    fn get(&self, index: usize) -> T {
+        Op::apply(self.a.get(index), self.b.get(index))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+
+    fn describe(&self) -> Description {
+        Description::new(Op::NAME, [self.a.describe(), self.b.describe()])
+    }
+
+    fn len(&self) -> Option<usize> {
+        match (self.a.len(), self.b.len()) {
+            (Some(m), Some(n)) => Some(m.min(n)),
+            (m, n) => m.or(n),
+        }
+    }
+
+    fn get_range(&self, start: usize, out: &mut [T]) {
+        let mut lhs = [T::zero(); LANES];
+        let mut rhs = [T::zero(); LANES];
+        for (block, chunk) in out.chunks_mut(LANES).enumerate() {
+            let first = start + block * LANES;
+            if chunk.len() < LANES {
+                self.a.get_range(first, chunk);
+                self.b.get_range(first, &mut rhs[..chunk.len()]);
+                for (x, &y) in chunk.iter_mut().zip(&rhs) {
+                    *x = Op::apply(*x, y);
+                }
+                continue;
+            }
+            self.a.get_range(first, &mut lhs);
+            self.b.get_range(first, &mut rhs);
+            for lane in 0..LANES {
+                chunk[lane] = Op::apply(lhs[lane], rhs[lane]);
+            }
+        }
+    }
+}
+
+/// The first `n` elements of `arr`, read through [`InfiniteArray::get_range`]
+pub fn materialize<T, A>(arr: &A, n: usize) -> Vec<T>
+where
+    T: Zero + Copy,
+    A: InfiniteArray<T> + ?Sized,
+{
+    let n = arr.len().map_or(n, |len| len.min(n));
+    let mut values = vec![T::zero(); n];
+    arr.get_range(0, &mut values);
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArrayFromFn, Ones};
+
+    #[test]
+    fn test_elementwise_matches_get() {
+        let x = InfiniteArrayFromFn::new(|i| i as f64 * 0.5);
+        let poly = add_simd(mul_simd(&x, &x), Fill::new(1.0));
+        let batch = materialize(&poly, 37);
+        assert_eq!(batch.len(), 37);
+        for (i, value) in batch.iter().enumerate() {
+            assert_eq!(*value, poly.get(i));
+        }
+        let mut window = [0.0f32; 5];
+        div_simd(Ones::<f32>::new(), InfiniteArrayFromFn::new(|i| (i + 1) as f32)).get_range(3, &mut window);
+        assert_eq!(window, [0.25, 0.2, 1.0 / 6.0, 1.0 / 7.0, 0.125]);
+    }
+
+    #[test]
+    fn test_sub_simd_offset_ranges() {
+        let diff = sub_simd(InfiniteArrayFromFn::new(|i| (i * i) as i64), InfiniteArrayFromFn::new(|i| i as i64));
+        let mut out = [0; 20];
+        diff.get_range(100, &mut out);
+        assert!(out.iter().enumerate().all(|(k, &v)| v == ((100 + k) * (99 + k)) as i64));
+    }
+}
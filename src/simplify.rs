@@ -0,0 +1,282 @@
+//! Algebraic identities on structured arrays
+//!
+//! Arithmetic between the constant arrays returns another structured array
+//! instead of a closure over its operands, so the result stays symbolic:
+//! `Zeros + x` is `x`, `Ones * k` is `Fill(k)`, `Fill(a) + Fill(b)` is
+//! `Fill(a + b)`. [`StructuredCumsum`] does the same for cumulative sums,
+//! whose closed forms are zeros and ranges. The ranges are closed under
+//! shifts, scaling and negation the same way, so `-OneToInf` is the
+//! decreasing `InfStepRange(-1, -1)`, and geometric ranges under scaling
+//! and element-wise products.
+
+use crate::arrays::{Fill, InfiniteArray, Ones, Zeros};
+use crate::ranges::{InfGeometricRange, InfStepRange, InfUnitRange, OneToInf};
+use num_traits::One;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+impl<T, A> Add<A> for Zeros<T>
+where
+    A: InfiniteArray<T>,
+{
+    type Output = A;
+
+    /// `0 + x = x`
+    fn add(self, other: A) -> A {
+        other
+    }
+}
+
+impl<T> Mul<T> for Zeros<T> {
+    type Output = Zeros<T>;
+
+    /// `0 k = 0`
+    fn mul(self, _scalar: T) -> Zeros<T> {
+        self
+    }
+}
+
+impl<T> Add<Zeros<T>> for Ones<T> {
+    type Output = Ones<T>;
+
+    /// `1 + 0 = 1`
+    fn add(self, _zeros: Zeros<T>) -> Ones<T> {
+        self
+    }
+}
+
+impl<T> Mul<T> for Ones<T> {
+    type Output = Fill<T>;
+
+    /// `1 k = Fill(k)`
+    fn mul(self, scalar: T) -> Fill<T> {
+        Fill::new(scalar)
+    }
+}
+
+impl<T> Add<Zeros<T>> for Fill<T> {
+    type Output = Fill<T>;
+
+    /// `Fill(a) + 0 = Fill(a)`
+    fn add(self, _zeros: Zeros<T>) -> Fill<T> {
+        self
+    }
+}
+
+impl<T: Clone + Mul<Output = T>> Mul<T> for Fill<T> {
+    type Output = Fill<T>;
+
+    /// `Fill(a) k = Fill(a k)`
+    fn mul(self, scalar: T) -> Fill<T> {
+        Fill::new(self.value().clone() * scalar)
+    }
+}
+
+impl<T: Clone + Neg<Output = T>> Neg for Fill<T> {
+    type Output = Fill<T>;
+
+    fn neg(self) -> Fill<T> {
+        Fill::new(-self.value().clone())
+    }
+}
+
+/// `Fill(a) op Fill(b) = Fill(a op b)`
+macro_rules! fill_ops {
+    ($($trait:ident, $method:ident;)*) => {$(
+        impl<T: Clone + $trait<Output = T>> $trait for Fill<T> {
+            type Output = Fill<T>;
+
+            fn $method(self, other: Fill<T>) -> Fill<T> {
+                Fill::new($trait::$method(self.value().clone(), other.value().clone()))
+            }
+        }
+    )*};
+}
+
+fill_ops! {
+    Add, add;
+    Sub, sub;
+    Mul, mul;
+    Div, div;
+}
+
+/// Cumulative sums with a closed form
+pub trait StructuredCumsum {
+    type Output;
+
+    fn cumsum(self) -> Self::Output;
+}
+
+impl<T> StructuredCumsum for Zeros<T> {
+    type Output = Zeros<T>;
+
+    fn cumsum(self) -> Zeros<T> {
+        self
+    }
+}
+
+impl<T> StructuredCumsum for Ones<T> {
+    type Output = OneToInf<T>;
+
+    /// `1, 2, 3, ...`
+    fn cumsum(self) -> OneToInf<T> {
+        OneToInf::new()
+    }
+}
+
+impl<T: Clone> StructuredCumsum for Fill<T> {
+    type Output = InfStepRange<T>;
+
+    /// `a, 2a, 3a, ...`
+    fn cumsum(self) -> InfStepRange<T> {
+        InfStepRange::new(self.value().clone(), self.value().clone())
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for InfStepRange<T> {
+    type Output = InfStepRange<T>;
+
+    /// `-(a + k d) = -a + k (-d)`, decreasing if the range increased
+    fn neg(self) -> InfStepRange<T> {
+        let (start, step) = self.into_parts();
+        InfStepRange::new(-start, -step)
+    }
+}
+
+impl<T: Clone + Mul<Output = T>> Mul<T> for InfStepRange<T> {
+    type Output = InfStepRange<T>;
+
+    /// `(a + k d) c = a c + k (d c)`
+    fn mul(self, scalar: T) -> InfStepRange<T> {
+        let (start, step) = self.into_parts();
+        InfStepRange::new(start * scalar.clone(), step * scalar)
+    }
+}
+
+/// Shifting a range by a scalar keeps its step
+macro_rules! shift_ops {
+    ($($trait:ident, $method:ident;)*) => {$(
+        impl<T: $trait<Output = T>> $trait<T> for InfStepRange<T> {
+            type Output = InfStepRange<T>;
+
+            fn $method(self, scalar: T) -> InfStepRange<T> {
+                let (start, step) = self.into_parts();
+                InfStepRange::new($trait::$method(start, scalar), step)
+            }
+        }
+
+        impl<T: $trait<Output = T>> $trait<T> for InfUnitRange<T> {
+            type Output = InfUnitRange<T>;
+
+            fn $method(self, scalar: T) -> InfUnitRange<T> {
+                InfUnitRange::new($trait::$method(self.into_start(), scalar))
+            }
+        }
+    )*};
+}
+
+shift_ops! {
+    Add, add;
+    Sub, sub;
+}
+
+impl<T: Add<Output = T>> Add for InfStepRange<T> {
+    type Output = InfStepRange<T>;
+
+    /// `(a + k d) + (b + k e) = (a + b) + k (d + e)`
+    fn add(self, other: InfStepRange<T>) -> InfStepRange<T> {
+        let ((a, d), (b, e)) = (self.into_parts(), other.into_parts());
+        InfStepRange::new(a + b, d + e)
+    }
+}
+
+impl<T: One + Neg<Output = T>> Neg for InfUnitRange<T> {
+    type Output = InfStepRange<T>;
+
+    /// `-a, -a - 1, -a - 2, ...`
+    fn neg(self) -> InfStepRange<T> {
+        InfStepRange::new(-self.into_start(), -T::one())
+    }
+}
+
+impl<T: One + Neg<Output = T>> Neg for OneToInf<T> {
+    type Output = InfStepRange<T>;
+
+    /// `-1, -2, -3, ...`
+    fn neg(self) -> InfStepRange<T> {
+        InfStepRange::new(-T::one(), -T::one())
+    }
+}
+
+impl<T: Mul<Output = T>> Mul<T> for InfGeometricRange<T> {
+    type Output = InfGeometricRange<T>;
+
+    /// `(a r^k) c = (a c) r^k`
+    fn mul(self, scalar: T) -> InfGeometricRange<T> {
+        let (start, ratio) = self.into_parts();
+        InfGeometricRange::new(start * scalar, ratio)
+    }
+}
+
+impl<T: Mul<Output = T>> Mul for InfGeometricRange<T> {
+    type Output = InfGeometricRange<T>;
+
+    /// `(a r^k) (b s^k) = (a b) (r s)^k`
+    fn mul(self, other: InfGeometricRange<T>) -> InfGeometricRange<T> {
+        let ((a, r), (b, s)) = (self.into_parts(), other.into_parts());
+        InfGeometricRange::new(a * b, r * s)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for InfGeometricRange<T> {
+    type Output = InfGeometricRange<T>;
+
+    fn neg(self) -> InfGeometricRange<T> {
+        let (start, ratio) = self.into_parts();
+        InfGeometricRange::new(-start, ratio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+    use crate::operations::cumsum;
+
+    #[test]
+    fn test_constant_arithmetic_stays_symbolic() {
+        let x = InfiniteArrayFromFn::new(|i| i as f64);
+        let same: InfiniteArrayFromFn<_, f64> = Zeros::new() + x;
+        assert_eq!(same.get(7), 7.0);
+        let scaled: Fill<f64> = Ones::new() * 3.0;
+        assert_eq!(scaled, Fill::new(3.0));
+        assert_eq!(Fill::new(1.5) + Fill::new(2.0), Fill::new(3.5));
+        assert_eq!(-(Fill::new(6) / Fill::new(2) - Fill::new(1)) * 4, Fill::new(-8));
+        assert_eq!(Fill::new(2) + Zeros::new(), Fill::new(2));
+    }
+
+    #[test]
+    fn test_structured_cumsum_matches_cumsum() {
+        let zeros = StructuredCumsum::cumsum(Zeros::<i64>::new());
+        assert_eq!(zeros.support(), Some(0));
+        let counts = StructuredCumsum::cumsum(Ones::<usize>::new());
+        let steps = StructuredCumsum::cumsum(Fill::new(3usize));
+        let expected = cumsum(Fill::new(3usize));
+        assert!((0..20).all(|i| steps.get(i) == expected.get(i) && counts.get(i) == i + 1));
+    }
+
+    #[test]
+    fn test_range_arithmetic_stays_symbolic() {
+        let countdown = -OneToInf::<i64>::new() * 2 + 102;
+        assert_eq!(countdown, InfStepRange::new(100, -2));
+        assert_eq!(countdown.iter().take(3).collect::<Vec<_>>(), vec![100, 98, 96]);
+        assert_eq!(-InfUnitRange::new(5i32) - 1, InfStepRange::new(-6, -1));
+        assert_eq!(InfUnitRange::new(5i32) + 1, InfUnitRange::new(6));
+        let flat = countdown + InfStepRange::new(0, 2);
+        assert_eq!((flat.get(0), flat.get(1000)), (100, 100));
+        assert_eq!(-StructuredCumsum::cumsum(Fill::new(0.5)), InfStepRange::new(-0.5, -0.5));
+
+        let decay = InfGeometricRange::new(1.0, 0.5) * InfGeometricRange::new(3.0, 0.5) * 2.0;
+        assert_eq!(decay, InfGeometricRange::new(6.0, 0.25));
+        assert_eq!((-decay).sum(), Some(-8.0));
+    }
+}
@@ -0,0 +1,336 @@
+//! Lazy combinations of sorted infinite arrays
+
+use crate::arrays::{Indexed, InfiniteArray, InfiniteVector};
+use crate::search::Monotone;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// A heap entry ordered so that `BinaryHeap` pops the smallest value first,
+/// breaking ties by source index
+struct HeapEntry<T> {
+    value: T,
+    source: usize,
+}
+
+impl<T: PartialOrd> PartialEq for HeapEntry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: PartialOrd> Eq for HeapEntry<T> {}
+
+impl<T: PartialOrd> PartialOrd for HeapEntry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PartialOrd> Ord for HeapEntry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .value
+            .partial_cmp(&self.value)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.source.cmp(&self.source))
+    }
+}
+
+struct MergeState<T> {
+    heap: BinaryHeap<HeapEntry<T>>,
+    cursors: Vec<usize>,
+    output: Vec<T>,
+}
+
+/// The sorted merge of several nondecreasing arrays, see [`merge_sorted`]
+pub struct MergeSorted<T, A> {
+    sources: Vec<Monotone<A>>,
+    state: RefCell<MergeState<T>>,
+}
+
+impl<T, A> MergeSorted<T, A>
+where
+    T: PartialOrd + Clone,
+    A: InfiniteArray<T>,
+{
+    /// Number of merged elements computed so far
+    pub fn computed_len(&self) -> usize {
+        self.state.borrow().output.len()
+    }
+}
+
+impl<T, A> InfiniteArray<T> for MergeSorted<T, A>
+where
+    T: PartialOrd + Clone,
+    A: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        let mut state = self.state.borrow_mut();
+        while state.output.len() <= index {
+            let HeapEntry { value, source } = state
+                .heap
+                .pop()
+                .expect("merge_sorted requires at least one source");
+            state.output.push(value);
+            state.cursors[source] += 1;
+            let next = self.sources[source].get(state.cursors[source]);
+            state.heap.push(HeapEntry { value: next, source });
+        }
+        state.output[index].clone()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+}
+
+impl<T, A> InfiniteVector<T> for MergeSorted<T, A>
+where
+    T: PartialOrd + Clone,
+    A: InfiniteArray<T>,
+{
+}
+
+/// Merge nondecreasing arrays into a single nondecreasing array
+///
+/// Duplicates are kept, so this is a multiset union. A heap holds the next
+/// element of every source and the merged prefix is cached, so reading the
+/// first `n` elements costs `O(n log k)` for `k` sources.
+///
+/// # Panics
+///
+/// Panics on access if `arrays` is empty.
+pub fn merge_sorted<T, A>(arrays: Vec<Monotone<A>>) -> Monotone<MergeSorted<T, A>>
+where
+    T: PartialOrd + Clone,
+    A: InfiniteArray<T>,
+{
+    let heap = arrays
+        .iter()
+        .enumerate()
+        .map(|(source, arr)| HeapEntry {
+            value: arr.get(0),
+            source,
+        })
+        .collect();
+    let cursors = vec![0; arrays.len()];
+    Monotone::new(MergeSorted {
+        sources: arrays,
+        state: RefCell::new(MergeState {
+            heap,
+            cursors,
+            output: Vec::new(),
+        }),
+    })
+}
+
+/// Which set operation a [`SetOperation`] computes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SetKind {
+    Union,
+    Intersection,
+    Difference,
+}
+
+struct SetState<T> {
+    left: usize,
+    right: usize,
+    output: Vec<T>,
+}
+
+/// A set operation on two nondecreasing arrays, see [`union`],
+/// [`intersection`] and [`difference`]
+pub struct SetOperation<T, A, B> {
+    kind: SetKind,
+    left: Monotone<A>,
+    right: Monotone<B>,
+    state: RefCell<SetState<T>>,
+}
+
+impl<T, A, B> SetOperation<T, A, B> {
+    fn new(kind: SetKind, left: Monotone<A>, right: Monotone<B>) -> Monotone<Self> {
+        Monotone::new(Self {
+            kind,
+            left,
+            right,
+            state: RefCell::new(SetState {
+                left: 0,
+                right: 0,
+                output: Vec::new(),
+            }),
+        })
+    }
+
+    /// Which operation this computes
+    pub fn kind(&self) -> SetKind {
+        self.kind
+    }
+}
+
+impl<T, A, B> SetOperation<T, A, B>
+where
+    T: PartialOrd + Clone,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    /// Advance the cursors until one more element is emitted
+    fn step(&self, state: &mut SetState<T>) -> T {
+        loop {
+            let a = self.left.get(state.left);
+            let b = self.right.get(state.right);
+            let candidate = match self.kind {
+                SetKind::Union => {
+                    if a < b {
+                        state.left += 1;
+                        Some(a)
+                    } else if b < a {
+                        state.right += 1;
+                        Some(b)
+                    } else {
+                        state.left += 1;
+                        state.right += 1;
+                        Some(a)
+                    }
+                }
+                SetKind::Intersection => {
+                    if a < b {
+                        state.left += 1;
+                        None
+                    } else if b < a {
+                        state.right += 1;
+                        None
+                    } else {
+                        state.left += 1;
+                        state.right += 1;
+                        Some(a)
+                    }
+                }
+                SetKind::Difference => {
+                    if a < b {
+                        state.left += 1;
+                        Some(a)
+                    } else if b < a {
+                        state.right += 1;
+                        None
+                    } else {
+                        state.left += 1;
+                        None
+                    }
+                }
+            };
+            match candidate {
+                Some(value) if state.output.last() != Some(&value) => return value,
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<T, A, B> InfiniteArray<T> for SetOperation<T, A, B>
+where
+    T: PartialOrd + Clone,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    fn get(&self, index: usize) -> T {
+        let mut state = self.state.borrow_mut();
+        while state.output.len() <= index {
+            let value = self.step(&mut state);
+            state.output.push(value);
+        }
+        state.output[index].clone()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |i| self.get(i)))
+    }
+}
+
+impl<T, A, B> InfiniteVector<T> for SetOperation<T, A, B>
+where
+    T: PartialOrd + Clone,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+}
+
+/// Distinct values occurring in either array
+///
+/// Like the other set operations, the result is computed lazily from two
+/// cursors and cached, so reading a prefix sequentially is linear in the
+/// number of input elements consumed.
+pub fn union<T, A, B>(a: Monotone<A>, b: Monotone<B>) -> Monotone<SetOperation<T, A, B>>
+where
+    T: PartialOrd + Clone,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    SetOperation::new(SetKind::Union, a, b)
+}
+
+/// Distinct values occurring in both arrays
+///
+/// Accessing an element past the last common value never returns, e.g. for
+/// the intersection of the even and odd numbers.
+pub fn intersection<T, A, B>(a: Monotone<A>, b: Monotone<B>) -> Monotone<SetOperation<T, A, B>>
+where
+    T: PartialOrd + Clone,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    SetOperation::new(SetKind::Intersection, a, b)
+}
+
+/// Distinct values occurring in `a` but not in `b`
+///
+/// As with [`intersection`], accessing an element that does not exist never
+/// returns.
+pub fn difference<T, A, B>(a: Monotone<A>, b: Monotone<B>) -> Monotone<SetOperation<T, A, B>>
+where
+    T: PartialOrd + Clone,
+    A: InfiniteArray<T>,
+    B: InfiniteArray<T>,
+{
+    SetOperation::new(SetKind::Difference, a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+
+    type Multiples = InfiniteArrayFromFn<Box<dyn Fn(usize) -> usize>, usize>;
+
+    fn multiples(k: usize) -> Monotone<Multiples> {
+        Monotone::new(InfiniteArrayFromFn::new(Box::new(move |i| k * i)))
+    }
+
+    #[test]
+    fn test_merge_sorted() {
+        let merged = merge_sorted(vec![multiples(2), multiples(3)]);
+
+        let prefix: Vec<usize> = merged.iter().take(8).collect();
+        assert_eq!(prefix, vec![0, 0, 2, 3, 4, 6, 6, 8]);
+        assert_eq!(merged.get(3), 3);
+        assert_eq!(merged.inner().computed_len(), 8);
+    }
+
+    #[test]
+    fn test_union() {
+        let result = union(multiples(2), multiples(3));
+        let prefix: Vec<usize> = result.iter().take(8).collect();
+        assert_eq!(prefix, vec![0, 2, 3, 4, 6, 8, 9, 10]);
+    }
+
+    #[test]
+    fn test_intersection_and_difference() {
+        let both = intersection(multiples(2), multiples(3));
+        let prefix: Vec<usize> = both.iter().take(4).collect();
+        assert_eq!(prefix, vec![0, 6, 12, 18]);
+
+        let only_evens = difference(multiples(2), multiples(3));
+        let prefix: Vec<usize> = only_evens.iter().take(5).collect();
+        assert_eq!(prefix, vec![2, 4, 8, 10, 14]);
+    }
+}
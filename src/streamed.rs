@@ -0,0 +1,390 @@
+//! Arrays whose elements arrive over a channel
+//!
+//! A [`StreamedArray`] wraps the receiving end of a channel. Element `i` is
+//! the `i`-th value sent; reading it waits until it has arrived, and every
+//! received value is kept so it can be read again. An [`IteratorArray`]
+//! does the same for the values of an iterator, pulling them on demand, and
+//! a [`GeneratorArray`] for the outputs of a stateful step function.
+
+use crate::arrays::{Indexed, InfiniteArray};
+use crate::error::Error;
+use std::cell::{Cell, Ref, RefCell};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::time::{Duration, Instant};
+
+/// The state of an element of a [`StreamedArray`] when read without waiting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetch<T> {
+    /// The element has arrived
+    Ready(T),
+    /// The element has not arrived yet
+    Pending,
+    /// Every sender was dropped before the element was sent
+    Closed,
+}
+
+impl<T> Fetch<T> {
+    /// The element, if it has arrived
+    pub fn ready(self) -> Option<T> {
+        match self {
+            Fetch::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// An infinite array fed by a producer through a channel, memoizing every
+/// element it receives
+///
+/// [`InfiniteArray::get`] blocks until the element arrives and panics if the
+/// producer stops before sending it; [`poll`](Self::poll) and
+/// [`get_timeout`](Self::get_timeout) never block indefinitely.
+pub struct StreamedArray<T> {
+    receiver: Receiver<T>,
+    received: RefCell<Vec<T>>,
+    closed: Cell<bool>,
+}
+
+impl<T: Clone> StreamedArray<T> {
+    /// An array of the values received by `receiver`, in order
+    pub fn new(receiver: Receiver<T>) -> Self {
+        Self {
+            receiver,
+            received: RefCell::new(Vec::new()),
+            closed: Cell::new(false),
+        }
+    }
+
+    /// A connected sender and array
+    pub fn channel() -> (Sender<T>, Self) {
+        let (sender, receiver) = mpsc::channel();
+        (sender, Self::new(receiver))
+    }
+
+    /// Number of elements received so far
+    pub fn received(&self) -> usize {
+        self.drain();
+        self.received.borrow().len()
+    }
+
+    /// Whether every sender has been dropped and all sent elements received
+    pub fn is_closed(&self) -> bool {
+        self.drain();
+        self.closed.get()
+    }
+
+    /// Element `index` if it has arrived, without waiting
+    pub fn poll(&self, index: usize) -> Fetch<T> {
+        self.drain();
+        self.fetch(index).unwrap_or(Fetch::Pending)
+    }
+
+    /// Element `index`, waiting until it arrives
+    pub fn wait(&self, index: usize) -> Fetch<T> {
+        loop {
+            if let Some(fetch) = self.fetch(index) {
+                return fetch;
+            }
+            match self.receiver.recv() {
+                Ok(value) => self.received.borrow_mut().push(value),
+                Err(_) => self.closed.set(true),
+            }
+        }
+    }
+
+    /// Element `index`, waiting at most `timeout` for it to arrive
+    pub fn get_timeout(&self, index: usize, timeout: Duration) -> Fetch<T> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(fetch) = self.fetch(index) {
+                return fetch;
+            }
+            match self.receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(value) => self.received.borrow_mut().push(value),
+                Err(RecvTimeoutError::Timeout) => return Fetch::Pending,
+                Err(RecvTimeoutError::Disconnected) => self.closed.set(true),
+            }
+        }
+    }
+
+    /// The answer for `index` if the received elements already decide it
+    fn fetch(&self, index: usize) -> Option<Fetch<T>> {
+        match self.received.borrow().get(index) {
+            Some(value) => Some(Fetch::Ready(value.clone())),
+            None if self.closed.get() => Some(Fetch::Closed),
+            None => None,
+        }
+    }
+
+    /// Store everything that has already arrived
+    fn drain(&self) {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(value) => self.received.borrow_mut().push(value),
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => return self.closed.set(true),
+            }
+        }
+    }
+}
+
+impl<T: Clone> InfiniteArray<T> for StreamedArray<T> {
+    fn get(&self, index: usize) -> T {
+        match self.wait(index) {
+            Fetch::Ready(value) => value,
+            _ => panic!("stream closed before element {} arrived", index),
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new((0..).map_while(move |i| self.wait(i).ready()))
+    }
+
+    /// Fails without waiting if the element has not arrived yet
+    fn try_get(&self, index: usize) -> Result<T, Error> {
+        match self.poll(index) {
+            Fetch::Ready(value) => Ok(value),
+            Fetch::Pending => Err(Error::Unavailable { index }),
+            Fetch::Closed => Err(Error::OutOfBounds {
+                index,
+                len: self.received.borrow().len(),
+            }),
+        }
+    }
+}
+
+/// An array of the values of an iterator, pulled on demand and memoized
+///
+/// Reading element `i` advances the iterator up to its `i`-th value, so
+/// any iterator, infinite or not, gains random access. If the iterator ends
+/// first, [`InfiniteArray::get`] panics and
+/// [`get_available`](Self::get_available) returns `None`; the length is
+/// known once the iterator is exhausted or reports an exact size.
+pub struct IteratorArray<T, I> {
+    source: RefCell<I>,
+    pulled: RefCell<Vec<T>>,
+    exhausted: Cell<bool>,
+}
+
+impl<T: Clone, I: Iterator<Item = T>> IteratorArray<T, I> {
+    pub fn new<S: IntoIterator<IntoIter = I>>(source: S) -> Self {
+        Self {
+            source: RefCell::new(source.into_iter()),
+            pulled: RefCell::new(Vec::new()),
+            exhausted: Cell::new(false),
+        }
+    }
+
+    /// Number of values pulled from the iterator so far
+    pub fn pulled(&self) -> usize {
+        self.pulled.borrow().len()
+    }
+
+    /// Element `index`, or `None` if the iterator ends before it
+    pub fn get_available(&self, index: usize) -> Option<T> {
+        let mut pulled = self.pulled.borrow_mut();
+        while pulled.len() <= index && !self.exhausted.get() {
+            match self.source.borrow_mut().next() {
+                Some(value) => pulled.push(value),
+                None => self.exhausted.set(true),
+            }
+        }
+        pulled.get(index).cloned()
+    }
+}
+
+impl<T: Clone, I: Iterator<Item = T>> InfiniteArray<T> for IteratorArray<T, I> {
+    fn get(&self, index: usize) -> T {
+        self.get_available(index)
+            .unwrap_or_else(|| panic!("iterator ended before element {}", index))
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new((0..).map_while(move |i| self.get_available(i)))
+    }
+
+    fn try_get(&self, index: usize) -> Result<T, Error> {
+        self.get_available(index).ok_or_else(|| Error::OutOfBounds {
+            index,
+            len: self.pulled(),
+        })
+    }
+
+    fn len(&self) -> Option<usize> {
+        let pulled = self.pulled();
+        if self.exhausted.get() {
+            return Some(pulled);
+        }
+        match self.source.borrow().size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(pulled + lower),
+            _ => None,
+        }
+    }
+}
+
+/// An array generated by repeatedly stepping a state, memoizing the values
+/// emitted
+///
+/// Element `i` is the value returned by the `i`-th call of `step`, so
+/// sequences most naturally produced one after another (sieves, parsers,
+/// simulations) can be read at random. The state and values emitted so far
+/// can be saved with [`checkpoint`](Self::checkpoint) and generation taken
+/// up again later with [`restore`](Self::restore).
+pub struct GeneratorArray<T, S, F> {
+    state: RefCell<S>,
+    step: RefCell<F>,
+    emitted: RefCell<Vec<T>>,
+}
+
+/// The state of a [`GeneratorArray`] and the values it emitted
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint<T, S> {
+    pub state: S,
+    pub emitted: Vec<T>,
+}
+
+impl<T, S, F> GeneratorArray<T, S, F>
+where
+    T: Clone,
+    F: FnMut(&mut S) -> T,
+{
+    pub fn new(initial: S, step: F) -> Self {
+        Self::restore(
+            Checkpoint {
+                state: initial,
+                emitted: Vec::new(),
+            },
+            step,
+        )
+    }
+
+    /// Continue generating from `checkpoint` with `step`
+    pub fn restore(checkpoint: Checkpoint<T, S>, step: F) -> Self {
+        Self {
+            state: RefCell::new(checkpoint.state),
+            step: RefCell::new(step),
+            emitted: RefCell::new(checkpoint.emitted),
+        }
+    }
+
+    /// Number of values emitted so far
+    pub fn emitted(&self) -> usize {
+        self.emitted.borrow().len()
+    }
+
+    /// The state after the values emitted so far
+    pub fn state(&self) -> Ref<'_, S> {
+        self.state.borrow()
+    }
+
+    /// A copy of the state and the values emitted so far
+    pub fn checkpoint(&self) -> Checkpoint<T, S>
+    where
+        S: Clone,
+    {
+        Checkpoint {
+            state: self.state.borrow().clone(),
+            emitted: self.emitted.borrow().clone(),
+        }
+    }
+
+    /// The state and values, dropping the step function
+    pub fn into_checkpoint(self) -> Checkpoint<T, S> {
+        Checkpoint {
+            state: self.state.into_inner(),
+            emitted: self.emitted.into_inner(),
+        }
+    }
+}
+
+impl<T, S, F> InfiniteArray<T> for GeneratorArray<T, S, F>
+where
+    T: Clone,
+    F: FnMut(&mut S) -> T,
+{
+    fn get(&self, index: usize) -> T {
+        let mut emitted = self.emitted.borrow_mut();
+        if emitted.len() <= index {
+            let mut state = self.state.borrow_mut();
+            let step = &mut *self.step.borrow_mut();
+            let missing = index + 1 - emitted.len();
+            emitted.extend(std::iter::repeat_with(|| step(&mut state)).take(missing));
+        }
+        emitted[index].clone()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(Indexed::new(move |n| self.get(n)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_streamed_array_waits_for_producer() {
+        let (sender, streamed) = StreamedArray::channel();
+        let producer = thread::spawn(move || {
+            for i in 0..100u64 {
+                sender.send(i * i).unwrap();
+            }
+        });
+        assert_eq!(streamed.get(50), 2500);
+        assert_eq!(streamed.get(3), 9);
+        producer.join().unwrap();
+        assert_eq!(streamed.iter().count(), 100);
+        assert_eq!(streamed.poll(100), Fetch::Closed);
+    }
+
+    #[test]
+    fn test_iterator_array_random_access() {
+        let primes = IteratorArray::new((2u64..).filter(|n| (2..*n).all(|d| n % d != 0)));
+        assert_eq!(primes.get(9), 29);
+        assert_eq!(primes.pulled(), 10);
+        assert_eq!(primes.get(2), 5);
+        assert_eq!(primes.len(), None);
+        assert_eq!(primes.iter().skip(10).take(2).collect::<Vec<_>>(), vec![31, 37]);
+
+        let finite = IteratorArray::new(vec![1, 2, 3]);
+        assert_eq!(finite.len(), Some(3));
+        assert_eq!((finite.get_available(2), finite.get_available(3)), (Some(3), None));
+        assert_eq!(finite.iter().count(), 3);
+    }
+
+    #[test]
+    fn test_generator_array_checkpoints() {
+        // Collatz trajectory of 27, one step per element
+        let step = |n: &mut u64| {
+            let value = *n;
+            *n = if value.is_multiple_of(2) { value / 2 } else { 3 * value + 1 };
+            value
+        };
+        let collatz = GeneratorArray::new(27u64, step);
+        assert_eq!(collatz.get(3), 124);
+        assert_eq!((collatz.emitted(), *collatz.state()), (4, 62));
+        let saved = collatz.checkpoint();
+        assert_eq!(collatz.get(111), 1);
+
+        let resumed = GeneratorArray::restore(saved, step);
+        assert_eq!(resumed.get(0), 27);
+        assert_eq!(resumed.iter().take(112).collect::<Vec<_>>(), collatz.iter().take(112).collect::<Vec<_>>());
+        assert_eq!(resumed.into_checkpoint().emitted.len(), 112);
+    }
+
+    #[test]
+    fn test_streamed_array_pending() {
+        let (sender, streamed) = StreamedArray::channel();
+        sender.send(1.5).unwrap();
+        assert_eq!(streamed.poll(0), Fetch::Ready(1.5));
+        assert_eq!(streamed.poll(1), Fetch::Pending);
+        assert!(matches!(streamed.try_get(1), Err(Error::Unavailable { index: 1 })));
+        assert_eq!(streamed.get_timeout(1, Duration::from_millis(10)), Fetch::Pending);
+        assert!(!streamed.is_closed());
+        drop(sender);
+        assert_eq!(streamed.get_timeout(1, Duration::from_secs(1)), Fetch::Closed);
+        assert_eq!(streamed.received(), 1);
+    }
+}
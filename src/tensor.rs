@@ -0,0 +1,488 @@
+//! N-dimensional arrays whose axes may each be finite or infinite
+//!
+//! # Broadcasting
+//!
+//! Binary operations on tensors of different shapes follow NumPy's rules,
+//! extended to infinite axes. Shapes are aligned on their trailing axes and
+//! missing leading axes count as length 1. Then, axis by axis:
+//!
+//! - equal lengths match;
+//! - a length-1 axis is repeated to the other length, finite or infinite;
+//! - a finite length `n > 1` against an infinite axis gives length `n`, and
+//!   the infinite operand is only read at its first `n` positions;
+//! - two different finite lengths, neither 1, are an error.
+//!
+//! So a row vector of length `n` added to an infinite matrix gives an
+//! `∞ × n` result, adding the vector to the first `n` columns of every row.
+
+use crate::arrays::{InfiniteArray, InfiniteVector};
+use crate::matrix::InfiniteMatrix;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+use std::rc::Rc;
+
+mod einsum;
+
+pub use einsum::{einsum, Contraction};
+
+/// Errors from combining tensor shapes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeError {
+    /// Axis `axis` of the broadcast result has incompatible lengths, with
+    /// axes counted in the aligned (longer) shape
+    Incompatible { axis: usize, left: Length, right: Length },
+    /// An einsum specification that cannot be parsed or has a repeated
+    /// output label
+    Malformed,
+    /// Operand `operand` has `expected` axes but `found` subscripts
+    Subscripts { operand: usize, expected: usize, found: usize },
+    /// An output label that no operand uses
+    UnknownLabel { label: char },
+    /// A label used for axes of different finite lengths
+    Label { label: char, left: Length, right: Length },
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::Incompatible { axis, left, right } => {
+                write!(f, "cannot broadcast axis {} of lengths {} and {}", axis, left, right)
+            }
+            ShapeError::Malformed => write!(f, "malformed einsum specification"),
+            ShapeError::Subscripts { operand, expected, found } => {
+                write!(f, "operand {} has {} axes but {} subscripts", operand, expected, found)
+            }
+            ShapeError::UnknownLabel { label } => {
+                write!(f, "output label '{}' does not appear in any operand", label)
+            }
+            ShapeError::Label { label, left, right } => {
+                write!(f, "label '{}' is used for axes of lengths {} and {}", label, left, right)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+/// The length of one axis of a [`Shape`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Length {
+    Finite(usize),
+    Infinity,
+}
+
+impl Length {
+    /// Whether `index` lies on an axis of this length
+    pub fn contains(self, index: usize) -> bool {
+        match self {
+            Length::Finite(n) => index < n,
+            Length::Infinity => true,
+        }
+    }
+
+    /// The length as an [`InfiniteArray::len`] value
+    pub fn finite(self) -> Option<usize> {
+        match self {
+            Length::Finite(n) => Some(n),
+            Length::Infinity => None,
+        }
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Length::Finite(n) => write!(f, "{}", n),
+            Length::Infinity => write!(f, "∞"),
+        }
+    }
+}
+
+/// The axis lengths of an [`InfiniteTensor`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shape(Vec<Length>);
+
+impl Shape {
+    pub fn new(axes: Vec<Length>) -> Self {
+        Self(axes)
+    }
+
+    /// Number of axes
+    pub fn ndim(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The length of axis `k`
+    pub fn axis(&self, k: usize) -> Length {
+        self.0[k]
+    }
+
+    /// All axis lengths
+    pub fn axes(&self) -> &[Length] {
+        &self.0
+    }
+
+    /// Whether `index` is a valid multi-index for this shape
+    pub fn contains(&self, index: &[usize]) -> bool {
+        index.len() == self.ndim() && self.0.iter().zip(index).all(|(length, &i)| length.contains(i))
+    }
+
+    /// Total number of elements, if every axis is finite
+    pub fn size(&self) -> Option<usize> {
+        self.0.iter().try_fold(1, |acc, length| Some(acc * length.finite()?))
+    }
+
+    /// The shape with axis `k` removed
+    pub fn remove_axis(&self, k: usize) -> Self {
+        let mut axes = self.0.clone();
+        axes.remove(k);
+        Self(axes)
+    }
+
+    /// The shape of the result of broadcasting `self` against `other`, see
+    /// the [module documentation](self)
+    pub fn broadcast(&self, other: &Shape) -> Result<Shape, ShapeError> {
+        let ndim = self.ndim().max(other.ndim());
+        let (left, right) = (self.aligned(ndim), other.aligned(ndim));
+        left.iter()
+            .zip(&right)
+            .enumerate()
+            .map(|(axis, (&l, &r))| match (l, r) {
+                _ if l == r => Ok(l),
+                (Length::Finite(1), _) => Ok(r),
+                (_, Length::Finite(1)) => Ok(l),
+                (Length::Finite(_), Length::Infinity) => Ok(l),
+                (Length::Infinity, Length::Finite(_)) => Ok(r),
+                _ => Err(ShapeError::Incompatible { axis, left: l, right: r }),
+            })
+            .collect::<Result<_, _>>()
+            .map(Shape)
+    }
+
+    /// The axes padded on the left with length 1 up to `ndim` axes
+    fn aligned(&self, ndim: usize) -> Vec<Length> {
+        let mut axes = vec![Length::Finite(1); ndim - self.ndim()];
+        axes.extend_from_slice(&self.0);
+        axes
+    }
+
+    /// The index into a tensor of this shape read by position `index` of a
+    /// broadcast result with `index.len()` axes
+    fn broadcast_index(&self, index: &[usize]) -> Vec<usize> {
+        let skip = index.len() - self.ndim();
+        self.0
+            .iter()
+            .zip(&index[skip..])
+            .map(|(&length, &i)| if length == Length::Finite(1) { 0 } else { i })
+            .collect()
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for (k, length) in self.0.iter().enumerate() {
+            if k > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", length)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// The generating function of an [`InfiniteTensor`]
+type Generator<T> = Rc<dyn Fn(&[usize]) -> T>;
+
+/// An N-dimensional array computed lazily from its multi-index
+///
+/// Elements are never stored; views and reductions build new tensors that
+/// share the generating function of the original.
+pub struct InfiniteTensor<T> {
+    shape: Shape,
+    f: Generator<T>,
+}
+
+impl<T> Clone for InfiniteTensor<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shape: self.shape.clone(),
+            f: Rc::clone(&self.f),
+        }
+    }
+}
+
+impl<T: 'static> InfiniteTensor<T> {
+    /// A tensor of the given shape with element `f(index)` at `index`
+    pub fn from_fn<F>(shape: Shape, f: F) -> Self
+    where
+        F: Fn(&[usize]) -> T + 'static,
+    {
+        Self { shape, f: Rc::new(f) }
+    }
+
+    /// A one-dimensional tensor of the elements of an array
+    pub fn from_array<A>(array: A) -> Self
+    where
+        A: InfiniteArray<T> + 'static,
+    {
+        let length = array.len().map_or(Length::Infinity, Length::Finite);
+        Self::from_fn(Shape::new(vec![length]), move |index| array.get(index[0]))
+    }
+
+    /// A two-dimensional tensor of the entries of a matrix
+    pub fn from_matrix<M>(matrix: M) -> Self
+    where
+        M: InfiniteMatrix<T> + 'static,
+    {
+        let length = |n: Option<usize>| n.map_or(Length::Infinity, Length::Finite);
+        let shape = Shape::new(vec![length(matrix.rows()), length(matrix.cols())]);
+        Self::from_fn(shape, move |index| matrix.get(index[0], index[1]))
+    }
+
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    /// The element at multi-index `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` has the wrong number of axes or is out of bounds on
+    /// a finite axis.
+    pub fn get(&self, index: &[usize]) -> T {
+        assert!(
+            self.shape.contains(index),
+            "index {:?} out of bounds for shape {}",
+            index,
+            self.shape
+        );
+        (self.f)(index)
+    }
+
+    /// The tensor with axis `axis` fixed at `position`, one dimension lower
+    pub fn slice(&self, axis: usize, position: usize) -> Self {
+        assert!(
+            self.shape.axis(axis).contains(position),
+            "position {} out of bounds for axis of length {}",
+            position,
+            self.shape.axis(axis)
+        );
+        let f = Rc::clone(&self.f);
+        Self::from_fn(self.shape.remove_axis(axis), move |index| {
+            let mut full = index.to_vec();
+            full.insert(axis, position);
+            f(&full)
+        })
+    }
+
+    /// The one-dimensional view along `axis` through `index`, whose entry
+    /// on `axis` is ignored
+    pub fn lane(&self, axis: usize, index: &[usize]) -> Lane<'_, T> {
+        assert!(
+            index.len() == self.shape.ndim(),
+            "index {:?} has the wrong number of axes for shape {}",
+            index,
+            self.shape
+        );
+        Lane {
+            tensor: self,
+            axis,
+            index: index.to_vec(),
+        }
+    }
+
+    /// Fold axis `axis` away with `op`, starting from `init` in every lane
+    ///
+    /// # Panics
+    ///
+    /// Panics if the axis is infinite.
+    pub fn reduce_axis<U, F>(&self, axis: usize, init: U, op: F) -> InfiniteTensor<U>
+    where
+        U: Clone + 'static,
+        F: Fn(U, T) -> U + 'static,
+    {
+        let n = self
+            .shape
+            .axis(axis)
+            .finite()
+            .expect("can only reduce along a finite axis");
+        let f = Rc::clone(&self.f);
+        InfiniteTensor::from_fn(self.shape.remove_axis(axis), move |index| {
+            let mut full = index.to_vec();
+            full.insert(axis, 0);
+            (0..n).fold(init.clone(), |acc, i| {
+                full[axis] = i;
+                op(acc, f(&full))
+            })
+        })
+    }
+
+    /// Combine two tensors elementwise after broadcasting their shapes, see
+    /// the [module documentation](self)
+    pub fn broadcast_with<U, V, F>(&self, other: &InfiniteTensor<U>, f: F) -> Result<InfiniteTensor<V>, ShapeError>
+    where
+        U: 'static,
+        V: 'static,
+        F: Fn(T, U) -> V + 'static,
+    {
+        let shape = self.shape.broadcast(&other.shape)?;
+        let (left, right) = (self.clone(), other.clone());
+        Ok(InfiniteTensor::from_fn(shape, move |index| {
+            let a = (left.f)(&left.shape.broadcast_index(index));
+            let b = (right.f)(&right.shape.broadcast_index(index));
+            f(a, b)
+        }))
+    }
+
+    /// The sum along a finite axis
+    pub fn sum_axis(&self, axis: usize) -> InfiniteTensor<T>
+    where
+        T: Add<Output = T> + num_traits::Zero + Clone,
+    {
+        self.reduce_axis(axis, T::zero(), |acc, x| acc + x)
+    }
+}
+
+macro_rules! broadcast_operator {
+    ($($trait:ident, $method:ident, $op:tt);*) => {$(
+        impl<T> $trait for InfiniteTensor<T>
+        where
+            T: $trait<Output = T> + 'static,
+        {
+            type Output = InfiniteTensor<T>;
+
+            /// Elementwise, with broadcasting
+            ///
+            /// # Panics
+            ///
+            /// Panics if the shapes cannot be broadcast together; use
+            /// [`broadcast_with`](InfiniteTensor::broadcast_with) to get
+            /// the error instead.
+            fn $method(self, other: InfiniteTensor<T>) -> InfiniteTensor<T> {
+                self.broadcast_with(&other, |a, b| a $op b)
+                    .unwrap_or_else(|error| panic!("{}", error))
+            }
+        }
+    )*};
+}
+
+broadcast_operator!(Add, add, +; Sub, sub, -; Mul, mul, *; Div, div, /);
+
+/// A one-dimensional view of an [`InfiniteTensor`] along one axis, see
+/// [`InfiniteTensor::lane`]
+pub struct Lane<'a, T> {
+    tensor: &'a InfiniteTensor<T>,
+    axis: usize,
+    index: Vec<usize>,
+}
+
+impl<T: 'static> InfiniteArray<T> for Lane<'_, T> {
+    fn get(&self, i: usize) -> T {
+        let mut index = self.index.clone();
+        index[self.axis] = i;
+        self.tensor.get(&index)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        let indices: Box<dyn Iterator<Item = usize>> = match self.len() {
+            Some(n) => Box::new(0..n),
+            None => Box::new(0..),
+        };
+        Box::new(indices.map(move |i| self.get(i)))
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.tensor.shape.axis(self.axis).finite()
+    }
+}
+
+impl<T: 'static> InfiniteVector<T> for Lane<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Length::{Finite, Infinity};
+
+    fn sample() -> InfiniteTensor<u64> {
+        // t[i, j, k] = 100 i + 10 j + k over (2, ∞, 3)
+        InfiniteTensor::from_fn(Shape::new(vec![Finite(2), Infinity, Finite(3)]), |index| {
+            (100 * index[0] + 10 * index[1] + index[2]) as u64
+        })
+    }
+
+    #[test]
+    fn test_shape() {
+        let shape = sample().shape().clone();
+        assert_eq!(shape.ndim(), 3);
+        assert_eq!(shape.size(), None);
+        assert_eq!(shape.to_string(), "(2, ∞, 3)");
+        assert!(shape.contains(&[1, 1_000_000, 2]));
+        assert!(!shape.contains(&[2, 0, 0]));
+        assert_eq!(shape.remove_axis(1).size(), Some(6));
+    }
+
+    #[test]
+    fn test_views() {
+        let t = sample();
+        assert_eq!(t.get(&[1, 42, 2]), 522);
+
+        let slice = t.slice(0, 1);
+        assert_eq!(slice.shape().axes(), &[Infinity, Finite(3)]);
+        assert_eq!(slice.get(&[5, 1]), 151);
+
+        let lane = t.lane(1, &[1, 0, 2]);
+        assert_eq!(lane.len(), None);
+        assert_eq!(lane.get(7), 172);
+        let finite = t.lane(2, &[0, 3, 0]);
+        assert_eq!(finite.iter().collect::<Vec<_>>(), vec![30, 31, 32]);
+    }
+
+    #[test]
+    fn test_reductions() {
+        let t = sample();
+        let sums = t.sum_axis(2);
+        assert_eq!(sums.shape().axes(), &[Finite(2), Infinity]);
+        assert_eq!(sums.get(&[1, 4]), 3 * 140 + 3);
+        let max = t.reduce_axis(0, 0, |acc: u64, x| acc.max(x));
+        assert_eq!(max.get(&[9, 1]), 191);
+    }
+
+    #[test]
+    fn test_broadcast_shapes() {
+        let shape = |axes: &[Length]| Shape::new(axes.to_vec());
+        let matrix = shape(&[Infinity, Infinity]);
+        assert_eq!(matrix.broadcast(&shape(&[Finite(3)])), Ok(shape(&[Infinity, Finite(3)])));
+        assert_eq!(matrix.broadcast(&shape(&[Finite(1), Finite(1)])), Ok(matrix.clone()));
+        assert_eq!(shape(&[Finite(4), Finite(1)]).broadcast(&shape(&[Finite(5)])), Ok(shape(&[Finite(4), Finite(5)])));
+        assert_eq!(
+            shape(&[Finite(2), Finite(3)]).broadcast(&shape(&[Finite(4)])),
+            Err(ShapeError::Incompatible { axis: 1, left: Finite(3), right: Finite(4) })
+        );
+    }
+
+    #[test]
+    fn test_broadcast_row_vector() {
+        // Add a row vector to every row of the infinite matrix A(i, j) = i
+        let matrix = InfiniteTensor::from_matrix(crate::matrix::InfiniteMatrixFromFn::new(|i, _| i as f64));
+        let row = InfiniteTensor::from_fn(Shape::new(vec![Finite(3)]), |index| 10.0 * (index[0] + 1) as f64);
+        let sum = matrix.clone() + row;
+        assert_eq!(sum.shape().axes(), &[Infinity, Finite(3)]);
+        assert_eq!(sum.get(&[1000, 2]), 1030.0);
+
+        // A column of length 1 on the row axis stretches over all rows
+        let scale = InfiniteTensor::from_fn(Shape::new(vec![Finite(1), Infinity]), |index| index[1] as f64);
+        let product = matrix * scale;
+        assert_eq!(product.shape().axes(), &[Infinity, Infinity]);
+        assert_eq!(product.get(&[4, 5]), 20.0);
+
+        let ones = InfiniteTensor::from_array(crate::arrays::Ones::<f64>::new());
+        assert_eq!(ones.shape().axes(), &[Infinity]);
+    }
+
+    #[test]
+    #[should_panic(expected = "finite axis")]
+    fn test_reduce_infinite_axis() {
+        sample().sum_axis(1);
+    }
+}
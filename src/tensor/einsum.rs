@@ -0,0 +1,322 @@
+//! Einstein-summation contractions of infinite tensors
+
+use super::{InfiniteTensor, Length, Shape, ShapeError};
+use crate::reductions::{adaptive_sum, exact_sum};
+use crate::series::{ConvergenceReport, SeriesError};
+use num_traits::Float;
+use std::rc::Rc;
+
+/// How the labels of a specification map onto the operands
+struct Plan {
+    /// Length of each distinct label
+    lengths: Vec<Length>,
+    /// Label of every axis of every operand
+    operand_labels: Vec<Vec<usize>>,
+    /// Labels of the output axes
+    output: Vec<usize>,
+    /// Contracted labels with finite length
+    finite: Vec<usize>,
+    /// Contracted labels with infinite length
+    infinite: Vec<usize>,
+}
+
+impl Plan {
+    fn new<T>(spec: &str, operands: &[&InfiniteTensor<T>]) -> Result<Self, ShapeError> {
+        let spec: String = spec.chars().filter(|c| !c.is_whitespace()).collect();
+        let (inputs, output) = spec.split_once("->").ok_or(ShapeError::Malformed)?;
+        let inputs: Vec<&str> = inputs.split(',').collect();
+        if inputs.len() != operands.len() || !spec.chars().all(|c| c.is_ascii_alphabetic() || ",->".contains(c)) {
+            return Err(ShapeError::Malformed);
+        }
+
+        let mut names: Vec<char> = Vec::new();
+        let mut lengths: Vec<Length> = Vec::new();
+        let mut operand_labels = Vec::new();
+        for (operand, (subscripts, tensor)) in inputs.iter().zip(operands).enumerate() {
+            if subscripts.len() != tensor.shape.ndim() {
+                return Err(ShapeError::Subscripts {
+                    operand,
+                    expected: tensor.shape.ndim(),
+                    found: subscripts.len(),
+                });
+            }
+            let mut labels = Vec::new();
+            for (label, &length) in subscripts.chars().zip(tensor.shape.axes()) {
+                let k = match names.iter().position(|&name| name == label) {
+                    Some(k) => {
+                        lengths[k] = match (lengths[k], length) {
+                            (a, b) if a == b => a,
+                            (Length::Infinity, finite) | (finite, Length::Infinity) => finite,
+                            (left, right) => return Err(ShapeError::Label { label, left, right }),
+                        };
+                        k
+                    }
+                    None => {
+                        names.push(label);
+                        lengths.push(length);
+                        names.len() - 1
+                    }
+                };
+                labels.push(k);
+            }
+            operand_labels.push(labels);
+        }
+
+        let mut output_labels = Vec::new();
+        for label in output.chars() {
+            let k = names
+                .iter()
+                .position(|&name| name == label)
+                .ok_or(ShapeError::UnknownLabel { label })?;
+            if output_labels.contains(&k) {
+                return Err(ShapeError::Malformed);
+            }
+            output_labels.push(k);
+        }
+        let contracted = (0..names.len()).filter(|k| !output_labels.contains(k));
+        let (infinite, finite) = contracted.partition(|&k| lengths[k] == Length::Infinity);
+        Ok(Self {
+            lengths,
+            operand_labels,
+            output: output_labels,
+            finite,
+            infinite,
+        })
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::new(self.output.iter().map(|&k| self.lengths[k]).collect())
+    }
+}
+
+/// The lazy result of [`einsum`]
+///
+/// Each element is a sum over the contracted labels. Finite contracted axes
+/// are summed exactly. Infinite ones are summed in shells, shell `s`
+/// holding the index tuples whose largest infinite contracted index is `s`,
+/// until the estimated tail of the shell sums is below the tolerance, as in
+/// [`dot`](crate::reductions::dot).
+pub struct Contraction<T> {
+    plan: Rc<Plan>,
+    operands: Vec<InfiniteTensor<T>>,
+    tol: T,
+}
+
+impl<T: Clone> Clone for Contraction<T> {
+    fn clone(&self) -> Self {
+        Self {
+            plan: Rc::clone(&self.plan),
+            operands: self.operands.clone(),
+            tol: self.tol.clone(),
+        }
+    }
+}
+
+impl<T: Float + 'static> Contraction<T> {
+    /// The shape of the output, one axis per output label
+    pub fn shape(&self) -> Shape {
+        self.plan.shape()
+    }
+
+    /// The element at `index` with a report on the adaptive summation
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for [`shape`](Self::shape).
+    pub fn try_get(&self, index: &[usize]) -> Result<ConvergenceReport<T>, SeriesError> {
+        let plan = &self.plan;
+        assert!(
+            plan.shape().contains(index),
+            "index {:?} out of bounds for shape {}",
+            index,
+            plan.shape()
+        );
+        let mut assignment = vec![0; plan.lengths.len()];
+        for (&k, &i) in plan.output.iter().zip(index) {
+            assignment[k] = i;
+        }
+        let shell = |s: usize| self.shell(&mut assignment.clone(), s);
+        if plan.infinite.is_empty() {
+            Ok(exact_sum(shell, 1))
+        } else {
+            adaptive_sum(shell, self.tol, |_, tail| tail)
+        }
+    }
+
+    /// The element at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds or the contraction does not
+    /// converge; use [`try_get`](Self::try_get) to get the error instead.
+    pub fn get(&self, index: &[usize]) -> T {
+        self.try_get(index)
+            .unwrap_or_else(|error| panic!("{}", error))
+            .value
+    }
+
+    /// The result as a tensor whose elements are computed by
+    /// [`get`](Self::get)
+    pub fn into_tensor(self) -> InfiniteTensor<T> {
+        let shape = self.shape();
+        InfiniteTensor::from_fn(shape, move |index| self.get(index))
+    }
+
+    /// Sum of the terms in shell `s` of the infinite contracted labels
+    ///
+    /// Each tuple of the shell is visited once, by the first position `p`
+    /// holding `s`: earlier positions run below `s`, later ones up to `s`.
+    fn shell(&self, assignment: &mut [usize], s: usize) -> T {
+        let infinite = &self.plan.infinite;
+        let m = infinite.len();
+        if m == 0 {
+            return self.finite_sum(assignment);
+        }
+        let mut sum = T::zero();
+        for p in 0..m {
+            if p > 0 && s == 0 {
+                break;
+            }
+            let mut rest = vec![0; m - 1];
+            loop {
+                for (q, &k) in infinite.iter().enumerate() {
+                    assignment[k] = match q.cmp(&p) {
+                        std::cmp::Ordering::Less => rest[q],
+                        std::cmp::Ordering::Equal => s,
+                        std::cmp::Ordering::Greater => rest[q - 1],
+                    };
+                }
+                sum = sum + self.finite_sum(assignment);
+                if !advance(&mut rest, |q| if q < p { s } else { s + 1 }) {
+                    break;
+                }
+            }
+        }
+        sum
+    }
+
+    /// Sum over the finite contracted labels with the others fixed
+    fn finite_sum(&self, assignment: &mut [usize]) -> T {
+        let plan = &self.plan;
+        let bound = |position: usize| match plan.lengths[plan.finite[position]] {
+            Length::Finite(n) => n,
+            Length::Infinity => unreachable!("finite labels have finite length"),
+        };
+        if plan.finite.iter().any(|&k| plan.lengths[k] == Length::Finite(0)) {
+            return T::zero();
+        }
+        let mut tuple = vec![0; plan.finite.len()];
+        let mut sum = T::zero();
+        loop {
+            for (&k, &i) in plan.finite.iter().zip(&tuple) {
+                assignment[k] = i;
+            }
+            let term = self.operands.iter().zip(&plan.operand_labels).fold(T::one(), |acc, (tensor, labels)| {
+                let index: Vec<usize> = labels.iter().map(|&k| assignment[k]).collect();
+                acc * (tensor.f)(&index)
+            });
+            sum = sum + term;
+            if !advance(&mut tuple, bound) {
+                return sum;
+            }
+        }
+    }
+}
+
+/// Step an odometer whose position `p` runs over `0..bound(p)`, returning
+/// `false` after the last tuple
+fn advance<B: Fn(usize) -> usize>(tuple: &mut [usize], bound: B) -> bool {
+    for p in (0..tuple.len()).rev() {
+        tuple[p] += 1;
+        if tuple[p] < bound(p) {
+            return true;
+        }
+        tuple[p] = 0;
+    }
+    false
+}
+
+/// Contract tensors according to an Einstein-summation specification such
+/// as `"ij,j->i"`
+///
+/// Labels are ASCII letters and the output after `->` is required. Labels
+/// repeated across or within operands are identified, and labels missing
+/// from the output are summed over, see [`Contraction`]. A label that is
+/// finite on one operand and infinite on another takes the finite length.
+/// Free infinite axes stay lazy. Dot products (`"i,i->"`), matrix-vector
+/// products (`"ij,j->i"`) and outer products (`"i,j->ij"`) are special
+/// cases.
+pub fn einsum<T>(spec: &str, operands: &[&InfiniteTensor<T>], tol: T) -> Result<Contraction<T>, ShapeError>
+where
+    T: Float + 'static,
+{
+    Ok(Contraction {
+        plan: Rc::new(Plan::new(spec, operands)?),
+        operands: operands.iter().map(|&tensor| tensor.clone()).collect(),
+        tol,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::InfiniteArrayFromFn;
+    use crate::matrix::InfiniteMatrixFromFn;
+    use Length::{Finite, Infinity};
+
+    #[test]
+    fn test_einsum_dot_and_matvec() {
+        // Σ 4^-i = 4/3
+        let halves = InfiniteTensor::from_array(InfiniteArrayFromFn::new(|i| 0.5f64.powi(i as i32)));
+        let dot = einsum("i,i->", &[&halves, &halves], 1e-14).unwrap();
+        assert_eq!(dot.shape().ndim(), 0);
+        assert!((dot.get(&[]) - 4.0 / 3.0).abs() < 1e-13);
+
+        // Σ_ij 2^-i 3^-j = 2 · 3/2 over two infinite contracted labels
+        let thirds = InfiniteTensor::from_array(InfiniteArrayFromFn::new(|i| (1.0 / 3.0f64).powi(i as i32)));
+        let double = einsum("i,j->", &[&halves, &thirds], 1e-14).unwrap();
+        assert!((double.get(&[]) - 3.0).abs() < 1e-12);
+
+        // (A v)_i = Σ_j 2^-(i+j) = 2^(1-i) with v = 1
+        let a = InfiniteTensor::from_matrix(InfiniteMatrixFromFn::new(|i, j| 0.5f64.powi((i + j) as i32)));
+        let ones = InfiniteTensor::from_array(crate::arrays::Ones::new());
+        let av = einsum("ij,j->i", &[&a, &ones], 1e-14).unwrap().into_tensor();
+        assert_eq!(av.shape().axes(), &[Infinity]);
+        assert!((av.get(&[3]) - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_einsum_outer_and_trace() {
+        let a = InfiniteTensor::from_array(InfiniteArrayFromFn::new(|i| i as f64));
+        let outer = einsum("i,j->ij", &[&a, &a], 1e-12).unwrap();
+        assert_eq!(outer.shape().axes(), &[Infinity, Infinity]);
+        assert_eq!(outer.get(&[1000, 3]), 3000.0);
+        assert_eq!(outer.try_get(&[2, 2]).unwrap().terms, 1);
+
+        let square = InfiniteTensor::from_fn(Shape::new(vec![Finite(3), Finite(3)]), |index| (3 * index[0] + index[1]) as f64);
+        let trace = einsum("ii->", &[&square], 0.0).unwrap();
+        assert_eq!(trace.get(&[]), 12.0);
+
+        // The finite axis truncates the infinite one it is contracted with
+        let row = einsum("ij,j->i", &[&square, &a], 0.0).unwrap();
+        assert_eq!(row.get(&[1]), 3.0 * 0.0 + 4.0 + 5.0 * 2.0);
+    }
+
+    #[test]
+    fn test_einsum_errors() {
+        let a = InfiniteTensor::from_array(InfiniteArrayFromFn::new(|i| i as f64));
+        let b = InfiniteTensor::from_fn(Shape::new(vec![Finite(2)]), |_| 1.0);
+        let c = InfiniteTensor::from_fn(Shape::new(vec![Finite(3)]), |_| 1.0);
+        assert_eq!(einsum("i,i", &[&a, &a], 0.0).err(), Some(ShapeError::Malformed));
+        assert_eq!(einsum("ij->i", &[&a], 0.0).err(), Some(ShapeError::Subscripts { operand: 0, expected: 1, found: 2 }));
+        assert_eq!(einsum("i->k", &[&a], 0.0).err(), Some(ShapeError::UnknownLabel { label: 'k' }));
+        assert_eq!(
+            einsum("i,i->", &[&b, &c], 0.0).err(),
+            Some(ShapeError::Label { label: 'i', left: Finite(2), right: Finite(3) })
+        );
+
+        // Σ i diverges
+        let diverging = einsum("i->", &[&a], 1e-6).unwrap();
+        assert!(diverging.try_get(&[]).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+//! Helpers for testing lazy pipelines
+//!
+//! Infinite arrays can only be compared on a prefix. [`eq_within`] checks
+//! the first `n` elements, and the [`assert_prefix_eq!`] and
+//! [`assert_prefix_approx_eq!`] macros report the first index where two
+//! arrays differ:
+//!
+//! ```rust
+//! use infinite_arrays::*;
+//!
+//! let evens = InfiniteArrayFromFn::new(|i| 2 * i);
+//! assert_prefix_eq!(mul_scalar(InfiniteArrayFromFn::new(|i| i), 2), evens, 100);
+//! let tenths = InfiniteArrayFromFn::new(|i| (i + 1) as f64 / 10.0);
+//! assert_prefix_approx_eq!(cumsum(Fill::new(0.1)), tenths, 100, 1e-12);
+//! ```
+
+use crate::arrays::InfiniteArray;
+use num_traits::Float;
+use std::fmt;
+
+/// Where two arrays first differ, see [`first_mismatch`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mismatch<T> {
+    /// Element `index` differs
+    Value { index: usize, left: T, right: T },
+    /// One array ends within the compared prefix and the other does not end
+    /// at the same index; a length is `None` if it is unknown or infinite
+    Length { left: Option<usize>, right: Option<usize> },
+}
+
+impl<T: fmt::Debug> fmt::Display for Mismatch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::Value { index, left, right } => {
+                write!(f, "arrays differ at index {}\n  left: {:?}\n right: {:?}", index, left, right)
+            }
+            Mismatch::Length { left, right } => {
+                write!(f, "arrays differ in length\n  left: {:?}\n right: {:?}", left, right)
+            }
+        }
+    }
+}
+
+/// A [`Mismatch::Length`] if `a` and `b` do not end at the same index
+/// within the first `n` elements
+fn length_mismatch<T, A, B>(a: &A, b: &B, n: usize) -> Option<Mismatch<T>>
+where
+    A: InfiniteArray<T> + ?Sized,
+    B: InfiniteArray<T> + ?Sized,
+{
+    let within = |len: Option<usize>| len.map_or(n, |len| len.min(n));
+    if within(a.len()) == within(b.len()) {
+        None
+    } else {
+        Some(Mismatch::Length {
+            left: a.len(),
+            right: b.len(),
+        })
+    }
+}
+
+/// Whether the first `n` elements of `a` and `b` are equal
+pub fn eq_within<T, A, B>(a: &A, b: &B, n: usize) -> bool
+where
+    T: PartialEq,
+    A: InfiniteArray<T> + ?Sized,
+    B: InfiniteArray<T> + ?Sized,
+{
+    first_mismatch(a, b, n).is_none()
+}
+
+/// How the first `n` elements of `a` and `b` first differ: in length if
+/// one of them ends first, otherwise at the first differing index
+pub fn first_mismatch<T, A, B>(a: &A, b: &B, n: usize) -> Option<Mismatch<T>>
+where
+    T: PartialEq,
+    A: InfiniteArray<T> + ?Sized,
+    B: InfiniteArray<T> + ?Sized,
+{
+    length_mismatch(a, b, n).or_else(|| {
+        a.iter()
+            .zip(b.iter())
+            .take(n)
+            .enumerate()
+            .find(|(_, (x, y))| x != y)
+            .map(|(index, (left, right))| Mismatch::Value { index, left, right })
+    })
+}
+
+/// The same as [`first_mismatch`], with elements differing if they are more
+/// than `tol` apart or either is NaN
+pub fn first_mismatch_approx<T, A, B>(a: &A, b: &B, n: usize, tol: T) -> Option<Mismatch<T>>
+where
+    T: Float,
+    A: InfiniteArray<T> + ?Sized,
+    B: InfiniteArray<T> + ?Sized,
+{
+    length_mismatch(a, b, n).or_else(|| {
+        a.iter()
+            .zip(b.iter())
+            .take(n)
+            .enumerate()
+            .find(|(_, (x, y))| x.is_nan() || y.is_nan() || (*x - *y).abs() > tol)
+            .map(|(index, (left, right))| Mismatch::Value { index, left, right })
+    })
+}
+
+/// Assert that the first `n` elements of two arrays are equal
+#[macro_export]
+macro_rules! assert_prefix_eq {
+    ($a:expr, $b:expr, $n:expr $(,)?) => {
+        if let Some(mismatch) = $crate::testing::first_mismatch(&$a, &$b, $n) {
+            panic!("{}", mismatch);
+        }
+    };
+}
+
+/// Assert that the first `n` elements of two float arrays differ by at most
+/// `tol`
+#[macro_export]
+macro_rules! assert_prefix_approx_eq {
+    ($a:expr, $b:expr, $n:expr, $tol:expr $(,)?) => {{
+        let tol = $tol;
+        match $crate::testing::first_mismatch_approx(&$a, &$b, $n, tol) {
+            Some($crate::testing::Mismatch::Value { index, left, right }) => panic!(
+                "arrays differ by more than {:?} at index {}\n  left: {:?}\n right: {:?}",
+                tol, index, left, right
+            ),
+            Some(mismatch) => panic!("{}", mismatch),
+            None => {}
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::{Fill, InfiniteArrayFromFn, PaddedArray};
+    use crate::streamed::IteratorArray;
+
+    #[test]
+    fn test_prefix_comparison() {
+        let padded = PaddedArray::new(vec![1, 1, 2], 1);
+        assert!(eq_within(&padded, &Fill::new(1), 2));
+        assert!(!eq_within(&padded, &Fill::new(1), 3));
+        assert_eq!(
+            first_mismatch(&padded, &Fill::new(1), 10),
+            Some(Mismatch::Value { index: 2, left: 2, right: 1 })
+        );
+        assert_prefix_eq!(padded, PaddedArray::new(vec![1, 1, 2, 1], 1), 50);
+        let nan = InfiniteArrayFromFn::new(|i| if i == 3 { f64::NAN } else { 0.0 });
+        assert!(matches!(first_mismatch_approx(&nan, &Fill::new(0.0), 10, 1.0), Some(Mismatch::Value { index: 3, .. })));
+    }
+
+    #[test]
+    fn test_lengths_are_compared() {
+        let short = IteratorArray::new(vec![1, 2]);
+        let long = IteratorArray::new(vec![1, 2, 3]);
+        assert_eq!(first_mismatch(&short, &long, 2), None);
+        let mismatch = first_mismatch(&short, &long, 10).unwrap();
+        assert_eq!(mismatch, Mismatch::Length { left: Some(2), right: Some(3) });
+        assert_eq!(mismatch.to_string(), "arrays differ in length\n  left: Some(2)\n right: Some(3)");
+        assert!(!eq_within(&short, &Fill::new(1), 5));
+        assert!(eq_within(&short, &PaddedArray::new(vec![1, 2], 0), 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "arrays differ by more than 0.001 at index 1")]
+    fn test_approx_assertion_reports_index() {
+        let close = PaddedArray::new(vec![1.0005, 1.01], 1.0);
+        assert_prefix_approx_eq!(close, Fill::new(1.0), 5, 1e-3);
+    }
+}